@@ -414,6 +414,105 @@ benches! {
     bench6_1m(data::ENC_1048576, data::DEC_1048576)
 }
 
+/// The fixed-size fast paths: 20-byte addresses, 32-byte hashes and 64-byte signatures.
+mod fixed {
+    use super::*;
+
+    macro_rules! fixed {
+        ($($name:ident($n:literal))*) => {
+            mod format {
+                use super::*;
+
+                $(
+                    #[divan::bench]
+                    fn $name(b: Bencher) {
+                        let input: &[u8; $n] = data::ENC_256[..$n].try_into().unwrap();
+                        let mut buffer = const_hex::Buffer::<$n>::new();
+                        b.bench_local(|| {
+                            black_box(buffer.format(black_box(input)));
+                        });
+                    }
+                )*
+            }
+
+            mod decode_to_array {
+                use super::*;
+
+                $(
+                    #[divan::bench]
+                    fn $name(b: Bencher) {
+                        let input = &data::DEC_256[..$n * 2];
+                        b.bench(|| {
+                            const_hex::decode_to_array::<_, $n>(black_box(input))
+                        });
+                    }
+                )*
+            }
+        };
+    }
+
+    fixed! {
+        bench1_20b(20)
+        bench2_32b(32)
+        bench3_64b(64)
+    }
+}
+
 fn main() {
     divan::main();
 }
+
+#[cfg(feature = "alloc")]
+mod batch {
+    use super::*;
+
+    const ITEMS: usize = 1000;
+
+    fn hashes() -> Vec<[u8; 32]> {
+        (0..ITEMS)
+            .map(|i| core::array::from_fn(|j| data::ENC_256[(i + j) % 256]))
+            .collect()
+    }
+
+    #[divan::bench]
+    fn encode_each(b: Bencher) {
+        let hashes = hashes();
+        b.bench(|| {
+            black_box(&hashes)
+                .iter()
+                .map(const_hex::encode)
+                .collect::<Vec<_>>()
+        });
+    }
+
+    #[divan::bench]
+    fn encode(b: Bencher) {
+        let hashes = hashes();
+        b.bench(|| const_hex::batch::encode(black_box(&hashes)));
+    }
+
+    #[divan::bench]
+    fn encode_buffers(b: Bencher) {
+        let hashes = hashes();
+        b.bench(|| const_hex::batch::encode_buffers::<32, false>(black_box(&hashes)));
+    }
+
+    #[divan::bench]
+    fn decode_each(b: Bencher) {
+        let encoded = const_hex::batch::encode(&hashes());
+        let inputs = encoded.iter().collect::<Vec<_>>();
+        b.bench(|| {
+            black_box(&inputs)
+                .iter()
+                .map(const_hex::decode_to_array::<_, 32>)
+                .collect::<Result<Vec<_>, _>>()
+        });
+    }
+
+    #[divan::bench]
+    fn decode(b: Bencher) {
+        let encoded = const_hex::batch::encode(&hashes());
+        let inputs = encoded.iter().collect::<Vec<_>>();
+        b.bench(|| const_hex::batch::decode::<32, _>(black_box(&inputs)));
+    }
+}
@@ -0,0 +1,79 @@
+/*
+ * C interface to the const-hex crate.
+ *
+ * Build the library with the `ffi` feature, for example:
+ *
+ *     cargo rustc --release --lib --features ffi --crate-type staticlib
+ *
+ * and link against `target/release/libconst_hex.a`.
+ *
+ * All buffers are passed as a pointer and an explicit length in bytes, and strings are neither
+ * read nor written with a NUL terminator. A pointer may be null if its length is 0.
+ *
+ * This file mirrors `src/ffi.rs` and must be kept in sync with it.
+ */
+
+#ifndef CONST_HEX_H
+#define CONST_HEX_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* The result of a function, mirroring `FromHexError`. */
+typedef enum const_hex_status {
+    /* The function succeeded. */
+    CONST_HEX_OK = 0,
+    /* An invalid character was found. */
+    CONST_HEX_INVALID_CHARACTER = 1,
+    /* The input has an odd number of hex digits. */
+    CONST_HEX_ODD_LENGTH = 2,
+    /* The output buffer has the wrong length for the input. */
+    CONST_HEX_INVALID_LENGTH = 3,
+} const_hex_status;
+
+/*
+ * Encodes `input_len` bytes from `input` as hex into `output`, which must be exactly
+ * `2 * input_len` bytes long. Writes upper case letters if `upper` is true. Does not write a
+ * prefix or a NUL terminator.
+ *
+ * Returns `CONST_HEX_INVALID_LENGTH` if `output_len` is wrong, in which case nothing is written.
+ */
+const_hex_status const_hex_encode(const uint8_t *input, size_t input_len, char *output,
+                                  size_t output_len, bool upper);
+
+/*
+ * Decodes `input_len` hex characters from `input` into `output`, which must be exactly
+ * `output_len` bytes long. An optional `0x` prefix is ignored.
+ *
+ * On `CONST_HEX_INVALID_CHARACTER`, the position of the first invalid character in `input` is
+ * written to `error_index`, unless it is null. The contents of `output` are unspecified if
+ * decoding fails.
+ */
+const_hex_status const_hex_decode(const char *input, size_t input_len, uint8_t *output,
+                                  size_t output_len, size_t *error_index);
+
+/*
+ * Checks that `input_len` characters from `input` are valid hex. An optional `0x` prefix is
+ * ignored.
+ *
+ * On `CONST_HEX_INVALID_CHARACTER`, the position of the first invalid character in `input` is
+ * written to `error_index`, unless it is null.
+ */
+const_hex_status const_hex_check(const char *input, size_t input_len, size_t *error_index);
+
+/*
+ * Returns a static, NUL-terminated description of `status`, such as "odd number of digits".
+ * Unknown values return "unknown status".
+ */
+const char *const_hex_decode_error(int status);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* CONST_HEX_H */
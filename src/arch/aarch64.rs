@@ -1,11 +1,29 @@
 #![allow(unsafe_op_in_unsafe_fn)]
 
-use super::generic;
-use crate::{get_chars_table, Output};
+use super::{generic, Kernels};
+use crate::{get_chars_table, Backend, RawOutput};
 use core::arch::aarch64::*;
 
 pub(crate) const USE_CHECK_FN: bool = false;
 
+pub(crate) static KERNELS: &[Kernels] = &[
+    Kernels {
+        backend: Backend::Neon,
+        is_available: has_neon,
+        encode: [encode_neon_slice::<false>, encode_neon_slice::<true>],
+        encode_reversed: [encode_reversed_neon::<false>, encode_reversed_neon::<true>],
+        check: check_neon,
+        decode_checked: decode_checked_neon,
+        decode_unchecked: decode_unchecked_neon,
+        unpack: unpack_neon,
+        pack: pack_neon,
+        encode_utf16: [encode_utf16_neon::<false>, encode_utf16_neon::<true>],
+        check_utf16: check_utf16_neon,
+        decode_utf16_checked: decode_utf16_checked_neon,
+    },
+    generic::GENERIC,
+];
+
 cfg_if::cfg_if! {
     if #[cfg(feature = "std")] {
         #[inline(always)]
@@ -21,59 +39,84 @@ cfg_if::cfg_if! {
 }
 
 #[inline]
-pub(crate) unsafe fn encode<const UPPER: bool>(input: &[u8], output: impl Output) {
-    if !has_neon() {
+pub(crate) unsafe fn encode<const UPPER: bool>(input: &[u8], output: impl RawOutput) {
+    if super::kernels().backend != Backend::Neon {
         return generic::encode::<UPPER>(input, output);
     }
     encode_neon::<UPPER>(input, output);
 }
 
 #[target_feature(enable = "neon")]
-pub(crate) unsafe fn encode_neon<const UPPER: bool>(input: &[u8], output: impl Output) {
+unsafe fn encode_neon_slice<const UPPER: bool>(input: &[u8], output: &mut [u8]) {
+    encode_neon::<UPPER>(input, output);
+}
+
+#[inline]
+#[target_feature(enable = "neon")]
+unsafe fn encode_neon<const UPPER: bool>(input: &[u8], output: impl RawOutput) {
     // Load table.
     let hex_table = vld1q_u8(get_chars_table::<UPPER>().as_ptr());
 
     generic::encode_unaligned_chunks::<UPPER, _, _>(input, output, |chunk: uint8x16_t| {
-        // Load input bytes and mask to nibbles.
-        let mut lo = vandq_u8(chunk, vdupq_n_u8(0x0F));
-        let mut hi = vshrq_n_u8(chunk, 4);
+        encode_chunk_neon(hex_table, chunk)
+    });
+}
 
-        // Lookup the corresponding ASCII hex digit for each nibble.
-        lo = vqtbl1q_u8(hex_table, lo);
-        hi = vqtbl1q_u8(hex_table, hi);
+/// Reversed encode: only the bytes within each 64-bit half are reversed. The swap of the halves is
+/// folded into the order in which the two zipped outputs are written.
+#[target_feature(enable = "neon")]
+unsafe fn encode_reversed_neon<const UPPER: bool>(input: &[u8], output: &mut [u8]) {
+    let hex_table = vld1q_u8(get_chars_table::<UPPER>().as_ptr());
 
-        // Interleave the nibbles ([hi[0], lo[0], hi[1], lo[1], ...]).
-        vzipq_u8(hi, lo)
-    });
+    generic::encode_reversed_unaligned_chunks(
+        input,
+        output,
+        |chunk: uint8x16_t| {
+            let uint8x16x2_t(lo, hi) = encode_chunk_neon(hex_table, vrev64q_u8(chunk));
+            uint8x16x2_t(hi, lo)
+        },
+        generic::encode_reversed::<UPPER>,
+    );
 }
 
 #[inline]
-pub(crate) fn check(input: &[u8]) -> bool {
-    if !has_neon() {
-        return generic::check(input);
-    }
-    unsafe { check_neon(input) }
+#[target_feature(enable = "neon")]
+unsafe fn encode_chunk_neon(hex_table: uint8x16_t, chunk: uint8x16_t) -> uint8x16x2_t {
+    // Load input bytes and mask to nibbles.
+    let mut lo = vandq_u8(chunk, vdupq_n_u8(0x0F));
+    let mut hi = vshrq_n_u8(chunk, 4);
+
+    // Lookup the corresponding ASCII hex digit for each nibble.
+    lo = vqtbl1q_u8(hex_table, lo);
+    hi = vqtbl1q_u8(hex_table, hi);
+
+    // Interleave the nibbles ([hi[0], lo[0], hi[1], lo[1], ...]).
+    vzipq_u8(hi, lo)
 }
 
 #[target_feature(enable = "neon")]
-pub(crate) unsafe fn check_neon(input: &[u8]) -> bool {
-    generic::check_unaligned_chunks(input, |chunk: uint8x16_t| {
-        let ge0 = vcgeq_u8(chunk, vdupq_n_u8(b'0'));
-        let le9 = vcleq_u8(chunk, vdupq_n_u8(b'9'));
-        let valid_digit = vandq_u8(ge0, le9);
+unsafe fn check_neon(input: &[u8]) -> bool {
+    generic::check_unaligned_chunks(input, |chunk| check_chunk_neon(chunk))
+}
 
-        let geua = vcgeq_u8(chunk, vdupq_n_u8(b'A'));
-        let leuf = vcleq_u8(chunk, vdupq_n_u8(b'F'));
-        let valid_upper = vandq_u8(geua, leuf);
+#[inline]
+#[target_feature(enable = "neon")]
+unsafe fn check_chunk_neon(chunk: uint8x16_t) -> bool {
+    let ge0 = vcgeq_u8(chunk, vdupq_n_u8(b'0'));
+    let le9 = vcleq_u8(chunk, vdupq_n_u8(b'9'));
+    let valid_digit = vandq_u8(ge0, le9);
 
-        let gela = vcgeq_u8(chunk, vdupq_n_u8(b'a'));
-        let lelf = vcleq_u8(chunk, vdupq_n_u8(b'f'));
-        let valid_lower = vandq_u8(gela, lelf);
+    let geua = vcgeq_u8(chunk, vdupq_n_u8(b'A'));
+    let leuf = vcleq_u8(chunk, vdupq_n_u8(b'F'));
+    let valid_upper = vandq_u8(geua, leuf);
 
-        let valid_letter = vorrq_u8(valid_lower, valid_upper);
-        let valid_mask = vorrq_u8(valid_digit, valid_letter);
-        vminvq_u8(valid_mask) == 0xFF
-    })
+    let gela = vcgeq_u8(chunk, vdupq_n_u8(b'a'));
+    let lelf = vcleq_u8(chunk, vdupq_n_u8(b'f'));
+    let valid_lower = vandq_u8(gela, lelf);
+
+    let valid_letter = vorrq_u8(valid_lower, valid_upper);
+    let valid_mask = vorrq_u8(valid_digit, valid_letter);
+    vminvq_u8(valid_mask) == 0xFF
 }
 
 /// Single-pass hex decode with validation using Muła & Langdale's Algorithm #3.
@@ -84,18 +127,22 @@ pub(crate) unsafe fn check_neon(input: &[u8]) -> bool {
 /// - Nibble pairs are merged with `vuzpq_u8` deinterleave + `(hi << 4) | lo`.
 ///
 /// Based on: <http://0x80.pl/notesen/2022-01-17-validating-hex-parse.html>
-#[inline]
-pub(crate) unsafe fn decode_checked(input: &[u8], output: &mut [u8]) -> bool {
-    if cfg!(miri) || !has_neon() {
+#[target_feature(enable = "neon")]
+unsafe fn decode_checked_neon(input: &[u8], output: &mut [u8]) -> bool {
+    debug_assert_eq!(output.len(), input.len() / 2);
+    if cfg!(miri) {
         return generic::decode_checked(input, output);
     }
-    decode_checked_neon(input, output)
+
+    generic::decode_checked_unaligned_chunks(input, output, |chunk| {
+        decode_chunk_checked_neon(chunk)
+    })
 }
 
+/// Decodes a 32-character chunk, see [`decode_checked_neon`].
+#[inline]
 #[target_feature(enable = "neon")]
-unsafe fn decode_checked_neon(input: &[u8], output: &mut [u8]) -> bool {
-    debug_assert_eq!(output.len(), input.len() / 2);
-
+unsafe fn decode_chunk_checked_neon([v0, v1]: [uint8x16_t; 2]) -> Option<uint8x16_t> {
     let add_c6 = vdupq_n_u8(0xC6); // 0xFF - b'9'
     let six = vdupq_n_u8(6);
     let f0 = vdupq_n_u8(0xF0);
@@ -104,41 +151,34 @@ unsafe fn decode_checked_neon(input: &[u8], output: &mut [u8]) -> bool {
     let ten = vdupq_n_u8(10);
     let check_bias = vdupq_n_u8(112); // 127 - 15
 
-    generic::decode_checked_unaligned_chunks(input, output, |[v0, v1]: [uint8x16_t; 2]| {
-        // Digits '0'..'9' → 0..9, others > 15.
-        let d0 = vsubq_u8(vqsubq_u8(vaddq_u8(v0, add_c6), six), f0);
-        let d1 = vsubq_u8(vqsubq_u8(vaddq_u8(v1, add_c6), six), f0);
-
-        // Letters 'A'..'F'/'a'..'f' → 10..15, others > 15.
-        let a0 = vqaddq_u8(vsubq_u8(vandq_u8(v0, df), big_a), ten);
-        let a1 = vqaddq_u8(vsubq_u8(vandq_u8(v1, df), big_a), ten);
+    // Digits '0'..'9' → 0..9, others > 15.
+    let d0 = vsubq_u8(vqsubq_u8(vaddq_u8(v0, add_c6), six), f0);
+    let d1 = vsubq_u8(vqsubq_u8(vaddq_u8(v1, add_c6), six), f0);
 
-        // Valid nibble wins (0..15), invalid stays > 15.
-        let n0 = vminq_u8(d0, a0);
-        let n1 = vminq_u8(d1, a1);
+    // Letters 'A'..'F'/'a'..'f' → 10..15, others > 15.
+    let a0 = vqaddq_u8(vsubq_u8(vandq_u8(v0, df), big_a), ten);
+    let a1 = vqaddq_u8(vsubq_u8(vandq_u8(v1, df), big_a), ten);
 
-        // Validate: saturating add sets MSB if nibble > 15.
-        let c = vorrq_u8(vqaddq_u8(n0, check_bias), vqaddq_u8(n1, check_bias));
-        if vmaxvq_u8(c) > 0x7F {
-            return None;
-        }
+    // Valid nibble wins (0..15), invalid stays > 15.
+    let n0 = vminq_u8(d0, a0);
+    let n1 = vminq_u8(d1, a1);
 
-        // Merge nibble pairs.
-        let uz = vuzpq_u8(n0, n1);
-        Some(vorrq_u8(vshlq_n_u8(uz.0, 4), uz.1))
-    })
-}
-
-#[inline]
-pub(crate) unsafe fn decode_unchecked(input: &[u8], output: &mut [u8]) {
-    if cfg!(miri) || !has_neon() {
-        return generic::decode_unchecked(input, output);
+    // Validate: saturating add sets MSB if nibble > 15.
+    let c = vorrq_u8(vqaddq_u8(n0, check_bias), vqaddq_u8(n1, check_bias));
+    if vmaxvq_u8(c) > 0x7F {
+        return None;
     }
-    decode_unchecked_neon(input, output);
+
+    // Merge nibble pairs.
+    let uz = vuzpq_u8(n0, n1);
+    Some(vorrq_u8(vshlq_n_u8(uz.0, 4), uz.1))
 }
 
 #[target_feature(enable = "neon")]
 unsafe fn decode_unchecked_neon(input: &[u8], output: &mut [u8]) {
+    if cfg!(miri) {
+        return generic::decode_unchecked(input, output);
+    }
     generic::decode_unchecked_unaligned_chunks(input, output, |[v0, v1]: [uint8x16_t; 2]| {
         let n0 = unhex_neon(v0);
         let n1 = unhex_neon(v1);
@@ -156,3 +196,122 @@ unsafe fn unhex_neon(x: uint8x16_t) -> uint8x16_t {
     let mul9 = vmulq_u8(sr6, vdupq_n_u8(9));
     vaddq_u8(mul9, low)
 }
+
+#[target_feature(enable = "neon")]
+unsafe fn unpack_neon(input: &[u8], output: &mut [u8]) {
+    if cfg!(miri) {
+        return generic::unpack(input, output);
+    }
+    generic::unpack_unaligned_chunks(input, output, |chunk: uint8x16_t| {
+        let lo = vandq_u8(chunk, vdupq_n_u8(0x0F));
+        let hi = vshrq_n_u8(chunk, 4);
+        vzipq_u8(hi, lo)
+    });
+}
+
+#[target_feature(enable = "neon")]
+unsafe fn pack_neon(input: &[u8], output: &mut [u8]) -> bool {
+    if cfg!(miri) {
+        return generic::pack(input, output);
+    }
+    generic::pack_unaligned_chunks(input, output, |[n0, n1]: [uint8x16_t; 2]| {
+        if vmaxvq_u8(vorrq_u8(n0, n1)) > 0x0F {
+            return None;
+        }
+        let uz = vuzpq_u8(n0, n1);
+        Some(vorrq_u8(vshlq_n_u8(uz.0, 4), uz.1))
+    })
+}
+
+#[target_feature(enable = "neon")]
+unsafe fn encode_utf16_neon<const UPPER: bool>(input: &[u8], output: &mut [u16]) {
+    if cfg!(miri) {
+        return generic::encode_utf16::<UPPER>(input, output);
+    }
+    let hex_table = vld1q_u8(get_chars_table::<UPPER>().as_ptr());
+    generic::encode_utf16_unaligned_chunks(
+        input,
+        output,
+        |chunk| {
+            let uint8x16x2_t(a, b) = encode_chunk_neon(hex_table, chunk);
+            [
+                vmovl_u8(vget_low_u8(a)),
+                vmovl_high_u8(a),
+                vmovl_u8(vget_low_u8(b)),
+                vmovl_high_u8(b),
+            ]
+        },
+        generic::encode_utf16::<UPPER>,
+    );
+}
+
+#[target_feature(enable = "neon")]
+unsafe fn check_utf16_neon(input: &[u16]) -> bool {
+    if cfg!(miri) {
+        return generic::check_utf16(input);
+    }
+    generic::check_utf16_unaligned_chunks(
+        input,
+        |[v0, v1]: [uint16x8_t; 2]| check_chunk_neon(narrow_neon(v0, v1)),
+        generic::check_utf16,
+    )
+}
+
+#[target_feature(enable = "neon")]
+unsafe fn decode_utf16_checked_neon(input: &[u16], output: &mut [u8]) -> bool {
+    if cfg!(miri) {
+        return generic::decode_utf16_checked(input, output);
+    }
+    generic::decode_utf16_checked_unaligned_chunks(
+        input,
+        output,
+        |[v0, v1, v2, v3]: [uint16x8_t; 4]| {
+            decode_chunk_checked_neon([narrow_neon(v0, v1), narrow_neon(v2, v3)])
+        },
+        generic::decode_utf16_checked,
+    )
+}
+
+/// Narrows two vectors of code units to bytes with unsigned saturation.
+#[inline]
+#[target_feature(enable = "neon")]
+unsafe fn narrow_neon(lo: uint16x8_t, hi: uint16x8_t) -> uint8x16_t {
+    vqmovn_high_u16(vqmovn_u16(lo), hi)
+}
+
+/// Fixed-size encode: one NEON register round-trip per 16 bytes, without length checks or
+/// remainder handling.
+#[inline]
+pub(crate) unsafe fn encode_fixed<const N: usize, const UPPER: bool>(
+    input: &[u8; N],
+    output: &mut [u8],
+) -> bool {
+    if super::kernels().backend != Backend::Neon || cfg!(miri) {
+        return false;
+    }
+    encode_fixed_neon::<N, UPPER>(input, output);
+    true
+}
+
+#[target_feature(enable = "neon")]
+unsafe fn encode_fixed_neon<const N: usize, const UPPER: bool>(input: &[u8; N], output: &mut [u8]) {
+    let hex_table = vld1q_u8(get_chars_table::<UPPER>().as_ptr());
+    generic::encode_fixed_chunks(input, output, |chunk| encode_chunk_neon(hex_table, chunk));
+}
+
+/// Fixed-size decode, see [`encode_fixed`].
+#[inline]
+pub(crate) unsafe fn decode_fixed<const N: usize>(
+    input: &[u8],
+    output: &mut [u8; N],
+) -> Option<bool> {
+    if super::kernels().backend != Backend::Neon || cfg!(miri) {
+        return None;
+    }
+    Some(decode_fixed_neon(input, output))
+}
+
+#[target_feature(enable = "neon")]
+unsafe fn decode_fixed_neon<const N: usize>(input: &[u8], output: &mut [u8; N]) -> bool {
+    generic::decode_fixed_chunks(input, output, |chunk| decode_chunk_checked_neon(chunk))
+}
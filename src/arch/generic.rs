@@ -1,4 +1,5 @@
-use crate::{byte2hex, Output, HEX_DECODE_LUT, NIL};
+use super::Kernels;
+use crate::{byte2hex, Backend, RawOutput, HEX_DECODE_LUT, NIL};
 use core::mem::size_of;
 
 /// Set to `true` to use `check` + `decode_unchecked` for decoding. Otherwise uses `decode_checked`.
@@ -7,12 +8,39 @@ use core::mem::size_of;
 #[allow(dead_code)]
 pub(crate) const USE_CHECK_FN: bool = false;
 
+/// The generic kernels, always available.
+pub(crate) const GENERIC: Kernels = Kernels {
+    backend: Backend::Generic,
+    is_available: || true,
+    encode: [encode_slice::<false>, encode_slice::<true>],
+    encode_reversed: [encode_reversed::<false>, encode_reversed::<true>],
+    check,
+    decode_checked,
+    decode_unchecked: decode_unchecked_slice,
+    unpack,
+    pack,
+    encode_utf16: [encode_utf16::<false>, encode_utf16::<true>],
+    check_utf16,
+    decode_utf16_checked,
+};
+
+#[allow(dead_code)]
+pub(crate) static KERNELS: &[Kernels] = &[GENERIC];
+
+unsafe fn encode_slice<const UPPER: bool>(input: &[u8], output: &mut [u8]) {
+    unsafe { encode::<UPPER>(input, output) }
+}
+
+unsafe fn decode_unchecked_slice(input: &[u8], output: &mut [u8]) {
+    unsafe { decode_unchecked(input, output) }
+}
+
 /// Default encoding function.
 ///
 /// # Safety
 ///
 /// `output` must be at least `2 * input.len()` bytes long.
-pub(crate) unsafe fn encode<const UPPER: bool>(input: &[u8], mut output: impl Output) {
+pub(crate) unsafe fn encode<const UPPER: bool>(input: &[u8], mut output: impl RawOutput) {
     for &byte in input {
         let (high, low) = byte2hex::<UPPER>(byte);
         output.write_byte(high);
@@ -20,6 +48,21 @@ pub(crate) unsafe fn encode<const UPPER: bool>(input: &[u8], mut output: impl Ou
     }
 }
 
+/// Const-stable encoding function, used by [`const_encode_to_slice`](crate::const_encode_to_slice).
+///
+/// Assumes `output.len() == input.len() * 2`.
+#[cfg(feature = "const-mut")]
+pub(crate) const fn const_encode<const UPPER: bool>(input: &[u8], output: &mut [u8]) {
+    debug_assert!(output.len() == input.len() * 2);
+    let mut i = 0;
+    while i < input.len() {
+        let (high, low) = byte2hex::<UPPER>(input[i]);
+        output[i * 2] = high;
+        output[i * 2 + 1] = low;
+        i += 1;
+    }
+}
+
 /// Encodes unaligned chunks of `T` in `input` to `output` using `encode_chunk`.
 ///
 /// The remainder is encoded using the generic [`encode`].
@@ -27,7 +70,7 @@ pub(crate) unsafe fn encode<const UPPER: bool>(input: &[u8], mut output: impl Ou
 #[allow(dead_code)]
 pub(crate) unsafe fn encode_unaligned_chunks<const UPPER: bool, T: Copy, U: Copy>(
     input: &[u8],
-    mut output: impl Output,
+    mut output: impl RawOutput,
     mut encode_chunk: impl FnMut(T) -> U,
 ) {
     debug_assert_eq!(size_of::<U>(), size_of::<T>() * 2);
@@ -48,7 +91,7 @@ pub(crate) unsafe fn encode_unaligned_chunks_with<
     const UPPER: bool,
     T: Copy,
     U: Copy,
-    O: Output,
+    O: RawOutput,
 >(
     input: &[u8],
     mut output: O,
@@ -70,7 +113,7 @@ pub(crate) unsafe fn encode_unaligned_chunks_with<
 #[allow(dead_code)]
 pub(crate) unsafe fn encode_one_unaligned_chunk<const UPPER: bool, T: Copy, U: Copy>(
     input: &[u8],
-    mut output: impl Output,
+    mut output: impl RawOutput,
     encode_chunk: impl FnOnce(T) -> U,
 ) {
     debug_assert_eq!(size_of::<U>(), size_of::<T>() * 2);
@@ -85,6 +128,158 @@ pub(crate) unsafe fn encode_one_unaligned_chunk<const UPPER: bool, T: Copy, U: C
     }
 }
 
+/// Default reversed encoding function.
+///
+/// Assumes `output.len() == input.len() * 2`.
+pub(crate) fn encode_reversed<const UPPER: bool>(input: &[u8], output: &mut [u8]) {
+    debug_assert_eq!(output.len(), input.len() * 2);
+    for (&byte, out) in input.iter().rev().zip(output.chunks_exact_mut(2)) {
+        let (high, low) = byte2hex::<UPPER>(byte);
+        out[0] = high;
+        out[1] = low;
+    }
+}
+
+/// Encodes unaligned chunks of `T` in `input` to `output` in reverse order, starting from the end
+/// of `input`, using `encode_chunk`, which must reverse the bytes within each chunk.
+///
+/// The remaining bytes at the start of `input` are passed to `encode_remainder`.
+#[inline]
+#[allow(dead_code)]
+pub(crate) unsafe fn encode_reversed_unaligned_chunks<T: Copy, U: Copy>(
+    input: &[u8],
+    mut output: &mut [u8],
+    mut encode_chunk: impl FnMut(T) -> U,
+    encode_remainder: impl FnOnce(&[u8], &mut [u8]),
+) {
+    debug_assert_eq!(size_of::<U>(), size_of::<T>() * 2);
+    let chunks = input.rchunks_exact(size_of::<T>());
+    let remainder = chunks.remainder();
+    for chunk in chunks {
+        let chunk = unsafe { chunk.as_ptr().cast::<T>().read_unaligned() };
+        output.write(as_bytes(&encode_chunk(chunk)));
+    }
+    encode_remainder(remainder, output);
+}
+
+/// The largest input size with a fixed-size fast path.
+pub(crate) const FIXED_MAX: usize = 64;
+
+/// Default fixed-size encoding function: there is no fast path.
+#[inline(always)]
+#[allow(dead_code)]
+pub(crate) unsafe fn encode_fixed<const N: usize, const UPPER: bool>(
+    _input: &[u8; N],
+    _output: &mut [u8],
+) -> bool {
+    false
+}
+
+/// Encodes a fixed-size `input` to `output` in whole chunks of `T` using `encode_chunk`.
+///
+/// If `N` is not a multiple of the chunk size, the input is zero-padded into a temporary buffer
+/// so that there is no remainder to handle.
+#[inline(always)]
+#[allow(dead_code)]
+pub(crate) unsafe fn encode_fixed_chunks<const N: usize, T: Copy, U: Copy>(
+    input: &[u8; N],
+    output: &mut [u8],
+    mut encode_chunk: impl FnMut(T) -> U,
+) {
+    debug_assert!(N <= FIXED_MAX);
+    debug_assert_eq!(output.len(), N * 2);
+    debug_assert_eq!(size_of::<U>(), size_of::<T>() * 2);
+    let size = size_of::<T>();
+    let mut encode_chunks = |input: *const u8, output: *mut u8| {
+        for i in 0..(N + size - 1) / size {
+            let chunk = unsafe { input.add(i * size).cast::<T>().read_unaligned() };
+            let encoded = encode_chunk(chunk);
+            unsafe {
+                output
+                    .add(i * size * 2)
+                    .cast::<U>()
+                    .write_unaligned(encoded)
+            };
+        }
+    };
+    if N % size == 0 {
+        encode_chunks(input.as_ptr(), output.as_mut_ptr());
+    } else {
+        // Copy with constant lengths, so that the copies get inlined.
+        let mut padded = [0u8; FIXED_MAX];
+        unsafe {
+            padded
+                .as_mut_ptr()
+                .copy_from_nonoverlapping(input.as_ptr(), N)
+        };
+        let mut encoded = [0u8; FIXED_MAX * 2];
+        encode_chunks(padded.as_ptr(), encoded.as_mut_ptr());
+        unsafe {
+            output
+                .as_mut_ptr()
+                .copy_from_nonoverlapping(encoded.as_ptr(), N * 2)
+        };
+    }
+}
+
+/// Default fixed-size decoding function: there is no fast path.
+#[inline(always)]
+#[allow(dead_code)]
+pub(crate) unsafe fn decode_fixed<const N: usize>(
+    _input: &[u8],
+    _output: &mut [u8; N],
+) -> Option<bool> {
+    None
+}
+
+/// Checked-decodes `input` to a fixed-size `output` in whole chunks of `U` using `decode_chunk`.
+///
+/// If `N` is not a multiple of the decoded chunk size, the input is padded with `'0'` into a
+/// temporary buffer so that there is no remainder to handle.
+#[inline(always)]
+#[allow(dead_code)]
+pub(crate) unsafe fn decode_fixed_chunks<const N: usize, T: Copy, U: Copy>(
+    input: &[u8],
+    output: &mut [u8; N],
+    mut decode_chunk: impl FnMut(U) -> Option<T>,
+) -> bool {
+    debug_assert!(N <= FIXED_MAX);
+    debug_assert_eq!(input.len(), N * 2);
+    debug_assert_eq!(size_of::<U>(), size_of::<T>() * 2);
+    let size = size_of::<T>();
+    let mut decode_chunks = |input: *const u8, output: *mut u8| {
+        for i in 0..(N + size - 1) / size {
+            let chunk = unsafe { input.add(i * size * 2).cast::<U>().read_unaligned() };
+            match decode_chunk(chunk) {
+                Some(decoded) => unsafe {
+                    output.add(i * size).cast::<T>().write_unaligned(decoded)
+                },
+                None => return false,
+            }
+        }
+        true
+    };
+    if N % size == 0 {
+        decode_chunks(input.as_ptr(), output.as_mut_ptr())
+    } else {
+        // Copy with constant lengths, so that the copies get inlined.
+        let mut padded = [b'0'; FIXED_MAX * 2];
+        unsafe {
+            padded
+                .as_mut_ptr()
+                .copy_from_nonoverlapping(input.as_ptr(), N * 2)
+        };
+        let mut decoded = [0u8; FIXED_MAX];
+        let valid = decode_chunks(padded.as_ptr(), decoded.as_mut_ptr());
+        unsafe {
+            output
+                .as_mut_ptr()
+                .copy_from_nonoverlapping(decoded.as_ptr(), N)
+        };
+        valid
+    }
+}
+
 /// Default check function.
 #[inline]
 pub(crate) const fn check(mut input: &[u8]) -> bool {
@@ -147,12 +342,32 @@ pub(crate) unsafe fn decode_checked(input: &[u8], output: &mut [u8]) -> bool {
     unsafe { decode_maybe_check::<true>(input, output) }
 }
 
+/// Const-stable checked decoding function, used by
+/// [`const_decode_to_slice`](crate::const_decode_to_slice).
+///
+/// Assumes `output.len() == input.len() / 2`.
+#[cfg(feature = "const-mut")]
+pub(crate) const fn const_decode_checked(input: &[u8], output: &mut [u8]) -> bool {
+    debug_assert!(output.len() == input.len() / 2);
+    let mut i = 0;
+    while i < output.len() {
+        let high = HEX_DECODE_LUT[input[i * 2] as usize];
+        let low = HEX_DECODE_LUT[input[i * 2 + 1] as usize];
+        if high == NIL || low == NIL {
+            return false;
+        }
+        output[i] = high << 4 | low;
+        i += 1;
+    }
+    true
+}
+
 /// Default unchecked decoding function.
 ///
 /// # Safety
 ///
 /// Assumes `output.len() == input.len() / 2` and that the input is valid hex.
-pub(crate) unsafe fn decode_unchecked(input: &[u8], output: impl Output) {
+pub(crate) unsafe fn decode_unchecked(input: &[u8], output: impl RawOutput) {
     #[allow(unused_braces)] // False positive on older rust versions.
     let success = unsafe { decode_maybe_check::<{ cfg!(debug_assertions) }>(input, output) };
     debug_assert!(success);
@@ -164,7 +379,7 @@ pub(crate) unsafe fn decode_unchecked(input: &[u8], output: impl Output) {
 ///
 /// Assumes `output.len() == input.len() / 2` and that the input is valid hex if `CHECK` is `true`.
 #[inline(always)]
-unsafe fn decode_maybe_check<const CHECK: bool>(input: &[u8], mut output: impl Output) -> bool {
+unsafe fn decode_maybe_check<const CHECK: bool>(input: &[u8], mut output: impl RawOutput) -> bool {
     let l = output.remaining().unwrap_or(input.len() / 2);
     debug_assert_eq!(l, input.len() / 2);
     let mut i = 0;
@@ -189,7 +404,7 @@ unsafe fn decode_maybe_check<const CHECK: bool>(input: &[u8], mut output: impl O
 #[allow(dead_code)]
 pub(crate) unsafe fn decode_unchecked_unaligned_chunks<T: Copy, U: Copy>(
     input: &[u8],
-    mut output: impl Output,
+    mut output: impl RawOutput,
     mut decode_chunk: impl FnMut(U) -> T,
 ) {
     debug_assert_eq!(size_of::<U>(), size_of::<T>() * 2);
@@ -207,7 +422,7 @@ pub(crate) unsafe fn decode_unchecked_unaligned_chunks<T: Copy, U: Copy>(
 #[allow(dead_code)]
 pub(crate) unsafe fn decode_checked_unaligned_chunks<T: Copy, U: Copy>(
     input: &[u8],
-    output: impl Output,
+    output: impl RawOutput,
     decode_chunk: impl FnMut(U) -> Option<T>,
 ) -> bool {
     decode_checked_unaligned_chunks_with(input, output, decode_chunk, |remainder, out| unsafe {
@@ -218,7 +433,7 @@ pub(crate) unsafe fn decode_checked_unaligned_chunks<T: Copy, U: Copy>(
 /// Like [`decode_checked_unaligned_chunks`], but with a custom remainder handler.
 #[inline]
 #[allow(dead_code)]
-pub(crate) unsafe fn decode_checked_unaligned_chunks_with<T: Copy, U: Copy, O: Output>(
+pub(crate) unsafe fn decode_checked_unaligned_chunks_with<T: Copy, U: Copy, O: RawOutput>(
     input: &[u8],
     mut output: O,
     mut decode_chunk: impl FnMut(U) -> Option<T>,
@@ -244,7 +459,7 @@ pub(crate) unsafe fn decode_checked_unaligned_chunks_with<T: Copy, U: Copy, O: O
 #[allow(dead_code)]
 pub(crate) unsafe fn decode_checked_one_unaligned_chunk<T: Copy, U: Copy>(
     input: &[u8],
-    mut output: impl Output,
+    mut output: impl RawOutput,
     decode_chunk: impl FnOnce(U) -> Option<T>,
 ) -> bool {
     debug_assert_eq!(size_of::<U>(), size_of::<T>() * 2);
@@ -264,6 +479,176 @@ pub(crate) unsafe fn decode_checked_one_unaligned_chunk<T: Copy, U: Copy>(
     }
 }
 
+/// Default nibble unpacking function.
+///
+/// Assumes `output.len() == input.len() * 2`.
+pub(crate) fn unpack(input: &[u8], output: &mut [u8]) {
+    debug_assert_eq!(output.len(), input.len() * 2);
+    for (&byte, out) in input.iter().zip(output.chunks_exact_mut(2)) {
+        out[0] = byte >> 4;
+        out[1] = byte & 0x0f;
+    }
+}
+
+/// Default nibble packing function. Returns `false` if any nibble is greater than `0xf`.
+///
+/// Assumes `output.len() == input.len() / 2`.
+pub(crate) fn pack(input: &[u8], output: &mut [u8]) -> bool {
+    debug_assert_eq!(output.len(), input.len() / 2);
+    for (pair, out) in input.chunks_exact(2).zip(output) {
+        let (high, low) = (pair[0], pair[1]);
+        if (high | low) > 0x0f {
+            return false;
+        }
+        *out = high << 4 | low;
+    }
+    true
+}
+
+/// Unpacks unaligned chunks of `T` in `input` to `output` using `unpack_chunk`.
+///
+/// The remainder is unpacked using the generic [`unpack`].
+#[inline]
+#[allow(dead_code)]
+pub(crate) unsafe fn unpack_unaligned_chunks<T: Copy, U: Copy>(
+    input: &[u8],
+    mut output: &mut [u8],
+    mut unpack_chunk: impl FnMut(T) -> U,
+) {
+    debug_assert_eq!(size_of::<U>(), size_of::<T>() * 2);
+    let (chunks, remainder) = chunks_unaligned::<T>(input);
+    for chunk in chunks {
+        output.write(as_bytes(&unpack_chunk(chunk)));
+    }
+    unpack(remainder, output);
+}
+
+/// Packs unaligned chunks of `U` in `input` to `output` using `pack_chunk`.
+///
+/// Returns `false` on the first invalid chunk. The remainder is packed using the generic [`pack`].
+#[inline]
+#[allow(dead_code)]
+pub(crate) unsafe fn pack_unaligned_chunks<T: Copy, U: Copy>(
+    input: &[u8],
+    mut output: &mut [u8],
+    mut pack_chunk: impl FnMut(U) -> Option<T>,
+) -> bool {
+    debug_assert_eq!(size_of::<U>(), size_of::<T>() * 2);
+    let (chunks, remainder) = chunks_unaligned::<U>(input);
+    for chunk in chunks {
+        match pack_chunk(chunk) {
+            Some(packed) => output.write(as_bytes(&packed)),
+            None => return false,
+        }
+    }
+    pack(remainder, output)
+}
+
+/// Default UTF-16 encoding function.
+///
+/// Assumes `output.len() == input.len() * 2`.
+pub(crate) fn encode_utf16<const UPPER: bool>(input: &[u8], output: &mut [u16]) {
+    debug_assert_eq!(output.len(), input.len() * 2);
+    for (&byte, out) in input.iter().zip(output.chunks_exact_mut(2)) {
+        let (high, low) = byte2hex::<UPPER>(byte);
+        out[0] = high as u16;
+        out[1] = low as u16;
+    }
+}
+
+/// Encodes unaligned chunks of `T` in `input` to UTF-16 `output` using `encode_chunk`, which must
+/// return the encoded characters widened to code units.
+///
+/// The remainder is passed to `encode_remainder`.
+#[inline]
+#[allow(dead_code)]
+pub(crate) unsafe fn encode_utf16_unaligned_chunks<T: Copy, U: Copy>(
+    input: &[u8],
+    output: &mut [u16],
+    mut encode_chunk: impl FnMut(T) -> U,
+    encode_remainder: impl FnOnce(&[u8], &mut [u16]),
+) {
+    debug_assert_eq!(output.len(), input.len() * 2);
+    debug_assert_eq!(size_of::<U>(), size_of::<T>() * 4);
+    let (chunks, remainder) = chunks_unaligned::<T>(input);
+    let written = (input.len() - remainder.len()) * 2;
+    let mut out = output.as_mut_ptr();
+    for chunk in chunks {
+        unsafe {
+            out.cast::<U>().write_unaligned(encode_chunk(chunk));
+            out = out.add(size_of::<U>() / 2);
+        }
+    }
+    encode_remainder(remainder, unsafe { output.get_unchecked_mut(written..) });
+}
+
+/// Returns the value of a hex digit code unit, or [`NIL`] if it is invalid.
+#[inline]
+const fn unhex_utf16(c: u16) -> u8 {
+    if c > 0xff {
+        NIL
+    } else {
+        HEX_DECODE_LUT[c as usize]
+    }
+}
+
+/// Default UTF-16 check function. Non-ASCII code units are invalid.
+pub(crate) fn check_utf16(input: &[u16]) -> bool {
+    input.iter().all(|&c| unhex_utf16(c) != NIL)
+}
+
+/// Runs the given check function on unaligned chunks of `T` in UTF-16 `input`, with the remainder
+/// passed to `check_remainder`.
+#[inline]
+#[allow(dead_code)]
+pub(crate) fn check_utf16_unaligned_chunks<T: Copy>(
+    input: &[u16],
+    check_chunk: impl FnMut(T) -> bool,
+    check_remainder: impl FnOnce(&[u16]) -> bool,
+) -> bool {
+    let (mut chunks, remainder) = chunks_unaligned_utf16(input);
+    chunks.all(check_chunk) && (remainder.is_empty() || check_remainder(remainder))
+}
+
+/// Default UTF-16 checked decoding function. Non-ASCII code units are invalid.
+///
+/// Assumes `output.len() == input.len() / 2`.
+pub(crate) fn decode_utf16_checked(input: &[u16], output: &mut [u8]) -> bool {
+    debug_assert_eq!(output.len(), input.len() / 2);
+    for (pair, out) in input.chunks_exact(2).zip(output) {
+        let high = unhex_utf16(pair[0]);
+        let low = unhex_utf16(pair[1]);
+        if (high | low) == NIL {
+            return false;
+        }
+        *out = high << 4 | low;
+    }
+    true
+}
+
+/// Checked-decodes unaligned chunks of `U` in UTF-16 `input` to `output` using `decode_chunk`.
+///
+/// Returns `false` on the first invalid chunk. The remainder is passed to `decode_remainder`.
+#[inline]
+#[allow(dead_code)]
+pub(crate) unsafe fn decode_utf16_checked_unaligned_chunks<T: Copy, U: Copy>(
+    input: &[u16],
+    mut output: &mut [u8],
+    mut decode_chunk: impl FnMut(U) -> Option<T>,
+    decode_remainder: impl FnOnce(&[u16], &mut [u8]) -> bool,
+) -> bool {
+    debug_assert_eq!(output.len(), input.len() / 2);
+    debug_assert_eq!(size_of::<U>(), size_of::<T>() * 4);
+    let (chunks, remainder) = chunks_unaligned_utf16::<U>(input);
+    for chunk in chunks {
+        match decode_chunk(chunk) {
+            Some(decoded) => output.write(as_bytes(&decoded)),
+            None => return false,
+        }
+    }
+    remainder.is_empty() || decode_remainder(remainder, output)
+}
+
 #[inline]
 fn chunks_unaligned<T: Copy>(input: &[u8]) -> (impl ExactSizeIterator<Item = T> + '_, &[u8]) {
     let chunks = input.chunks_exact(core::mem::size_of::<T>());
@@ -274,6 +659,18 @@ fn chunks_unaligned<T: Copy>(input: &[u8]) -> (impl ExactSizeIterator<Item = T>
     )
 }
 
+#[inline]
+fn chunks_unaligned_utf16<T: Copy>(
+    input: &[u16],
+) -> (impl ExactSizeIterator<Item = T> + '_, &[u16]) {
+    let chunks = input.chunks_exact(size_of::<T>() / 2);
+    let remainder = chunks.remainder();
+    (
+        chunks.map(|chunk| unsafe { chunk.as_ptr().cast::<T>().read_unaligned() }),
+        remainder,
+    )
+}
+
 #[inline]
 const fn as_bytes<T: Copy>(x: &T) -> &[u8] {
     unsafe { core::slice::from_raw_parts(x as *const _ as *const u8, size_of::<T>()) }
@@ -1,3 +1,5 @@
+use crate::Backend;
+
 pub(crate) mod generic;
 
 // The main implementation functions.
@@ -21,3 +23,323 @@ cfg_if::cfg_if! {
         pub(crate) use generic as imp;
     }
 }
+
+type EncodeFn = unsafe fn(&[u8], &mut [u8]);
+type CheckFn = unsafe fn(&[u8]) -> bool;
+type DecodeCheckedFn = unsafe fn(&[u8], &mut [u8]) -> bool;
+type DecodeUncheckedFn = unsafe fn(&[u8], &mut [u8]);
+type UnpackFn = unsafe fn(&[u8], &mut [u8]);
+type PackFn = unsafe fn(&[u8], &mut [u8]) -> bool;
+type EncodeUtf16Fn = unsafe fn(&[u8], &mut [u16]);
+type CheckUtf16Fn = unsafe fn(&[u16]) -> bool;
+type DecodeUtf16CheckedFn = unsafe fn(&[u16], &mut [u8]) -> bool;
+
+/// A table of the kernels implemented by a single backend.
+///
+/// Each implementation module exports a `KERNELS` slice of these, in order of preference. The
+/// first available one is resolved once and cached, see [`kernels`].
+#[derive(Clone, Copy)]
+pub(crate) struct Kernels {
+    pub(crate) backend: Backend,
+    /// Returns `true` if the backend is supported by the current CPU.
+    pub(crate) is_available: fn() -> bool,
+    /// Lowercase and uppercase encoding functions, in that order.
+    pub(crate) encode: [EncodeFn; 2],
+    /// Like `encode`, but reads the input bytes in reverse order.
+    pub(crate) encode_reversed: [EncodeFn; 2],
+    pub(crate) check: CheckFn,
+    pub(crate) decode_checked: DecodeCheckedFn,
+    pub(crate) decode_unchecked: DecodeUncheckedFn,
+    /// Splits bytes into nibbles, high nibble first.
+    pub(crate) unpack: UnpackFn,
+    /// Joins pairs of nibbles into bytes, returning `false` if any nibble is out of range.
+    pub(crate) pack: PackFn,
+    /// Like `encode`, but widens the output characters to UTF-16 code units.
+    pub(crate) encode_utf16: [EncodeUtf16Fn; 2],
+    /// Like `check`, but reads UTF-16 code units. Non-ASCII code units are invalid.
+    ///
+    /// The SIMD kernels narrow code units to bytes with saturation and reuse the byte kernels.
+    /// This cannot make an invalid input valid: a non-ASCII code unit narrows to a value of at
+    /// least `0x80`, or to `0x00` where the saturation is signed (`0x8000..` on x86 and wasm32),
+    /// and none of these is a hex digit.
+    pub(crate) check_utf16: CheckUtf16Fn,
+    /// Like `decode_checked`, but reads UTF-16 code units. Non-ASCII code units are invalid. See
+    /// `check_utf16` for how the SIMD kernels handle them.
+    pub(crate) decode_utf16_checked: DecodeUtf16CheckedFn,
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(any(
+        feature = "force-generic",
+        feature = "portable-simd",
+        all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "avx2"),
+        all(target_arch = "aarch64", target_feature = "neon"),
+        not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")),
+    ))] {
+        cfg_if::cfg_if! {
+            if #[cfg(target_has_atomic = "ptr")] {
+                use core::ptr;
+                use core::sync::atomic::{AtomicPtr, Ordering};
+
+                /// The forced kernels, or null if the best backend is active.
+                static FORCED: AtomicPtr<Kernels> = AtomicPtr::new(ptr::null_mut());
+
+                /// Returns the active kernels.
+                ///
+                /// The best backend is enabled at compile time, so unless another backend is
+                /// forced, it is used directly, without detection, and calls through it can be
+                /// inlined.
+                #[inline(always)]
+                pub(crate) fn kernels() -> &'static Kernels {
+                    let forced = FORCED.load(Ordering::Relaxed);
+                    if forced.is_null() {
+                        &imp::KERNELS[0]
+                    } else {
+                        forced_kernels(forced)
+                    }
+                }
+
+                #[cold]
+                fn forced_kernels(forced: *mut Kernels) -> &'static Kernels {
+                    // SAFETY: Only ever set to a `&'static Kernels` in `set_kernels`.
+                    unsafe { &*forced }
+                }
+
+                fn set_kernels(kernels: &'static Kernels) -> bool {
+                    let forced = if kernels.backend == imp::KERNELS[0].backend {
+                        ptr::null_mut()
+                    } else {
+                        kernels as *const Kernels as *mut Kernels
+                    };
+                    FORCED.store(forced, Ordering::Relaxed);
+                    true
+                }
+            } else {
+                /// Returns the active kernels.
+                ///
+                /// The best backend is enabled at compile time, so it is used directly, without
+                /// detection, and calls through it can be inlined. Only it can be forced.
+                #[inline(always)]
+                pub(crate) fn kernels() -> &'static Kernels {
+                    &imp::KERNELS[0]
+                }
+
+                fn set_kernels(kernels: &'static Kernels) -> bool {
+                    kernels.backend == imp::KERNELS[0].backend
+                }
+            }
+        }
+    } else if #[cfg(target_has_atomic = "ptr")] {
+        use core::ptr;
+        use core::sync::atomic::{AtomicPtr, Ordering};
+
+        static ACTIVE: AtomicPtr<Kernels> = AtomicPtr::new(ptr::null_mut());
+
+        /// Returns the active kernels, resolving them on first use.
+        #[inline]
+        pub(crate) fn kernels() -> &'static Kernels {
+            let kernels = ACTIVE.load(Ordering::Relaxed);
+            if kernels.is_null() {
+                resolve()
+            } else {
+                // SAFETY: Only ever set to a `&'static Kernels` in `set_kernels`.
+                unsafe { &*kernels }
+            }
+        }
+
+        #[cold]
+        fn resolve() -> &'static Kernels {
+            let kernels = detect();
+            set_kernels(kernels);
+            kernels
+        }
+
+        fn set_kernels(kernels: &'static Kernels) -> bool {
+            ACTIVE.store(kernels as *const Kernels as *mut Kernels, Ordering::Relaxed);
+            true
+        }
+    } else {
+        /// Returns the active kernels.
+        ///
+        /// Caching is not supported on targets without atomic pointers.
+        #[inline]
+        pub(crate) fn kernels() -> &'static Kernels {
+            detect()
+        }
+
+        fn set_kernels(kernels: &'static Kernels) -> bool {
+            kernels.backend == detect().backend
+        }
+    }
+}
+
+/// Returns the first available kernels of the current implementation.
+fn detect() -> &'static Kernels {
+    imp::KERNELS
+        .iter()
+        .find(|k| (k.is_available)())
+        .unwrap_or(&generic::GENERIC)
+}
+
+/// Returns the kernels for `backend`, if it was compiled in and is supported by the current CPU.
+pub(crate) fn find(backend: Backend) -> Option<&'static Kernels> {
+    imp::KERNELS
+        .iter()
+        .find(|k| k.backend == backend && (k.is_available)())
+}
+
+/// Returns all the kernels that were compiled in and are supported by the current CPU, in order of
+/// preference.
+pub(crate) fn available() -> impl Iterator<Item = &'static Kernels> {
+    imp::KERNELS.iter().filter(|k| (k.is_available)())
+}
+
+/// Overrides the active kernels. Returns `false` if `backend` is not available.
+pub(crate) fn force(backend: Backend) -> bool {
+    match find(backend) {
+        Some(kernels) => set_kernels(kernels),
+        None => false,
+    }
+}
+
+/// Resets the active kernels to the detected ones.
+pub(crate) fn reset() {
+    set_kernels(detect());
+}
+
+/// Encodes `input` into `output` using the active kernels.
+///
+/// # Safety
+///
+/// `output` must be at least `2 * input.len()` bytes long.
+#[inline]
+pub(crate) unsafe fn encode<const UPPER: bool>(input: &[u8], output: &mut [u8]) {
+    unsafe { (kernels().encode[UPPER as usize])(input, output) }
+}
+
+/// Encodes `input` in reverse byte order into `output` using the active kernels.
+///
+/// # Safety
+///
+/// `output` must be exactly `2 * input.len()` bytes long.
+#[inline]
+pub(crate) unsafe fn encode_reversed<const UPPER: bool>(input: &[u8], output: &mut [u8]) {
+    unsafe { (kernels().encode_reversed[UPPER as usize])(input, output) }
+}
+
+/// Returns `true` if there is a fixed-size fast path for `len`-byte inputs.
+///
+/// These are the most common sizes of hashes and addresses.
+#[inline]
+pub(crate) const fn has_fixed(len: usize) -> bool {
+    matches!(len, 20 | 32 | 64)
+}
+
+/// Encodes a fixed-size `input` into `output`, using a fully unrolled fast path if the active
+/// backend has one for `N`.
+///
+/// # Safety
+///
+/// `output` must be exactly `2 * N` bytes long.
+#[inline]
+pub(crate) unsafe fn encode_fixed<const N: usize, const UPPER: bool>(
+    input: &[u8; N],
+    output: &mut [u8],
+) {
+    if !(has_fixed(N) && unsafe { imp::encode_fixed::<N, UPPER>(input, output) }) {
+        unsafe { encode::<UPPER>(input, output) }
+    }
+}
+
+/// Checked-decodes `input` into a fixed-size `output` using a fully unrolled fast path.
+///
+/// Returns `None` if the active backend has no fast path for `N`.
+///
+/// # Safety
+///
+/// Assumes `input.len() == 2 * N`.
+#[inline]
+pub(crate) unsafe fn decode_fixed<const N: usize>(
+    input: &[u8],
+    output: &mut [u8; N],
+) -> Option<bool> {
+    if has_fixed(N) {
+        unsafe { imp::decode_fixed::<N>(input, output) }
+    } else {
+        None
+    }
+}
+
+/// Returns `true` if `input` is valid hex, using the active kernels.
+#[inline]
+pub(crate) fn check(input: &[u8]) -> bool {
+    // SAFETY: Only available kernels are ever active.
+    unsafe { (kernels().check)(input) }
+}
+
+/// Checked-decodes `input` into `output` using the active kernels.
+///
+/// # Safety
+///
+/// Assumes `output.len() == input.len() / 2`.
+#[inline]
+pub(crate) unsafe fn decode_checked(input: &[u8], output: &mut [u8]) -> bool {
+    unsafe { (kernels().decode_checked)(input, output) }
+}
+
+/// Decodes `input` into `output` using the active kernels.
+///
+/// # Safety
+///
+/// Assumes `output.len() == input.len() / 2` and that the input is valid hex.
+#[inline]
+pub(crate) unsafe fn decode_unchecked(input: &[u8], output: &mut [u8]) {
+    unsafe { (kernels().decode_unchecked)(input, output) }
+}
+
+/// Splits `input` into nibbles using the active kernels.
+///
+/// # Safety
+///
+/// Assumes `output.len() == input.len() * 2`.
+#[inline]
+pub(crate) unsafe fn unpack(input: &[u8], output: &mut [u8]) {
+    unsafe { (kernels().unpack)(input, output) }
+}
+
+/// Joins pairs of nibbles in `input` into `output` using the active kernels.
+///
+/// # Safety
+///
+/// Assumes `output.len() == input.len() / 2`.
+#[inline]
+pub(crate) unsafe fn pack(input: &[u8], output: &mut [u8]) -> bool {
+    unsafe { (kernels().pack)(input, output) }
+}
+
+/// Encodes `input` into UTF-16 `output` using the active kernels.
+///
+/// # Safety
+///
+/// `output` must be exactly `2 * input.len()` code units long.
+#[inline]
+pub(crate) unsafe fn encode_utf16<const UPPER: bool>(input: &[u8], output: &mut [u16]) {
+    unsafe { (kernels().encode_utf16[UPPER as usize])(input, output) }
+}
+
+/// Returns `true` if UTF-16 `input` is valid hex, using the active kernels.
+#[inline]
+pub(crate) fn check_utf16(input: &[u16]) -> bool {
+    // SAFETY: Only available kernels are ever active.
+    unsafe { (kernels().check_utf16)(input) }
+}
+
+/// Checked-decodes UTF-16 `input` into `output` using the active kernels.
+///
+/// # Safety
+///
+/// Assumes `output.len() == input.len() / 2`.
+#[inline]
+pub(crate) unsafe fn decode_utf16_checked(input: &[u16], output: &mut [u8]) -> bool {
+    unsafe { (kernels().decode_utf16_checked)(input, output) }
+}
@@ -1,40 +1,90 @@
 #![allow(unsafe_op_in_unsafe_fn)]
 
-use super::generic;
-use crate::{get_chars_table, Output};
+use super::{generic, Kernels};
+use crate::{get_chars_table, Backend, RawOutput};
 use core::simd::prelude::*;
 
 type Simd = u8x16;
 
 pub(crate) const USE_CHECK_FN: bool = false;
 
-pub(crate) unsafe fn encode<const UPPER: bool>(input: &[u8], output: impl Output) {
+pub(crate) use super::generic::{decode_fixed, encode_fixed};
+
+pub(crate) static KERNELS: &[Kernels] = &[
+    Kernels {
+        backend: Backend::PortableSimd,
+        is_available: || true,
+        encode: [encode_slice::<false>, encode_slice::<true>],
+        encode_reversed: [encode_reversed::<false>, encode_reversed::<true>],
+        check,
+        decode_checked,
+        decode_unchecked,
+        unpack,
+        pack,
+        encode_utf16: [encode_utf16::<false>, encode_utf16::<true>],
+        check_utf16,
+        decode_utf16_checked,
+    },
+    generic::GENERIC,
+];
+
+pub(crate) unsafe fn encode<const UPPER: bool>(input: &[u8], output: impl RawOutput) {
+    if super::kernels().backend != Backend::PortableSimd {
+        return generic::encode::<UPPER>(input, output);
+    }
+    encode_simd::<UPPER>(input, output);
+}
+
+unsafe fn encode_slice<const UPPER: bool>(input: &[u8], output: &mut [u8]) {
+    encode_simd::<UPPER>(input, output);
+}
+
+unsafe fn encode_simd<const UPPER: bool>(input: &[u8], output: impl RawOutput) {
     // Load table.
     let hex_table = Simd::from_array(*get_chars_table::<UPPER>());
 
     generic::encode_unaligned_chunks::<UPPER, _, _>(input, output, |chunk: Simd| {
-        // Load input bytes and mask to nibbles.
-        let mut lo = chunk & Simd::splat(15);
-        let mut hi = chunk >> Simd::splat(4);
+        encode_chunk(hex_table, chunk)
+    });
+}
 
-        // Lookup the corresponding ASCII hex digit for each nibble.
-        lo = hex_table.swizzle_dyn(lo);
-        hi = hex_table.swizzle_dyn(hi);
+unsafe fn encode_reversed<const UPPER: bool>(input: &[u8], output: &mut [u8]) {
+    let hex_table = Simd::from_array(*get_chars_table::<UPPER>());
 
-        // Interleave the nibbles ([hi[0], lo[0], hi[1], lo[1], ...]).
-        let (hex_lo, hex_hi) = Simd::interleave(hi, lo);
-        [hex_lo, hex_hi]
-    });
+    generic::encode_reversed_unaligned_chunks(
+        input,
+        output,
+        |chunk: Simd| encode_chunk(hex_table, chunk.reverse()),
+        generic::encode_reversed::<UPPER>,
+    );
 }
 
-pub(crate) fn check(input: &[u8]) -> bool {
-    generic::check_unaligned_chunks(input, |chunk: Simd| {
-        let valid_digit = chunk.simd_ge(Simd::splat(b'0')) & chunk.simd_le(Simd::splat(b'9'));
-        let valid_upper = chunk.simd_ge(Simd::splat(b'A')) & chunk.simd_le(Simd::splat(b'F'));
-        let valid_lower = chunk.simd_ge(Simd::splat(b'a')) & chunk.simd_le(Simd::splat(b'f'));
-        let valid = valid_digit | valid_upper | valid_lower;
-        valid.all()
-    })
+#[inline]
+fn encode_chunk(hex_table: Simd, chunk: Simd) -> [Simd; 2] {
+    // Load input bytes and mask to nibbles.
+    let mut lo = chunk & Simd::splat(15);
+    let mut hi = chunk >> Simd::splat(4);
+
+    // Lookup the corresponding ASCII hex digit for each nibble.
+    lo = hex_table.swizzle_dyn(lo);
+    hi = hex_table.swizzle_dyn(hi);
+
+    // Interleave the nibbles ([hi[0], lo[0], hi[1], lo[1], ...]).
+    let (hex_lo, hex_hi) = Simd::interleave(hi, lo);
+    [hex_lo, hex_hi]
+}
+
+fn check(input: &[u8]) -> bool {
+    generic::check_unaligned_chunks(input, check_chunk)
+}
+
+#[inline]
+fn check_chunk(chunk: Simd) -> bool {
+    let valid_digit = chunk.simd_ge(Simd::splat(b'0')) & chunk.simd_le(Simd::splat(b'9'));
+    let valid_upper = chunk.simd_ge(Simd::splat(b'A')) & chunk.simd_le(Simd::splat(b'F'));
+    let valid_lower = chunk.simd_ge(Simd::splat(b'a')) & chunk.simd_le(Simd::splat(b'f'));
+    let valid = valid_digit | valid_upper | valid_lower;
+    valid.all()
 }
 
 /// Single-pass hex decode with validation using Muła & Langdale's Algorithm #3.
@@ -45,9 +95,14 @@ pub(crate) fn check(input: &[u8]) -> bool {
 /// - Nibble pairs are merged with `deinterleave` + `(hi << 4) | lo`.
 ///
 /// Based on: <http://0x80.pl/notesen/2022-01-17-validating-hex-parse.html>
-pub(crate) unsafe fn decode_checked(input: &[u8], output: &mut [u8]) -> bool {
+unsafe fn decode_checked(input: &[u8], output: &mut [u8]) -> bool {
     debug_assert_eq!(output.len(), input.len() / 2);
+    generic::decode_checked_unaligned_chunks(input, output, decode_chunk_checked)
+}
 
+/// Decodes a 32-character chunk, see [`decode_checked`].
+#[inline]
+fn decode_chunk_checked([v0, v1]: [Simd; 2]) -> Option<Simd> {
     let add_c6 = Simd::splat(0xC6); // 0xFF - b'9'
     let six = Simd::splat(6);
     let f0 = Simd::splat(0xF0);
@@ -56,32 +111,30 @@ pub(crate) unsafe fn decode_checked(input: &[u8], output: &mut [u8]) -> bool {
     let ten = Simd::splat(10);
     let check_bias = Simd::splat(112); // 127 - 15
 
-    generic::decode_checked_unaligned_chunks(input, output, |[v0, v1]: [Simd; 2]| {
-        // Digits '0'..'9' → 0..9, others > 15.
-        let d0 = (v0 + add_c6).saturating_sub(six) - f0;
-        let d1 = (v1 + add_c6).saturating_sub(six) - f0;
+    // Digits '0'..'9' → 0..9, others > 15.
+    let d0 = (v0 + add_c6).saturating_sub(six) - f0;
+    let d1 = (v1 + add_c6).saturating_sub(six) - f0;
 
-        // Letters 'A'..'F'/'a'..'f' → 10..15, others > 15.
-        let a0 = ((v0 & df) - big_a).saturating_add(ten);
-        let a1 = ((v1 & df) - big_a).saturating_add(ten);
+    // Letters 'A'..'F'/'a'..'f' → 10..15, others > 15.
+    let a0 = ((v0 & df) - big_a).saturating_add(ten);
+    let a1 = ((v1 & df) - big_a).saturating_add(ten);
 
-        // Valid nibble wins (0..15), invalid stays > 15.
-        let n0 = d0.simd_min(a0);
-        let n1 = d1.simd_min(a1);
+    // Valid nibble wins (0..15), invalid stays > 15.
+    let n0 = d0.simd_min(a0);
+    let n1 = d1.simd_min(a1);
 
-        // Validate: saturating add sets MSB if nibble > 15.
-        let c = n0.saturating_add(check_bias) | n1.saturating_add(check_bias);
-        if c.simd_gt(Simd::splat(0x7F)).any() {
-            return None;
-        }
+    // Validate: saturating add sets MSB if nibble > 15.
+    let c = n0.saturating_add(check_bias) | n1.saturating_add(check_bias);
+    if c.simd_gt(Simd::splat(0x7F)).any() {
+        return None;
+    }
 
-        // Deinterleave and merge nibble pairs.
-        let (hi, lo) = Simd::deinterleave(n0, n1);
-        Some((hi << Simd::splat(4)) | lo)
-    })
+    // Deinterleave and merge nibble pairs.
+    let (hi, lo) = Simd::deinterleave(n0, n1);
+    Some((hi << Simd::splat(4)) | lo)
 }
 
-pub(crate) unsafe fn decode_unchecked(input: &[u8], output: &mut [u8]) {
+unsafe fn decode_unchecked(input: &[u8], output: &mut [u8]) {
     generic::decode_unchecked_unaligned_chunks(input, output, |[v0, v1]: [Simd; 2]| {
         let n0 = unhex(v0);
         let n1 = unhex(v1);
@@ -97,3 +150,56 @@ fn unhex(x: Simd) -> Simd {
     let low = x & Simd::splat(0x0F);
     sr6 * Simd::splat(9) + low
 }
+
+unsafe fn unpack(input: &[u8], output: &mut [u8]) {
+    generic::unpack_unaligned_chunks(input, output, |chunk: Simd| {
+        let (hi, lo) = Simd::interleave(chunk >> Simd::splat(4), chunk & Simd::splat(0x0F));
+        [hi, lo]
+    });
+}
+
+unsafe fn pack(input: &[u8], output: &mut [u8]) -> bool {
+    generic::pack_unaligned_chunks(input, output, |[n0, n1]: [Simd; 2]| {
+        if (n0 | n1).simd_gt(Simd::splat(0x0F)).any() {
+            return None;
+        }
+        let (hi, lo) = Simd::deinterleave(n0, n1);
+        Some((hi << Simd::splat(4)) | lo)
+    })
+}
+
+type Simd16 = u16x16;
+
+unsafe fn encode_utf16<const UPPER: bool>(input: &[u8], output: &mut [u16]) {
+    let hex_table = Simd::from_array(*get_chars_table::<UPPER>());
+
+    generic::encode_utf16_unaligned_chunks(
+        input,
+        output,
+        |chunk: Simd| encode_chunk(hex_table, chunk).map(|hex| hex.cast::<u16>()),
+        generic::encode_utf16::<UPPER>,
+    );
+}
+
+fn check_utf16(input: &[u16]) -> bool {
+    generic::check_utf16_unaligned_chunks(
+        input,
+        |chunk: Simd16| check_chunk(narrow(chunk)),
+        generic::check_utf16,
+    )
+}
+
+unsafe fn decode_utf16_checked(input: &[u16], output: &mut [u8]) -> bool {
+    generic::decode_utf16_checked_unaligned_chunks(
+        input,
+        output,
+        |chunks: [Simd16; 2]| decode_chunk_checked(chunks.map(narrow)),
+        generic::decode_utf16_checked,
+    )
+}
+
+/// Narrows UTF-16 code units to bytes, saturating at `0xFF`.
+#[inline]
+fn narrow(x: Simd16) -> Simd {
+    x.simd_min(Simd16::splat(0xFF)).cast::<u8>()
+}
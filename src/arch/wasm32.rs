@@ -1,73 +1,119 @@
 #![allow(unsafe_op_in_unsafe_fn)]
 
-use super::generic;
-use crate::{get_chars_table, Output};
+use super::{generic, Kernels};
+use crate::{get_chars_table, Backend, RawOutput};
 use core::arch::wasm32::*;
 
 pub(crate) const USE_CHECK_FN: bool = false;
 
+pub(crate) use super::generic::{decode_fixed, encode_fixed};
+
+pub(crate) static KERNELS: &[Kernels] = &[
+    Kernels {
+        backend: Backend::Simd128,
+        is_available: || true,
+        encode: [encode_slice::<false>, encode_slice::<true>],
+        encode_reversed: [
+            generic::encode_reversed::<false>,
+            generic::encode_reversed::<true>,
+        ],
+        check,
+        decode_checked,
+        decode_unchecked,
+        unpack,
+        pack,
+        encode_utf16: [encode_utf16::<false>, encode_utf16::<true>],
+        check_utf16,
+        decode_utf16_checked,
+    },
+    generic::GENERIC,
+];
+
+#[inline]
+pub(crate) unsafe fn encode<const UPPER: bool>(input: &[u8], output: impl RawOutput) {
+    if super::kernels().backend != Backend::Simd128 {
+        return generic::encode::<UPPER>(input, output);
+    }
+    encode_simd128::<UPPER>(input, output);
+}
+
+#[target_feature(enable = "simd128")]
+unsafe fn encode_slice<const UPPER: bool>(input: &[u8], output: &mut [u8]) {
+    encode_simd128::<UPPER>(input, output);
+}
+
 #[inline]
 #[target_feature(enable = "simd128")]
-pub(crate) unsafe fn encode<const UPPER: bool>(input: &[u8], output: impl Output) {
+unsafe fn encode_simd128<const UPPER: bool>(input: &[u8], output: impl RawOutput) {
     // Load table.
     let hex_table = v128_load(get_chars_table::<UPPER>().as_ptr().cast());
 
     generic::encode_unaligned_chunks::<UPPER, _, _>(input, output, |chunk: v128| {
-        // Load input bytes and mask to nibbles.
-        let mut lo = v128_and(chunk, u8x16_splat(0x0F));
-        let mut hi = u8x16_shr(chunk, 4);
+        encode_chunk(hex_table, chunk)
+    });
+}
 
-        // Lookup the corresponding ASCII hex digit for each nibble.
-        lo = u8x16_swizzle(hex_table, lo);
-        hi = u8x16_swizzle(hex_table, hi);
+#[inline]
+#[target_feature(enable = "simd128")]
+fn encode_chunk(hex_table: v128, chunk: v128) -> [v128; 2] {
+    // Load input bytes and mask to nibbles.
+    let mut lo = v128_and(chunk, u8x16_splat(0x0F));
+    let mut hi = u8x16_shr(chunk, 4);
 
-        // Interleave the nibbles ([hi[0], lo[0], hi[1], lo[1], ...]).
-        #[rustfmt::skip]
-        let hex_lo = u8x16_shuffle::<
-            0, 16,
-            1, 17,
-            2, 18,
-            3, 19,
-            4, 20,
-            5, 21,
-            6, 22,
-            7, 23,
-        >(hi, lo);
-        #[rustfmt::skip]
-        let hex_hi = u8x16_shuffle::<
-            8, 24,
-            9, 25,
-            10, 26,
-            11, 27,
-            12, 28,
-            13, 29,
-            14, 30,
-            15, 31,
-        >(hi, lo);
-        [hex_lo, hex_hi]
-    });
+    // Lookup the corresponding ASCII hex digit for each nibble.
+    lo = u8x16_swizzle(hex_table, lo);
+    hi = u8x16_swizzle(hex_table, hi);
+
+    // Interleave the nibbles ([hi[0], lo[0], hi[1], lo[1], ...]).
+    #[rustfmt::skip]
+    let hex_lo = u8x16_shuffle::<
+        0, 16,
+        1, 17,
+        2, 18,
+        3, 19,
+        4, 20,
+        5, 21,
+        6, 22,
+        7, 23,
+    >(hi, lo);
+    #[rustfmt::skip]
+    let hex_hi = u8x16_shuffle::<
+        8, 24,
+        9, 25,
+        10, 26,
+        11, 27,
+        12, 28,
+        13, 29,
+        14, 30,
+        15, 31,
+    >(hi, lo);
+    [hex_lo, hex_hi]
 }
 
 #[inline]
 #[target_feature(enable = "simd128")]
-pub(crate) fn check(input: &[u8]) -> bool {
-    generic::check_unaligned_chunks(input, |chunk: v128| {
-        let ge0 = u8x16_ge(chunk, u8x16_splat(b'0'));
-        let le9 = u8x16_le(chunk, u8x16_splat(b'9'));
-        let valid_digit = v128_and(ge0, le9);
-
-        let geua = u8x16_ge(chunk, u8x16_splat(b'A'));
-        let leuf = u8x16_le(chunk, u8x16_splat(b'F'));
-        let valid_upper = v128_and(geua, leuf);
-
-        let gela = u8x16_ge(chunk, u8x16_splat(b'a'));
-        let lelf = u8x16_le(chunk, u8x16_splat(b'f'));
-        let valid_lower = v128_and(gela, lelf);
-
-        let valid_letter = v128_or(valid_lower, valid_upper);
-        let valid = v128_or(valid_digit, valid_letter);
-        u8x16_all_true(valid)
-    })
+fn check(input: &[u8]) -> bool {
+    generic::check_unaligned_chunks(input, |chunk| check_chunk(chunk))
+}
+
+#[inline]
+#[target_feature(enable = "simd128")]
+fn check_chunk(chunk: v128) -> bool {
+    let ge0 = u8x16_ge(chunk, u8x16_splat(b'0'));
+    let le9 = u8x16_le(chunk, u8x16_splat(b'9'));
+    let valid_digit = v128_and(ge0, le9);
+
+    let geua = u8x16_ge(chunk, u8x16_splat(b'A'));
+    let leuf = u8x16_le(chunk, u8x16_splat(b'F'));
+    let valid_upper = v128_and(geua, leuf);
+
+    let gela = u8x16_ge(chunk, u8x16_splat(b'a'));
+    let lelf = u8x16_le(chunk, u8x16_splat(b'f'));
+    let valid_lower = v128_and(gela, lelf);
+
+    let valid_letter = v128_or(valid_lower, valid_upper);
+    let valid = v128_or(valid_digit, valid_letter);
+    u8x16_all_true(valid)
 }
 
 /// Single-pass hex decode with validation using Muła & Langdale's Algorithm #3.
@@ -80,9 +126,15 @@ pub(crate) fn check(input: &[u8]) -> bool {
 /// Based on: <http://0x80.pl/notesen/2022-01-17-validating-hex-parse.html>
 #[inline]
 #[target_feature(enable = "simd128")]
-pub(crate) unsafe fn decode_checked(input: &[u8], output: &mut [u8]) -> bool {
+unsafe fn decode_checked(input: &[u8], output: &mut [u8]) -> bool {
     debug_assert_eq!(output.len(), input.len() / 2);
+    generic::decode_checked_unaligned_chunks(input, output, |chunk| decode_chunk_checked(chunk))
+}
 
+/// Decodes a 32-character chunk, see [`decode_checked`].
+#[inline]
+#[target_feature(enable = "simd128")]
+fn decode_chunk_checked([v0, v1]: [v128; 2]) -> Option<v128> {
     let add_c6 = u8x16_splat(0xC6); // 0xFF - b'9'
     let six = u8x16_splat(6);
     let f0 = u8x16_splat(0xF0);
@@ -91,37 +143,35 @@ pub(crate) unsafe fn decode_checked(input: &[u8], output: &mut [u8]) -> bool {
     let ten = u8x16_splat(10);
     let check_bias = u8x16_splat(112); // 127 - 15
 
-    generic::decode_checked_unaligned_chunks(input, output, |[v0, v1]: [v128; 2]| {
-        // Digits '0'..'9' → 0..9, others > 15.
-        let d0 = u8x16_sub(u8x16_sub_sat(u8x16_add(v0, add_c6), six), f0);
-        let d1 = u8x16_sub(u8x16_sub_sat(u8x16_add(v1, add_c6), six), f0);
+    // Digits '0'..'9' → 0..9, others > 15.
+    let d0 = u8x16_sub(u8x16_sub_sat(u8x16_add(v0, add_c6), six), f0);
+    let d1 = u8x16_sub(u8x16_sub_sat(u8x16_add(v1, add_c6), six), f0);
 
-        // Letters 'A'..'F'/'a'..'f' → 10..15, others > 15.
-        let a0 = u8x16_add_sat(u8x16_sub(v128_and(v0, df), big_a), ten);
-        let a1 = u8x16_add_sat(u8x16_sub(v128_and(v1, df), big_a), ten);
+    // Letters 'A'..'F'/'a'..'f' → 10..15, others > 15.
+    let a0 = u8x16_add_sat(u8x16_sub(v128_and(v0, df), big_a), ten);
+    let a1 = u8x16_add_sat(u8x16_sub(v128_and(v1, df), big_a), ten);
 
-        // Valid nibble wins (0..15), invalid stays > 15.
-        let n0 = u8x16_min(d0, a0);
-        let n1 = u8x16_min(d1, a1);
+    // Valid nibble wins (0..15), invalid stays > 15.
+    let n0 = u8x16_min(d0, a0);
+    let n1 = u8x16_min(d1, a1);
 
-        // Validate: saturating add sets MSB if nibble > 15.
-        let c = v128_or(u8x16_add_sat(n0, check_bias), u8x16_add_sat(n1, check_bias));
-        if u8x16_bitmask(c) != 0 {
-            return None;
-        }
+    // Validate: saturating add sets MSB if nibble > 15.
+    let c = v128_or(u8x16_add_sat(n0, check_bias), u8x16_add_sat(n1, check_bias));
+    if u8x16_bitmask(c) != 0 {
+        return None;
+    }
 
-        // Deinterleave and merge nibble pairs.
-        #[rustfmt::skip]
-        let hi = u8x16_shuffle::<0, 2, 4, 6, 8, 10, 12, 14, 16, 18, 20, 22, 24, 26, 28, 30>(n0, n1);
-        #[rustfmt::skip]
-        let lo = u8x16_shuffle::<1, 3, 5, 7, 9, 11, 13, 15, 17, 19, 21, 23, 25, 27, 29, 31>(n0, n1);
-        Some(v128_or(u8x16_shl(hi, 4), lo))
-    })
+    // Deinterleave and merge nibble pairs.
+    #[rustfmt::skip]
+    let hi = u8x16_shuffle::<0, 2, 4, 6, 8, 10, 12, 14, 16, 18, 20, 22, 24, 26, 28, 30>(n0, n1);
+    #[rustfmt::skip]
+    let lo = u8x16_shuffle::<1, 3, 5, 7, 9, 11, 13, 15, 17, 19, 21, 23, 25, 27, 29, 31>(n0, n1);
+    Some(v128_or(u8x16_shl(hi, 4), lo))
 }
 
 #[inline]
 #[target_feature(enable = "simd128")]
-pub(crate) unsafe fn decode_unchecked(input: &[u8], output: &mut [u8]) {
+unsafe fn decode_unchecked(input: &[u8], output: &mut [u8]) {
     generic::decode_unchecked_unaligned_chunks(input, output, |[v0, v1]: [v128; 2]| {
         let n0 = unhex(v0);
         let n1 = unhex(v1);
@@ -145,3 +195,79 @@ unsafe fn unhex(x: v128) -> v128 {
     let mul9 = u8x16_add(u8x16_shl(sr6, 3), sr6);
     u8x16_add(mul9, low)
 }
+
+#[target_feature(enable = "simd128")]
+unsafe fn unpack(input: &[u8], output: &mut [u8]) {
+    generic::unpack_unaligned_chunks(input, output, |chunk: v128| {
+        let hi = u8x16_shr(chunk, 4);
+        let lo = v128_and(chunk, u8x16_splat(0x0F));
+
+        // Interleave the nibbles ([hi[0], lo[0], hi[1], lo[1], ...]).
+        #[rustfmt::skip]
+        let out0 = u8x16_shuffle::<0, 16, 1, 17, 2, 18, 3, 19, 4, 20, 5, 21, 6, 22, 7, 23>(hi, lo);
+        #[rustfmt::skip]
+        let out1 = u8x16_shuffle::<8, 24, 9, 25, 10, 26, 11, 27, 12, 28, 13, 29, 14, 30, 15, 31>(hi, lo);
+        [out0, out1]
+    });
+}
+
+#[target_feature(enable = "simd128")]
+unsafe fn pack(input: &[u8], output: &mut [u8]) -> bool {
+    let check_bias = u8x16_splat(112); // 127 - 15
+    generic::pack_unaligned_chunks(input, output, |[n0, n1]: [v128; 2]| {
+        // Validate: saturating add sets MSB if nibble > 15.
+        let c = v128_or(u8x16_add_sat(n0, check_bias), u8x16_add_sat(n1, check_bias));
+        if u8x16_bitmask(c) != 0 {
+            return None;
+        }
+
+        // Deinterleave and merge nibble pairs.
+        #[rustfmt::skip]
+        let hi = u8x16_shuffle::<0, 2, 4, 6, 8, 10, 12, 14, 16, 18, 20, 22, 24, 26, 28, 30>(n0, n1);
+        #[rustfmt::skip]
+        let lo = u8x16_shuffle::<1, 3, 5, 7, 9, 11, 13, 15, 17, 19, 21, 23, 25, 27, 29, 31>(n0, n1);
+        Some(v128_or(u8x16_shl(hi, 4), lo))
+    })
+}
+
+#[target_feature(enable = "simd128")]
+unsafe fn encode_utf16<const UPPER: bool>(input: &[u8], output: &mut [u16]) {
+    let hex_table = v128_load(get_chars_table::<UPPER>().as_ptr().cast());
+    generic::encode_utf16_unaligned_chunks(
+        input,
+        output,
+        |chunk| {
+            let [a, b] = encode_chunk(hex_table, chunk);
+            [
+                u16x8_extend_low_u8x16(a),
+                u16x8_extend_high_u8x16(a),
+                u16x8_extend_low_u8x16(b),
+                u16x8_extend_high_u8x16(b),
+            ]
+        },
+        generic::encode_utf16::<UPPER>,
+    );
+}
+
+#[inline]
+#[target_feature(enable = "simd128")]
+fn check_utf16(input: &[u16]) -> bool {
+    generic::check_utf16_unaligned_chunks(
+        input,
+        |[v0, v1]: [v128; 2]| check_chunk(u8x16_narrow_i16x8(v0, v1)),
+        generic::check_utf16,
+    )
+}
+
+#[inline]
+#[target_feature(enable = "simd128")]
+unsafe fn decode_utf16_checked(input: &[u16], output: &mut [u8]) -> bool {
+    generic::decode_utf16_checked_unaligned_chunks(
+        input,
+        output,
+        |[v0, v1, v2, v3]: [v128; 4]| {
+            decode_chunk_checked([u8x16_narrow_i16x8(v0, v1), u8x16_narrow_i16x8(v2, v3)])
+        },
+        generic::decode_utf16_checked,
+    )
+}
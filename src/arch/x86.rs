@@ -1,8 +1,8 @@
 #![allow(unsafe_op_in_unsafe_fn)]
 #![allow(unexpected_cfgs)]
 
-use super::generic;
-use crate::{get_chars_table, Output};
+use super::{generic, Kernels};
+use crate::{get_chars_table, Backend, RawOutput};
 
 #[cfg(target_arch = "x86")]
 use core::arch::x86::*;
@@ -11,6 +11,48 @@ use core::arch::x86_64::*;
 
 pub(crate) const USE_CHECK_FN: bool = false;
 
+pub(crate) static KERNELS: &[Kernels] = &[
+    Kernels {
+        backend: Backend::Avx2,
+        is_available: has_avx2,
+        encode: [encode_avx2_slice::<false>, encode_avx2_slice::<true>],
+        encode_reversed: [encode_reversed_avx2::<false>, encode_reversed_avx2::<true>],
+        check: check_avx2,
+        decode_checked: decode_checked_avx2,
+        decode_unchecked: decode_avx2,
+        unpack: unpack_sse2,
+        pack: pack_sse2,
+        encode_utf16: [encode_utf16_avx2::<false>, encode_utf16_avx2::<true>],
+        check_utf16: check_utf16_avx2,
+        decode_utf16_checked: decode_utf16_checked_avx2,
+    },
+    Kernels {
+        backend: Backend::Ssse3,
+        is_available: has_ssse3,
+        encode: [encode_ssse3_slice::<false>, encode_ssse3_slice::<true>],
+        encode_reversed: [
+            encode_reversed_ssse3::<false>,
+            encode_reversed_ssse3::<true>,
+        ],
+        check: check_sse2,
+        unpack: unpack_sse2,
+        pack: pack_sse2,
+        encode_utf16: [encode_utf16_ssse3::<false>, encode_utf16_ssse3::<true>],
+        check_utf16: check_utf16_sse2,
+        ..generic::GENERIC
+    },
+    Kernels {
+        backend: Backend::Sse2,
+        is_available: has_sse2,
+        check: check_sse2,
+        unpack: unpack_sse2,
+        pack: pack_sse2,
+        check_utf16: check_utf16_sse2,
+        ..generic::GENERIC
+    },
+    generic::GENERIC,
+];
+
 cfg_if::cfg_if! {
     if #[cfg(feature = "std")] {
         #[inline(always)]
@@ -39,17 +81,22 @@ cfg_if::cfg_if! {
 // itself taken from [`zbjornson/fast-hex`](https://github.com/zbjornson/fast-hex/blob/a3487bca95127634a61bfeae8f8bfc8f0e5baa3f/src/hex.cc).
 
 #[inline]
-pub(crate) unsafe fn encode<const UPPER: bool>(input: &[u8], output: impl Output) {
-    match () {
-        _ if has_avx2() => encode_avx2::<UPPER>(input, output),
-        _ if has_ssse3() => encode_ssse3::<UPPER>(input, output),
+pub(crate) unsafe fn encode<const UPPER: bool>(input: &[u8], output: impl RawOutput) {
+    match super::kernels().backend {
+        Backend::Avx2 => encode_avx2::<UPPER>(input, output),
+        Backend::Ssse3 => encode_ssse3::<UPPER>(input, output),
         _ => generic::encode::<UPPER>(input, output),
     }
 }
 
-#[inline(never)]
 #[target_feature(enable = "avx2")]
-unsafe fn encode_avx2<const UPPER: bool>(input: &[u8], output: impl Output) {
+unsafe fn encode_avx2_slice<const UPPER: bool>(input: &[u8], output: &mut [u8]) {
+    encode_avx2::<UPPER>(input, output);
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn encode_avx2<const UPPER: bool>(input: &[u8], output: impl RawOutput) {
     generic::encode_unaligned_chunks_with::<UPPER, _, _, _>(
         input,
         output,
@@ -85,7 +132,13 @@ unsafe fn encode_chunk_avx2<const UPPER: bool>(input: __m256i) -> [__m256i; 2] {
 }
 
 #[target_feature(enable = "ssse3")]
-unsafe fn encode_ssse3<const UPPER: bool>(input: &[u8], output: impl Output) {
+unsafe fn encode_ssse3_slice<const UPPER: bool>(input: &[u8], output: &mut [u8]) {
+    encode_ssse3::<UPPER>(input, output);
+}
+
+#[inline]
+#[target_feature(enable = "ssse3")]
+unsafe fn encode_ssse3<const UPPER: bool>(input: &[u8], output: impl RawOutput) {
     generic::encode_unaligned_chunks::<UPPER, _, _>(input, output, |av| {
         encode_chunk_ssse3::<UPPER>(av)
     });
@@ -106,6 +159,37 @@ unsafe fn encode_chunk_ssse3<const UPPER: bool>(input: __m128i) -> [__m128i; 2]
     [_mm_shuffle_epi8(lut, out1), _mm_shuffle_epi8(lut, out2)]
 }
 
+/// Reversed encode: only the bytes within each 128-bit lane are reversed with a shuffle. The lane
+/// swap is folded into the order in which the two encoded lanes are written.
+#[target_feature(enable = "avx2")]
+unsafe fn encode_reversed_avx2<const UPPER: bool>(input: &[u8], output: &mut [u8]) {
+    #[rustfmt::skip]
+    let reverse = _mm256_setr_epi8(
+        15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0,
+        15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0,
+    );
+    generic::encode_reversed_unaligned_chunks(
+        input,
+        output,
+        |chunk: __m256i| {
+            let [lo, hi] = encode_chunk_avx2::<UPPER>(_mm256_shuffle_epi8(chunk, reverse));
+            [hi, lo]
+        },
+        |remainder, out| encode_reversed_ssse3::<UPPER>(remainder, out),
+    );
+}
+
+#[target_feature(enable = "ssse3")]
+unsafe fn encode_reversed_ssse3<const UPPER: bool>(input: &[u8], output: &mut [u8]) {
+    let reverse = _mm_setr_epi8(15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0);
+    generic::encode_reversed_unaligned_chunks(
+        input,
+        output,
+        |chunk: __m128i| encode_chunk_ssse3::<UPPER>(_mm_shuffle_epi8(chunk, reverse)),
+        generic::encode_reversed::<UPPER>,
+    );
+}
+
 /// Hex check using signed overflow trick: bias each valid range so it starts at `i8::MIN`,
 /// then a single `cmpgt(start + len, x)` checks if `x` falls within the range.
 ///
@@ -115,36 +199,31 @@ unsafe fn encode_chunk_ssse3<const UPPER: bool>(input: __m128i) -> [__m128i; 2]
 ///
 /// Based on Muła & Langdale:
 /// <http://0x80.pl/notesen/2022-01-17-validating-hex-parse.html>
-#[inline]
-pub(crate) fn check(input: &[u8]) -> bool {
-    match () {
-        _ if has_avx2() => unsafe { check_avx2(input) },
-        _ if has_sse2() => unsafe { check_sse2(input) },
-        _ => generic::check(input),
-    }
+#[target_feature(enable = "avx2")]
+unsafe fn check_avx2(input: &[u8]) -> bool {
+    generic::check_unaligned_chunks_with(
+        input,
+        |c| check_chunk_avx2(c),
+        |remainder| generic::check_one_unaligned_chunk(remainder, |c| check_chunk_sse2(c)),
+    )
 }
 
+#[inline]
 #[target_feature(enable = "avx2")]
-unsafe fn check_avx2(input: &[u8]) -> bool {
+unsafe fn check_chunk_avx2(chunk: __m256i) -> bool {
     let digit_bias = _mm256_set1_epi8(0xB0_u8 as i8); // '0' + 0x80
     let alpha_bias = _mm256_set1_epi8(0xC1_u8 as i8); // 'A' + 0x80
     let case_mask = _mm256_set1_epi8(0xDF_u8 as i8);
     let digit_threshold = _mm256_set1_epi8(-118); // i8::MIN + 10
     let alpha_threshold = _mm256_set1_epi8(-122); // i8::MIN + 6
 
-    generic::check_unaligned_chunks_with(
-        input,
-        |chunk| {
-            let x1 = _mm256_sub_epi8(chunk, digit_bias);
-            let m1 = _mm256_cmpgt_epi8(digit_threshold, x1);
+    let x1 = _mm256_sub_epi8(chunk, digit_bias);
+    let m1 = _mm256_cmpgt_epi8(digit_threshold, x1);
 
-            let x2 = _mm256_sub_epi8(_mm256_and_si256(chunk, case_mask), alpha_bias);
-            let m2 = _mm256_cmpgt_epi8(alpha_threshold, x2);
+    let x2 = _mm256_sub_epi8(_mm256_and_si256(chunk, case_mask), alpha_bias);
+    let m2 = _mm256_cmpgt_epi8(alpha_threshold, x2);
 
-            _mm256_movemask_epi8(_mm256_or_si256(m1, m2)) == -1
-        },
-        |remainder| generic::check_one_unaligned_chunk(remainder, |c| check_chunk_sse2(c)),
-    )
+    _mm256_movemask_epi8(_mm256_or_si256(m1, m2)) == -1
 }
 
 #[target_feature(enable = "sse2")]
@@ -170,14 +249,6 @@ unsafe fn check_chunk_sse2(chunk: __m128i) -> bool {
     _mm_movemask_epi8(_mm_or_si128(m1, m2)) == 0xffff
 }
 
-#[inline]
-pub(crate) unsafe fn decode_unchecked(input: &[u8], output: &mut [u8]) {
-    if !has_avx2() {
-        return generic::decode_unchecked(input, output);
-    }
-    decode_avx2(input, output);
-}
-
 #[target_feature(enable = "avx2")]
 unsafe fn decode_avx2(input: &[u8], output: &mut [u8]) {
     #[rustfmt::skip]
@@ -236,14 +307,6 @@ unsafe fn nib2byte(a1: __m256i, b1: __m256i, a2: __m256i, b2: __m256i) -> __m256
 /// - Nibble pairs are merged with `maddubs(nibbles, 0x0110)` (hi*16 + lo).
 ///
 /// Based on: <http://0x80.pl/notesen/2022-01-17-validating-hex-parse.html>
-#[inline]
-pub(crate) unsafe fn decode_checked(input: &[u8], output: &mut [u8]) -> bool {
-    if has_avx2() {
-        return decode_checked_avx2(input, output);
-    }
-    generic::decode_checked(input, output)
-}
-
 #[target_feature(enable = "avx2")]
 unsafe fn decode_checked_avx2(input: &[u8], output: &mut [u8]) -> bool {
     debug_assert_eq!(output.len(), input.len() / 2);
@@ -260,32 +323,7 @@ unsafe fn decode_checked_avx2(input: &[u8], output: &mut [u8]) -> bool {
     generic::decode_checked_unaligned_chunks_with(
         input,
         output,
-        |[v1, v2]: [__m256i; 2]| {
-            // Digits '0'..'9' → 0..9, others > 15.
-            let d1 = _mm256_sub_epi8(_mm256_subs_epu8(_mm256_add_epi8(v1, add_c6), six), f0);
-            let d2 = _mm256_sub_epi8(_mm256_subs_epu8(_mm256_add_epi8(v2, add_c6), six), f0);
-
-            // Letters 'A'..'F'/'a'..'f' → 10..15, others > 15.
-            let a1 = _mm256_adds_epu8(_mm256_sub_epi8(_mm256_and_si256(v1, df), big_a), ten);
-            let a2 = _mm256_adds_epu8(_mm256_sub_epi8(_mm256_and_si256(v2, df), big_a), ten);
-
-            // Valid nibble wins (0..15), invalid stays > 15.
-            let n1 = _mm256_min_epu8(d1, a1);
-            let n2 = _mm256_min_epu8(d2, a2);
-
-            // Validate: saturating add sets MSB if nibble > 15.
-            let c1 = _mm256_adds_epu8(n1, check_bias);
-            let c2 = _mm256_adds_epu8(n2, check_bias);
-            if _mm256_movemask_epi8(_mm256_or_si256(c1, c2)) != 0 {
-                return None;
-            }
-
-            // Merge nibble pairs: hi * 16 + lo.
-            let b1 = _mm256_maddubs_epi16(n1, weights);
-            let b2 = _mm256_maddubs_epi16(n2, weights);
-            let packed = _mm256_packus_epi16(b1, b2);
-            Some(_mm256_permute4x64_epi64(packed, 0b11_01_10_00))
-        },
+        |chunk| decode_chunk_checked_avx2(chunk),
         |remainder, out| {
             generic::decode_checked_one_unaligned_chunk(remainder, out, |v: __m256i| {
                 let d = _mm256_sub_epi8(_mm256_subs_epu8(_mm256_add_epi8(v, add_c6), six), f0);
@@ -304,3 +342,180 @@ unsafe fn decode_checked_avx2(input: &[u8], output: &mut [u8]) -> bool {
         },
     )
 }
+
+#[target_feature(enable = "sse2")]
+unsafe fn unpack_sse2(input: &[u8], output: &mut [u8]) {
+    let mask_lo = _mm_set1_epi8(0x0f);
+    generic::unpack_unaligned_chunks(input, output, |chunk: __m128i| {
+        let hi = _mm_and_si128(_mm_srli_epi16(chunk, 4), mask_lo);
+        let lo = _mm_and_si128(chunk, mask_lo);
+        [_mm_unpacklo_epi8(hi, lo), _mm_unpackhi_epi8(hi, lo)]
+    });
+}
+
+/// Joins nibble pairs, read as little-endian 16-bit lanes `hi | lo << 8`, with
+/// `(lane << 4 | lane >> 8) & 0xff`.
+#[target_feature(enable = "sse2")]
+unsafe fn pack_sse2(input: &[u8], output: &mut [u8]) -> bool {
+    let check_bias = _mm_set1_epi8(112); // 127 - 15
+    let mask_lo = _mm_set1_epi16(0xff);
+    generic::pack_unaligned_chunks(input, output, |[v1, v2]: [__m128i; 2]| {
+        // Validate: saturating add sets MSB if nibble > 15.
+        let c = _mm_or_si128(_mm_adds_epu8(v1, check_bias), _mm_adds_epu8(v2, check_bias));
+        if _mm_movemask_epi8(c) != 0 {
+            return None;
+        }
+
+        let b1 = _mm_or_si128(_mm_slli_epi16(v1, 4), _mm_srli_epi16(v1, 8));
+        let b2 = _mm_or_si128(_mm_slli_epi16(v2, 4), _mm_srli_epi16(v2, 8));
+        Some(_mm_packus_epi16(
+            _mm_and_si128(b1, mask_lo),
+            _mm_and_si128(b2, mask_lo),
+        ))
+    })
+}
+
+/// Decodes a 64-character chunk, see [`decode_checked_avx2`].
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn decode_chunk_checked_avx2([v1, v2]: [__m256i; 2]) -> Option<__m256i> {
+    let add_c6 = _mm256_set1_epi8(0xC6u8 as i8); // 0xFF - b'9'
+    let six = _mm256_set1_epi8(6);
+    let f0 = _mm256_set1_epi8(0xF0u8 as i8);
+    let df = _mm256_set1_epi8(0xDFu8 as i8);
+    let big_a = _mm256_set1_epi8(b'A' as i8);
+    let ten = _mm256_set1_epi8(10);
+    let check_bias = _mm256_set1_epi8(112); // 127 - 15
+    let weights = _mm256_set1_epi16(0x0110);
+
+    // Digits '0'..'9' → 0..9, others > 15.
+    let d1 = _mm256_sub_epi8(_mm256_subs_epu8(_mm256_add_epi8(v1, add_c6), six), f0);
+    let d2 = _mm256_sub_epi8(_mm256_subs_epu8(_mm256_add_epi8(v2, add_c6), six), f0);
+
+    // Letters 'A'..'F'/'a'..'f' → 10..15, others > 15.
+    let a1 = _mm256_adds_epu8(_mm256_sub_epi8(_mm256_and_si256(v1, df), big_a), ten);
+    let a2 = _mm256_adds_epu8(_mm256_sub_epi8(_mm256_and_si256(v2, df), big_a), ten);
+
+    // Valid nibble wins (0..15), invalid stays > 15.
+    let n1 = _mm256_min_epu8(d1, a1);
+    let n2 = _mm256_min_epu8(d2, a2);
+
+    // Validate: saturating add sets MSB if nibble > 15.
+    let c1 = _mm256_adds_epu8(n1, check_bias);
+    let c2 = _mm256_adds_epu8(n2, check_bias);
+    if _mm256_movemask_epi8(_mm256_or_si256(c1, c2)) != 0 {
+        return None;
+    }
+
+    // Merge nibble pairs: hi * 16 + lo.
+    let b1 = _mm256_maddubs_epi16(n1, weights);
+    let b2 = _mm256_maddubs_epi16(n2, weights);
+    let packed = _mm256_packus_epi16(b1, b2);
+    Some(_mm256_permute4x64_epi64(packed, 0b11_01_10_00))
+}
+
+#[target_feature(enable = "avx2")]
+unsafe fn encode_utf16_avx2<const UPPER: bool>(input: &[u8], output: &mut [u16]) {
+    generic::encode_utf16_unaligned_chunks(
+        input,
+        output,
+        |chunk| {
+            let [a, b] = encode_chunk_avx2::<UPPER>(chunk);
+            [
+                _mm256_cvtepu8_epi16(_mm256_castsi256_si128(a)),
+                _mm256_cvtepu8_epi16(_mm256_extracti128_si256(a, 1)),
+                _mm256_cvtepu8_epi16(_mm256_castsi256_si128(b)),
+                _mm256_cvtepu8_epi16(_mm256_extracti128_si256(b, 1)),
+            ]
+        },
+        |remainder, out| encode_utf16_ssse3::<UPPER>(remainder, out),
+    );
+}
+
+#[target_feature(enable = "ssse3")]
+unsafe fn encode_utf16_ssse3<const UPPER: bool>(input: &[u8], output: &mut [u16]) {
+    let zero = _mm_setzero_si128();
+    generic::encode_utf16_unaligned_chunks(
+        input,
+        output,
+        |chunk| {
+            let [a, b] = encode_chunk_ssse3::<UPPER>(chunk);
+            [
+                _mm_unpacklo_epi8(a, zero),
+                _mm_unpackhi_epi8(a, zero),
+                _mm_unpacklo_epi8(b, zero),
+                _mm_unpackhi_epi8(b, zero),
+            ]
+        },
+        generic::encode_utf16::<UPPER>,
+    );
+}
+
+/// Narrowing interleaves the 128-bit lanes of the two inputs, which does not matter for checking.
+#[target_feature(enable = "avx2")]
+unsafe fn check_utf16_avx2(input: &[u16]) -> bool {
+    generic::check_utf16_unaligned_chunks(
+        input,
+        |[v1, v2]: [__m256i; 2]| check_chunk_avx2(_mm256_packus_epi16(v1, v2)),
+        |remainder| check_utf16_sse2(remainder),
+    )
+}
+
+#[target_feature(enable = "sse2")]
+unsafe fn check_utf16_sse2(input: &[u16]) -> bool {
+    generic::check_utf16_unaligned_chunks(
+        input,
+        |[v1, v2]: [__m128i; 2]| check_chunk_sse2(_mm_packus_epi16(v1, v2)),
+        generic::check_utf16,
+    )
+}
+
+#[target_feature(enable = "avx2")]
+unsafe fn decode_utf16_checked_avx2(input: &[u16], output: &mut [u8]) -> bool {
+    generic::decode_utf16_checked_unaligned_chunks(
+        input,
+        output,
+        |[v1, v2, v3, v4]: [__m256i; 4]| {
+            let a = _mm256_permute4x64_epi64(_mm256_packus_epi16(v1, v2), 0b11_01_10_00);
+            let b = _mm256_permute4x64_epi64(_mm256_packus_epi16(v3, v4), 0b11_01_10_00);
+            decode_chunk_checked_avx2([a, b])
+        },
+        generic::decode_utf16_checked,
+    )
+}
+
+/// Fixed-size encode: a single AVX2 register round-trip per 32 bytes, without length checks or
+/// remainder handling.
+#[inline]
+pub(crate) unsafe fn encode_fixed<const N: usize, const UPPER: bool>(
+    input: &[u8; N],
+    output: &mut [u8],
+) -> bool {
+    if super::kernels().backend != Backend::Avx2 {
+        return false;
+    }
+    encode_fixed_avx2::<N, UPPER>(input, output);
+    true
+}
+
+#[target_feature(enable = "avx2")]
+unsafe fn encode_fixed_avx2<const N: usize, const UPPER: bool>(input: &[u8; N], output: &mut [u8]) {
+    generic::encode_fixed_chunks(input, output, |chunk| encode_chunk_avx2::<UPPER>(chunk));
+}
+
+/// Fixed-size decode, see [`encode_fixed`].
+#[inline]
+pub(crate) unsafe fn decode_fixed<const N: usize>(
+    input: &[u8],
+    output: &mut [u8; N],
+) -> Option<bool> {
+    if super::kernels().backend != Backend::Avx2 {
+        return None;
+    }
+    Some(decode_fixed_avx2(input, output))
+}
+
+#[target_feature(enable = "avx2")]
+unsafe fn decode_fixed_avx2<const N: usize>(input: &[u8], output: &mut [u8; N]) -> bool {
+    generic::decode_fixed_chunks(input, output, |chunk| decode_chunk_checked_avx2(chunk))
+}
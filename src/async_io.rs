@@ -0,0 +1,429 @@
+//! Asynchronous hex encoding and decoding adapters.
+//!
+//! [`HexEncoder`] wraps a writer and writes the hex encoding of everything written to it, and
+//! [`HexDecoder`] wraps a reader of hex characters and reads the decoded bytes. Both buffer
+//! internally and run the same SIMD kernels as [`encode`](crate::encode) and
+//! [`decode`](crate::decode) on whole buffers at a time.
+//!
+//! They implement the `AsyncRead` and `AsyncWrite` traits of `tokio` and `futures-io`, behind
+//! features of the same names.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "tokio")]
+//! # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+//! use const_hex::async_io::{HexDecoder, HexEncoder};
+//! use tokio::io::{AsyncReadExt, AsyncWriteExt};
+//!
+//! let mut encoder = HexEncoder::new(Vec::new());
+//! encoder.write_all(b"kiwi").await?;
+//! encoder.shutdown().await?;
+//! assert_eq!(encoder.get_ref(), b"6b697769");
+//!
+//! let mut decoder = HexDecoder::new(&b"0x6b697769"[..]);
+//! let mut bytes = Vec::new();
+//! decoder.read_to_end(&mut bytes).await?;
+//! assert_eq!(bytes, b"kiwi");
+//! # std::io::Result::Ok(())
+//! # }).unwrap();
+//! ```
+
+use crate::{arch, decode_checked, offset_error, FromHexError};
+use alloc::{boxed::Box, vec};
+use core::fmt;
+use core::pin::Pin;
+use core::task::{ready, Context, Poll};
+use std::io;
+
+/// The size of the internal buffers, in hex characters.
+const BUF_LEN: usize = 8 * 1024;
+
+/// An asynchronous writer that hex-encodes all data written to it into an inner writer.
+///
+/// Data is encoded into an internal buffer, which is written to the inner writer on the next
+/// write, on flush, and on shutdown. Remember to flush or shut down the encoder before dropping
+/// it, or the buffered data will be lost.
+pub struct HexEncoder<W> {
+    inner: W,
+    state: EncodeState,
+}
+
+impl<W: fmt::Debug> fmt::Debug for HexEncoder<W> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HexEncoder")
+            .field("inner", &self.inner)
+            .field("upper", &self.state.upper)
+            .field("buffered", &(self.state.len - self.state.pos))
+            .finish()
+    }
+}
+
+impl<W> HexEncoder<W> {
+    /// Creates a new encoder that writes *lower* hex characters to `inner`.
+    #[inline]
+    pub fn new(inner: W) -> Self {
+        Self::with_case(inner, false)
+    }
+
+    /// Creates a new encoder that writes *upper* hex characters to `inner`.
+    #[inline]
+    pub fn new_upper(inner: W) -> Self {
+        Self::with_case(inner, true)
+    }
+
+    fn with_case(inner: W, upper: bool) -> Self {
+        Self {
+            inner,
+            state: EncodeState {
+                buf: vec![0; BUF_LEN].into_boxed_slice(),
+                pos: 0,
+                len: 0,
+                upper,
+            },
+        }
+    }
+
+    /// Returns a reference to the inner writer.
+    #[inline]
+    pub const fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner writer.
+    ///
+    /// Writing to the inner writer directly may interleave with buffered data.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Returns a pinned mutable reference to the inner writer.
+    #[inline]
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut W> {
+        self.project().0
+    }
+
+    /// Consumes the encoder, returning the inner writer.
+    ///
+    /// Any buffered data that has not been flushed yet is lost.
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    fn project(self: Pin<&mut Self>) -> (Pin<&mut W>, &mut EncodeState) {
+        // SAFETY: `inner` is structurally pinned and never moved out of a pinned `Self`; the
+        // state is not.
+        unsafe {
+            let this = self.get_unchecked_mut();
+            (Pin::new_unchecked(&mut this.inner), &mut this.state)
+        }
+    }
+}
+
+struct EncodeState {
+    buf: Box<[u8]>,
+    /// The encoded characters in `buf[pos..len]` have not been written yet.
+    pos: usize,
+    len: usize,
+    upper: bool,
+}
+
+impl EncodeState {
+    /// Writes all buffered characters with `write`.
+    fn poll_drain(
+        &mut self,
+        mut write: impl FnMut(&[u8]) -> Poll<io::Result<usize>>,
+    ) -> Poll<io::Result<()>> {
+        while self.pos < self.len {
+            let n = ready!(write(&self.buf[self.pos..self.len]))?;
+            if n == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+            self.pos += n;
+        }
+        self.pos = 0;
+        self.len = 0;
+        Poll::Ready(Ok(()))
+    }
+
+    /// Encodes as much of `input` as fits into the empty buffer, returning the number of bytes
+    /// encoded.
+    fn fill(&mut self, input: &[u8]) -> usize {
+        debug_assert_eq!(self.len, 0);
+        let input = &input[..input.len().min(self.buf.len() / 2)];
+        let output = &mut self.buf[..input.len() * 2];
+        // SAFETY: The output is exactly twice as long as the input.
+        unsafe {
+            if self.upper {
+                arch::encode::<true>(input, output);
+            } else {
+                arch::encode::<false>(input, output);
+            }
+        }
+        self.len = input.len() * 2;
+        input.len()
+    }
+}
+
+/// An asynchronous reader that decodes the hex characters read from an inner reader.
+///
+/// A leading `0x` or `0X` prefix at the start of the stream is skipped. Both upper and lower case
+/// characters are accepted.
+///
+/// An invalid character fails with an [`io::Error`] of kind
+/// [`InvalidData`](io::ErrorKind::InvalidData), which wraps a [`FromHexError`] whose index is the
+/// offset of the character in the stream, including the prefix. All the bytes before the invalid
+/// character are returned first. A trailing unpaired character fails with
+/// [`FromHexError::OddLength`] at the end of the stream. Once an error has been returned, every
+/// following read returns it again.
+pub struct HexDecoder<R> {
+    inner: R,
+    state: DecodeState,
+}
+
+impl<R: fmt::Debug> fmt::Debug for HexDecoder<R> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HexDecoder")
+            .field("inner", &self.inner)
+            .field("offset", &self.state.offset)
+            .field("error", &self.state.error)
+            .finish()
+    }
+}
+
+impl<R> HexDecoder<R> {
+    /// Creates a new decoder that reads hex characters from `inner`.
+    #[inline]
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            state: DecodeState {
+                buf: vec![0; BUF_LEN].into_boxed_slice(),
+                start: 0,
+                end: 0,
+                offset: 0,
+                error: None,
+            },
+        }
+    }
+
+    /// Returns a reference to the inner reader.
+    #[inline]
+    pub const fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner reader.
+    ///
+    /// Reading from the inner reader directly skips over characters that have not been buffered
+    /// yet.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Returns a pinned mutable reference to the inner reader.
+    #[inline]
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut R> {
+        self.project().0
+    }
+
+    /// Returns the offset in the stream of the next hex character to be decoded.
+    #[inline]
+    pub const fn offset(&self) -> usize {
+        self.state.offset
+    }
+
+    /// Consumes the decoder, returning the inner reader.
+    ///
+    /// Any buffered characters that have not been decoded yet are lost.
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn project(self: Pin<&mut Self>) -> (Pin<&mut R>, &mut DecodeState) {
+        // SAFETY: `inner` is structurally pinned and never moved out of a pinned `Self`; the
+        // state is not.
+        unsafe {
+            let this = self.get_unchecked_mut();
+            (Pin::new_unchecked(&mut this.inner), &mut this.state)
+        }
+    }
+}
+
+struct DecodeState {
+    buf: Box<[u8]>,
+    /// The characters in `buf[start..end]` have been read but not decoded yet.
+    start: usize,
+    end: usize,
+    /// The stream offset of `buf[start]`.
+    offset: usize,
+    error: Option<FromHexError>,
+}
+
+impl DecodeState {
+    /// Decodes into `output`, reading more characters with `read` when fewer than a pair are
+    /// buffered. An unpaired character is carried over to the next read.
+    fn poll_decode(
+        &mut self,
+        output: &mut [u8],
+        mut read: impl FnMut(&mut [u8]) -> Poll<io::Result<usize>>,
+    ) -> Poll<io::Result<usize>> {
+        if let Some(e) = self.error {
+            return Poll::Ready(Err(e.into()));
+        }
+        if output.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        loop {
+            let available = self.end - self.start;
+            if available >= 2 {
+                if self.offset == 0 && matches!(self.buf[self.start..], [b'0', b'x' | b'X', ..]) {
+                    self.start += 2;
+                    self.offset += 2;
+                    continue;
+                }
+                return Poll::Ready(self.decode(output));
+            }
+
+            // Move the unpaired character, if any, to the front.
+            self.buf.copy_within(self.start..self.end, 0);
+            self.start = 0;
+            self.end = available;
+            let n = ready!(read(&mut self.buf[available..]))?;
+            if n == 0 {
+                if available != 0 {
+                    return Poll::Ready(Err(self.fail(FromHexError::OddLength)));
+                }
+                return Poll::Ready(Ok(0));
+            }
+            self.end += n;
+        }
+    }
+
+    /// Decodes as many buffered pairs as fit into `output`.
+    fn decode(&mut self, output: &mut [u8]) -> io::Result<usize> {
+        let n = ((self.end - self.start) / 2).min(output.len());
+        let input = &self.buf[self.start..self.start + n * 2];
+        // SAFETY: The output is exactly half as long as the input.
+        let n = match unsafe { decode_checked(input, &mut output[..n]) } {
+            Ok(()) => n,
+            Err(e) => {
+                let e = offset_error(e, self.offset);
+                // Return the valid pairs before the invalid character first.
+                let valid = match e {
+                    FromHexError::InvalidHexCharacter { index, .. } => (index - self.offset) / 2,
+                    _ => 0,
+                };
+                if valid == 0 {
+                    return Err(self.fail(e));
+                }
+                self.error = Some(e);
+                // SAFETY: As above; the pairs before the invalid character are valid.
+                let _ = unsafe { decode_checked(&input[..valid * 2], &mut output[..valid]) };
+                valid
+            }
+        };
+        self.start += n * 2;
+        self.offset += n * 2;
+        Ok(n)
+    }
+
+    fn fail(&mut self, e: FromHexError) -> io::Error {
+        self.error = Some(e);
+        e.into()
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<W: tokio::io::AsyncWrite> tokio::io::AsyncWrite for HexEncoder<W> {
+    #[inline]
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let (mut inner, state) = self.project();
+        ready!(state.poll_drain(|b| inner.as_mut().poll_write(cx, b)))?;
+        Poll::Ready(Ok(state.fill(buf)))
+    }
+
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let (mut inner, state) = self.project();
+        ready!(state.poll_drain(|b| inner.as_mut().poll_write(cx, b)))?;
+        inner.poll_flush(cx)
+    }
+
+    #[inline]
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let (mut inner, state) = self.project();
+        ready!(state.poll_drain(|b| inner.as_mut().poll_write(cx, b)))?;
+        inner.poll_shutdown(cx)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<R: tokio::io::AsyncRead> tokio::io::AsyncRead for HexDecoder<R> {
+    #[inline]
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let (mut inner, state) = self.project();
+        let n = ready!(state.poll_decode(buf.initialize_unfilled(), |b| {
+            let mut b = tokio::io::ReadBuf::new(b);
+            ready!(inner.as_mut().poll_read(cx, &mut b))?;
+            Poll::Ready(Ok(b.filled().len()))
+        }))?;
+        buf.advance(n);
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl<W: futures_io::AsyncWrite> futures_io::AsyncWrite for HexEncoder<W> {
+    #[inline]
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let (mut inner, state) = self.project();
+        ready!(state.poll_drain(|b| inner.as_mut().poll_write(cx, b)))?;
+        Poll::Ready(Ok(state.fill(buf)))
+    }
+
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let (mut inner, state) = self.project();
+        ready!(state.poll_drain(|b| inner.as_mut().poll_write(cx, b)))?;
+        inner.poll_flush(cx)
+    }
+
+    #[inline]
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let (mut inner, state) = self.project();
+        ready!(state.poll_drain(|b| inner.as_mut().poll_write(cx, b)))?;
+        inner.poll_close(cx)
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl<R: futures_io::AsyncRead> futures_io::AsyncRead for HexDecoder<R> {
+    #[inline]
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let (mut inner, state) = self.project();
+        state.poll_decode(buf, |b| inner.as_mut().poll_read(cx, b))
+    }
+}
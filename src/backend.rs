@@ -0,0 +1,108 @@
+use crate::arch;
+use core::fmt;
+
+/// An implementation backend used for encoding, decoding and checking.
+///
+/// See [`backend`] for more details.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Backend {
+    /// The portable scalar implementation.
+    Generic,
+    /// `x86`/`x86-64` SSE2. Only used for checking.
+    Sse2,
+    /// `x86`/`x86-64` SSSE3.
+    Ssse3,
+    /// `x86`/`x86-64` AVX2.
+    Avx2,
+    /// `aarch64` NEON.
+    Neon,
+    /// `wasm32` SIMD128.
+    Simd128,
+    /// The nightly `portable-simd` implementation.
+    PortableSimd,
+}
+
+impl fmt::Display for Backend {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Backend {
+    /// Returns the name of the backend.
+    #[inline]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Generic => "generic",
+            Self::Sse2 => "sse2",
+            Self::Ssse3 => "ssse3",
+            Self::Avx2 => "avx2",
+            Self::Neon => "neon",
+            Self::Simd128 => "simd128",
+            Self::PortableSimd => "portable-simd",
+        }
+    }
+
+    /// Returns `true` if this backend was compiled in and is supported by the
+    /// current CPU.
+    #[inline]
+    pub fn is_available(self) -> bool {
+        arch::find(self).is_some()
+    }
+}
+
+/// Returns the backend that is currently used for encoding, decoding and
+/// checking at runtime.
+///
+/// The best available backend is detected once, on first use, and cached for
+/// subsequent calls, unless it is enabled at compile time. It can be overridden
+/// with [`force_backend`].
+///
+/// Note that this does not affect `const fn`s, which always use the generic
+/// implementation.
+///
+/// # Examples
+///
+/// ```
+/// let backend = const_hex::backend();
+/// println!("using {backend}");
+/// assert!(backend.is_available());
+/// ```
+#[inline]
+pub fn backend() -> Backend {
+    arch::kernels().backend
+}
+
+/// Forces the given backend to be used at runtime, overriding the detected one.
+///
+/// This is intended for testing and benchmarking. The override is global and
+/// applies to all threads.
+///
+/// Returns `false` and leaves the active backend unchanged if `backend` was not
+/// compiled in or is not supported by the current CPU. On targets without atomic
+/// pointers, only the detected backend can be forced.
+///
+/// # Examples
+///
+/// ```
+/// if const_hex::force_backend(const_hex::Backend::Generic) {
+///     assert_eq!(const_hex::backend(), const_hex::Backend::Generic);
+/// }
+/// let mut buf = [0; 4];
+/// assert_eq!(const_hex::encode_to_str([0xde, 0xad], &mut buf).unwrap(), "dead");
+///
+/// const_hex::reset_backend();
+/// ```
+#[inline]
+pub fn force_backend(backend: Backend) -> bool {
+    arch::force(backend)
+}
+
+/// Resets the backend used at runtime to the detected one, undoing
+/// [`force_backend`].
+#[inline]
+pub fn reset_backend() {
+    arch::reset();
+}
@@ -0,0 +1,157 @@
+//! Direct access to every backend that was compiled in, for differential testing.
+//!
+//! **NOTE:** This module is not part of the public API and is exempt from semver.
+//! Use [`force_backend`](crate::force_backend) to select a backend instead.
+
+use crate::{arch, Backend};
+use core::fmt;
+
+/// The kernels of a single backend.
+#[derive(Clone, Copy)]
+pub struct Implementation(&'static arch::Kernels);
+
+impl fmt::Debug for Implementation {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Implementation")
+            .field(&self.backend())
+            .finish()
+    }
+}
+
+impl Implementation {
+    /// Returns the backend of this implementation.
+    #[inline]
+    pub const fn backend(self) -> Backend {
+        self.0.backend
+    }
+
+    /// Encodes `input` into `output`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `output.len() != input.len() * 2`.
+    #[inline]
+    pub fn encode<const UPPER: bool>(self, input: &[u8], output: &mut [u8]) {
+        assert_eq!(output.len(), input.len() * 2);
+        // SAFETY: Only available kernels are exposed, and the length is checked above.
+        unsafe { (self.0.encode[UPPER as usize])(input, output) }
+    }
+
+    /// Encodes `input` into `output` in reverse byte order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `output.len() != input.len() * 2`.
+    #[inline]
+    pub fn encode_reversed<const UPPER: bool>(self, input: &[u8], output: &mut [u8]) {
+        assert_eq!(output.len(), input.len() * 2);
+        // SAFETY: Only available kernels are exposed, and the length is checked above.
+        unsafe { (self.0.encode_reversed[UPPER as usize])(input, output) }
+    }
+
+    /// Returns `true` if `input` only contains valid hex characters. Does not strip any prefix.
+    #[inline]
+    pub fn check(self, input: &[u8]) -> bool {
+        // SAFETY: Only available kernels are exposed.
+        unsafe { (self.0.check)(input) }
+    }
+
+    /// Decodes `input` into `output`, returning `false` if `input` is not valid hex.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `input.len() != output.len() * 2`.
+    #[inline]
+    pub fn decode_checked(self, input: &[u8], output: &mut [u8]) -> bool {
+        assert_eq!(input.len(), output.len() * 2);
+        // SAFETY: Only available kernels are exposed, and the length is checked above.
+        unsafe { (self.0.decode_checked)(input, output) }
+    }
+
+    /// Decodes `input` into `output` without validating it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `input.len() != output.len() * 2`.
+    ///
+    /// # Safety
+    ///
+    /// `input` must be valid hex, see [`check`](Self::check).
+    #[inline]
+    pub unsafe fn decode_unchecked(self, input: &[u8], output: &mut [u8]) {
+        assert_eq!(input.len(), output.len() * 2);
+        (self.0.decode_unchecked)(input, output)
+    }
+
+    /// Splits `input` into nibbles in `output`, high nibble first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `output.len() != input.len() * 2`.
+    #[inline]
+    pub fn unpack(self, input: &[u8], output: &mut [u8]) {
+        assert_eq!(output.len(), input.len() * 2);
+        // SAFETY: Only available kernels are exposed, and the length is checked above.
+        unsafe { (self.0.unpack)(input, output) }
+    }
+
+    /// Joins pairs of nibbles in `input` into `output`, returning `false` if any nibble is greater
+    /// than `0xf`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `input.len() != output.len() * 2`.
+    #[inline]
+    pub fn pack(self, input: &[u8], output: &mut [u8]) -> bool {
+        assert_eq!(input.len(), output.len() * 2);
+        // SAFETY: Only available kernels are exposed, and the length is checked above.
+        unsafe { (self.0.pack)(input, output) }
+    }
+
+    /// Encodes `input` into UTF-16 `output`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `output.len() != input.len() * 2`.
+    #[inline]
+    pub fn encode_utf16<const UPPER: bool>(self, input: &[u8], output: &mut [u16]) {
+        assert_eq!(output.len(), input.len() * 2);
+        // SAFETY: Only available kernels are exposed, and the length is checked above.
+        unsafe { (self.0.encode_utf16[UPPER as usize])(input, output) }
+    }
+
+    /// Returns `true` if UTF-16 `input` only contains valid hex characters. Does not strip any
+    /// prefix.
+    #[inline]
+    pub fn check_utf16(self, input: &[u16]) -> bool {
+        // SAFETY: Only available kernels are exposed.
+        unsafe { (self.0.check_utf16)(input) }
+    }
+
+    /// Decodes UTF-16 `input` into `output`, returning `false` if `input` is not valid hex.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `input.len() != output.len() * 2`.
+    #[inline]
+    pub fn decode_utf16_checked(self, input: &[u16], output: &mut [u8]) -> bool {
+        assert_eq!(input.len(), output.len() * 2);
+        // SAFETY: Only available kernels are exposed, and the length is checked above.
+        unsafe { (self.0.decode_utf16_checked)(input, output) }
+    }
+}
+
+/// Returns every backend that was compiled in and is supported by the current CPU, in order of
+/// preference. The last one is always [`Backend::Generic`].
+#[inline]
+pub fn all() -> impl Iterator<Item = Implementation> {
+    arch::available().map(Implementation)
+}
+
+/// Returns the implementation of `backend`, if it was compiled in and is supported by the current
+/// CPU.
+#[inline]
+pub fn get(backend: Backend) -> Option<Implementation> {
+    arch::find(backend).map(Implementation)
+}
@@ -0,0 +1,510 @@
+//! Encoding and decoding of many small inputs at once.
+//!
+//! Encoding a large number of short values one at a time, such as 32-byte hashes, leaves most of
+//! each vector register unused and, when encoding to [`String`]s, allocates once per value. The
+//! functions in this module instead lay the items out contiguously and run the SIMD kernels over
+//! all of them at once, so that each register holds bytes of several items.
+//!
+//! Results are written either into a single [`Batch`] arena, into a caller-provided slice, or into
+//! a `Vec` of [`Buffer`]s.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "alloc")] {
+//! use const_hex::batch;
+//!
+//! let hashes = [[0x01; 4], [0xab; 4], [0xff; 4]];
+//!
+//! let encoded = batch::encode(&hashes);
+//! assert_eq!(encoded.len(), 3);
+//! assert_eq!(encoded.as_str(), "01010101ababababffffffff");
+//! assert_eq!(&encoded[1], "abababab");
+//!
+//! let decoded: Vec<[u8; 4]> = batch::decode(&["01010101", "0xabababab", "FFFFFFFF"]).unwrap();
+//! assert_eq!(decoded, hashes);
+//! # }
+//! ```
+
+use crate::{decode_to_slice_inner, strip_prefix, unlikely, FromHexError};
+use core::{fmt, slice};
+
+#[cfg(feature = "alloc")]
+use crate::{arch, Buffer};
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
+#[cfg(feature = "alloc")]
+use core::ops::{Index, Range};
+
+/// The number of hex characters staged on the stack when the items of a batch are not contiguous.
+const STAGE_LEN: usize = 512;
+
+/// A contiguous string of hex-encoded items, along with the offset at which each item ends.
+///
+/// Returned by [`encode`], [`encode_upper`] and [`encode_iter`].
+///
+/// # Examples
+///
+/// ```
+/// use const_hex::batch;
+///
+/// let encoded = batch::encode_iter(["", "ab", "\x01\x02\x03"]);
+/// assert_eq!(encoded.as_str(), "6162010203");
+/// assert_eq!(encoded.ends(), [0, 4, 10]);
+/// assert_eq!(encoded.iter().collect::<Vec<_>>(), ["", "6162", "010203"]);
+/// assert_eq!(encoded.range(2), Some(4..10));
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Batch {
+    hex: String,
+    ends: Vec<usize>,
+}
+
+#[cfg(feature = "alloc")]
+impl Batch {
+    /// Creates a new empty batch.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            hex: String::new(),
+            ends: Vec::new(),
+        }
+    }
+
+    /// Returns the number of items in the batch.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.ends.len()
+    }
+
+    /// Returns `true` if the batch contains no items.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.ends.is_empty()
+    }
+
+    /// Returns the hex encoding of the item at `index`, or `None` if it is out of bounds.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.range(index).map(|range| &self.hex[range])
+    }
+
+    /// Returns the range of the item at `index` within [`as_str`](Self::as_str), or `None` if it is
+    /// out of bounds.
+    #[inline]
+    pub fn range(&self, index: usize) -> Option<Range<usize>> {
+        let end = *self.ends.get(index)?;
+        let start = if index == 0 { 0 } else { self.ends[index - 1] };
+        Some(start..end)
+    }
+
+    /// Returns an iterator over the hex encodings of the items.
+    #[inline]
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &str> + ExactSizeIterator {
+        (0..self.len()).map(move |i| &self[i])
+    }
+
+    /// Returns the offset at which each item ends within [`as_str`](Self::as_str).
+    ///
+    /// Each item starts where the previous one ends; the first item starts at `0`.
+    #[inline]
+    pub fn ends(&self) -> &[usize] {
+        &self.ends
+    }
+
+    /// Returns the hex encodings of all items, concatenated.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.hex
+    }
+
+    /// Consumes the batch, returning the concatenated hex encodings and the end offsets.
+    #[inline]
+    pub fn into_parts(self) -> (String, Vec<usize>) {
+        (self.hex, self.ends)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Index<usize> for Batch {
+    type Output = str;
+
+    #[inline]
+    #[track_caller]
+    fn index(&self, index: usize) -> &str {
+        match self.get(index) {
+            Some(s) => s,
+            None => panic!(
+                "index {index} out of bounds for batch of length {}",
+                self.len()
+            ),
+        }
+    }
+}
+
+/// An error that occurred while decoding one of the items of a batch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Error {
+    item: usize,
+    error: FromHexError,
+}
+
+impl Error {
+    /// Returns the index of the item that failed to decode.
+    #[inline]
+    pub const fn item(&self) -> usize {
+        self.item
+    }
+
+    /// Returns the error of the item. As with [`decode`](crate::decode), the index of an invalid
+    /// character is relative to the start of the item without its `0x` prefix.
+    #[inline]
+    pub const fn error(&self) -> &FromHexError {
+        &self.error
+    }
+}
+
+#[cfg(feature = "core-error")]
+impl core::error::Error for Error {}
+#[cfg(all(feature = "std", not(feature = "core-error")))]
+impl std::error::Error for Error {}
+
+#[cfg(feature = "std")]
+impl From<Error> for std::io::Error {
+    #[inline]
+    fn from(e: Error) -> Self {
+        Self::new(std::io::ErrorKind::InvalidData, e)
+    }
+}
+
+impl fmt::Display for Error {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "item {}: {}", self.item, self.error)
+    }
+}
+
+/// Encodes each array of `inputs` as a *lower* hex string, into one contiguous [`Batch`].
+///
+/// # Examples
+///
+/// ```
+/// let encoded = const_hex::batch::encode(&[[0xde, 0xad], [0xbe, 0xef]]);
+/// assert_eq!(encoded.iter().collect::<Vec<_>>(), ["dead", "beef"]);
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn encode<const N: usize>(inputs: &[[u8; N]]) -> Batch {
+    encode_arrays_inner::<N, false>(inputs)
+}
+
+/// Encodes each array of `inputs` as an *upper* hex string, into one contiguous [`Batch`].
+///
+/// # Examples
+///
+/// ```
+/// let encoded = const_hex::batch::encode_upper(&[[0xde, 0xad], [0xbe, 0xef]]);
+/// assert_eq!(encoded.iter().collect::<Vec<_>>(), ["DEAD", "BEEF"]);
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn encode_upper<const N: usize>(inputs: &[[u8; N]]) -> Batch {
+    encode_arrays_inner::<N, true>(inputs)
+}
+
+/// Encodes each item of `inputs` as a *lower* hex string, into one contiguous [`Batch`].
+///
+/// The items may have different lengths.
+///
+/// # Examples
+///
+/// ```
+/// let encoded = const_hex::batch::encode_iter(vec![vec![1], vec![2, 3], vec![]]);
+/// assert_eq!(encoded.iter().collect::<Vec<_>>(), ["01", "0203", ""]);
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn encode_iter<I>(inputs: I) -> Batch
+where
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+{
+    encode_iter_inner::<false>(inputs.into_iter())
+}
+
+/// Encodes each item of `inputs` as an *upper* hex string, into one contiguous [`Batch`].
+///
+/// The items may have different lengths.
+///
+/// # Examples
+///
+/// ```
+/// let encoded = const_hex::batch::encode_iter_upper(["\u{ab}", "\u{cd}"]);
+/// assert_eq!(encoded.iter().collect::<Vec<_>>(), ["C2AB", "C38D"]);
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn encode_iter_upper<I>(inputs: I) -> Batch
+where
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+{
+    encode_iter_inner::<true>(inputs.into_iter())
+}
+
+/// Encodes each array of `inputs` as a *lower* hex string, writing the results one after another
+/// into `output`.
+///
+/// Item `i` is written to `output[i * N * 2..(i + 1) * N * 2]`.
+///
+/// # Errors
+///
+/// If the output buffer is not exactly `inputs.len() * N * 2` bytes long.
+///
+/// # Examples
+///
+/// ```
+/// let mut output = [0u8; 8];
+/// const_hex::batch::encode_to_slice(&[[0xde, 0xad], [0xbe, 0xef]], &mut output).unwrap();
+/// assert_eq!(&output, b"deadbeef");
+/// ```
+#[inline]
+pub fn encode_to_slice<const N: usize>(
+    inputs: &[[u8; N]],
+    output: &mut [u8],
+) -> Result<(), FromHexError> {
+    crate::encode_to_slice_inner::<false>(flatten(inputs), output)
+}
+
+/// Encodes each array of `inputs` as an *upper* hex string, writing the results one after another
+/// into `output`.
+///
+/// Item `i` is written to `output[i * N * 2..(i + 1) * N * 2]`.
+///
+/// # Errors
+///
+/// If the output buffer is not exactly `inputs.len() * N * 2` bytes long.
+///
+/// # Examples
+///
+/// ```
+/// let mut output = [0u8; 8];
+/// const_hex::batch::encode_to_slice_upper(&[[0xde, 0xad], [0xbe, 0xef]], &mut output).unwrap();
+/// assert_eq!(&output, b"DEADBEEF");
+/// ```
+#[inline]
+pub fn encode_to_slice_upper<const N: usize>(
+    inputs: &[[u8; N]],
+    output: &mut [u8],
+) -> Result<(), FromHexError> {
+    crate::encode_to_slice_inner::<true>(flatten(inputs), output)
+}
+
+/// Encodes each array of `inputs` as a *lower* hex string, into its own [`Buffer`].
+///
+/// # Examples
+///
+/// ```
+/// use const_hex::Buffer;
+///
+/// let buffers: Vec<Buffer<2, true>> = const_hex::batch::encode_buffers(&[[0xde, 0xad], [0xbe, 0xef]]);
+/// assert_eq!(buffers[0].as_str(), "0xdead");
+/// assert_eq!(buffers[1].as_str(), "0xbeef");
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn encode_buffers<const N: usize, const PREFIX: bool>(
+    inputs: &[[u8; N]],
+) -> Vec<Buffer<N, PREFIX>> {
+    encode_buffers_inner::<N, PREFIX, false>(inputs)
+}
+
+/// Encodes each array of `inputs` as an *upper* hex string, into its own [`Buffer`].
+///
+/// # Examples
+///
+/// ```
+/// use const_hex::Buffer;
+///
+/// let buffers: Vec<Buffer<2>> = const_hex::batch::encode_buffers_upper(&[[0xde, 0xad], [0xbe, 0xef]]);
+/// assert_eq!(buffers[0].as_str(), "DEAD");
+/// assert_eq!(buffers[1].as_str(), "BEEF");
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn encode_buffers_upper<const N: usize, const PREFIX: bool>(
+    inputs: &[[u8; N]],
+) -> Vec<Buffer<N, PREFIX>> {
+    encode_buffers_inner::<N, PREFIX, true>(inputs)
+}
+
+/// Decodes each hex string of `inputs` into an array of `N` bytes.
+///
+/// Each item may optionally start with a `0x` prefix.
+///
+/// # Errors
+///
+/// If any item is not exactly `N * 2` hex characters long after stripping its prefix, or
+/// contains an invalid character. The error refers to the first such item.
+///
+/// # Examples
+///
+/// ```
+/// use const_hex::{batch, FromHexError};
+///
+/// let decoded: Vec<[u8; 2]> = batch::decode(&["dead", "0xbeef"]).unwrap();
+/// assert_eq!(decoded, [[0xde, 0xad], [0xbe, 0xef]]);
+///
+/// let err = batch::decode::<2, _>(&["dead", "0xbeeg"]).unwrap_err();
+/// assert_eq!(err.item(), 1);
+/// assert_eq!(*err.error(), FromHexError::InvalidHexCharacter { c: 'g', index: 3 });
+/// assert_eq!(err.to_string(), "item 1: invalid character 'g' at position 3");
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn decode<const N: usize, T: AsRef<[u8]>>(inputs: &[T]) -> Result<Vec<[u8; N]>, Error> {
+    let mut output = vec![[0; N]; inputs.len()];
+    decode_to_slice(inputs, &mut output)?;
+    Ok(output)
+}
+
+/// Decodes each hex string of `inputs` into the array at the same index of `output`.
+///
+/// Each item may optionally start with a `0x` prefix.
+///
+/// # Errors
+///
+/// If `inputs` and `output` have different lengths, the error refers to item `0` and is
+/// [`FromHexError::InvalidStringLength`]. Otherwise, see [`decode`].
+///
+/// # Examples
+///
+/// ```
+/// let mut output = [[0u8; 2]; 2];
+/// const_hex::batch::decode_to_slice(&["dead", "beef"], &mut output).unwrap();
+/// assert_eq!(output, [[0xde, 0xad], [0xbe, 0xef]]);
+/// ```
+#[inline]
+pub fn decode_to_slice<const N: usize, T: AsRef<[u8]>>(
+    inputs: &[T],
+    output: &mut [[u8; N]],
+) -> Result<(), Error> {
+    if unlikely(inputs.len() != output.len()) {
+        return Err(Error {
+            item: 0,
+            error: FromHexError::InvalidStringLength,
+        });
+    }
+
+    let per_stage = STAGE_LEN / (N * 2).max(1);
+    if N == 0 || per_stage <= 1 {
+        // Items are large enough to fill the registers on their own.
+        return decode_each(inputs, output, 0);
+    }
+
+    let mut stage = [0u8; STAGE_LEN];
+    for (i, (inputs, output)) in inputs
+        .chunks(per_stage)
+        .zip(output.chunks_mut(per_stage))
+        .enumerate()
+    {
+        let first = i * per_stage;
+        let len = inputs.len() * N * 2;
+        for (input, stage) in inputs.iter().zip(stage.chunks_exact_mut(N * 2)) {
+            let input = input.as_ref();
+            let stripped = strip_prefix(input);
+            if unlikely(input.len() % 2 != 0 || stripped.len() != N * 2) {
+                // Let the per-item path report the error of the first failing item.
+                return decode_each(inputs, output, first);
+            }
+            stage.copy_from_slice(stripped);
+        }
+        // SAFETY: `stage[..len]` holds `N * 2` characters for each of the `output.len()` items.
+        if unsafe { crate::decode_checked(&stage[..len], flatten_mut(output)) }.is_err() {
+            return decode_each(inputs, output, first);
+        }
+    }
+    Ok(())
+}
+
+/// Decodes each item on its own. `first` is the index of the first item in the whole batch.
+#[cold]
+fn decode_each<const N: usize, T: AsRef<[u8]>>(
+    inputs: &[T],
+    output: &mut [[u8; N]],
+    first: usize,
+) -> Result<(), Error> {
+    for (i, (input, output)) in inputs.iter().zip(output).enumerate() {
+        decode_to_slice_inner(input.as_ref(), output).map_err(|error| Error {
+            item: first + i,
+            error,
+        })?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "alloc")]
+fn encode_arrays_inner<const N: usize, const UPPER: bool>(inputs: &[[u8; N]]) -> Batch {
+    let hex = crate::encode_inner::<UPPER, false>(flatten(inputs));
+    let ends = (1..=inputs.len()).map(|i| i * N * 2).collect();
+    Batch { hex, ends }
+}
+
+#[cfg(feature = "alloc")]
+fn encode_iter_inner<const UPPER: bool>(inputs: impl Iterator<Item = impl AsRef<[u8]>>) -> Batch {
+    // Concatenate the raw items so that they are all encoded in a single pass.
+    let mut raw = Vec::new();
+    let mut ends = Vec::with_capacity(inputs.size_hint().0);
+    for input in inputs {
+        raw.extend_from_slice(input.as_ref());
+        ends.push(raw.len() * 2);
+    }
+    let hex = crate::encode_inner::<UPPER, false>(&raw);
+    Batch { hex, ends }
+}
+
+#[cfg(feature = "alloc")]
+fn encode_buffers_inner<const N: usize, const PREFIX: bool, const UPPER: bool>(
+    inputs: &[[u8; N]],
+) -> Vec<Buffer<N, PREFIX>> {
+    let mut buffers = vec![Buffer::<N, PREFIX>::new(); inputs.len()];
+
+    let per_stage = STAGE_LEN / (N * 2).max(1);
+    if N == 0 || per_stage <= 1 {
+        // Items are large enough to fill the registers on their own.
+        for (input, buffer) in inputs.iter().zip(&mut buffers) {
+            if UPPER {
+                buffer.format_upper(input);
+            } else {
+                buffer.format(input);
+            }
+        }
+        return buffers;
+    }
+
+    // Buffers are not contiguous because of their prefixes, so encode into a staging area first.
+    let mut stage = [0u8; STAGE_LEN];
+    for (inputs, buffers) in inputs.chunks(per_stage).zip(buffers.chunks_mut(per_stage)) {
+        let stage = &mut stage[..inputs.len() * N * 2];
+        // SAFETY: `stage` is exactly twice as long as the flattened inputs.
+        unsafe { arch::encode::<UPPER>(flatten(inputs), &mut *stage) };
+        for (hex, buffer) in stage.chunks_exact(N * 2).zip(buffers) {
+            // SAFETY: We only write only ASCII bytes.
+            unsafe { buffer.as_mut_bytes()[PREFIX as usize * 2..].copy_from_slice(hex) };
+        }
+    }
+    buffers
+}
+
+#[inline]
+fn flatten<const N: usize>(arrays: &[[u8; N]]) -> &[u8] {
+    // SAFETY: `[[u8; N]]` has the same layout as `[u8]` with `N` times the length.
+    unsafe { slice::from_raw_parts(arrays.as_ptr().cast(), arrays.len() * N) }
+}
+
+#[inline]
+fn flatten_mut<const N: usize>(arrays: &mut [[u8; N]]) -> &mut [u8] {
+    // SAFETY: `[[u8; N]]` has the same layout as `[u8]` with `N` times the length.
+    unsafe { slice::from_raw_parts_mut(arrays.as_mut_ptr().cast(), arrays.len() * N) }
+}
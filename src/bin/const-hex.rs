@@ -0,0 +1,407 @@
+//! The `const-hex` command-line tool.
+//!
+//! Streams standard input, or a file, to standard output through the crate's encoder and decoder.
+//! Run `const-hex --help` for usage.
+
+use const_hex::engine::{Case, Config, Engine, PrefixMode};
+use const_hex::{FromHexError, HEX_DECODE_LUT, NIL};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::process::ExitCode;
+
+/// The number of input bytes read at a time.
+const CHUNK_LEN: usize = 64 * 1024;
+
+/// The default number of bytes per row of `dump`.
+const DUMP_WRAP: usize = 16;
+
+const USAGE: &str = "\
+Usage: const-hex <COMMAND> [OPTIONS] [FILE]
+
+Reads FILE, or standard input if FILE is missing or `-`, and writes to standard output.
+
+Commands:
+  encode                Encode the input as hex
+  decode                Decode hex input; line breaks are ignored
+  check                 Check that the input is valid hex, without writing anything
+  dump                  Print a hexdump of the input, with offsets and ASCII
+
+Options:
+  -u, --upper           Use upper case letters
+  -p, --prefix          Write a `0x` prefix before the output of `encode` and the offsets of
+                        `dump`; a leading prefix is always accepted when decoding
+  -s, --separator <C>   Separate bytes with the ASCII character C; it is ignored when decoding
+  -w, --wrap <N>        Write at most N bytes per line, or 0 for no limit [default: 0, or 16 for
+                        `dump`]
+  -i, --ignore-garbage  Ignore all non-hex characters when decoding
+  -h, --help            Print this help
+";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Command {
+    Encode,
+    Decode,
+    Check,
+    Dump,
+}
+
+struct Options {
+    command: Command,
+    upper: bool,
+    prefix: bool,
+    separator: Option<u8>,
+    wrap: Option<usize>,
+    ignore_garbage: bool,
+    file: Option<String>,
+}
+
+enum Error {
+    Usage(String),
+    Io(io::Error),
+    Hex(FromHexError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Usage(msg) => write!(f, "{msg}\nTry `const-hex --help` for more information."),
+            Self::Io(e) => e.fmt(f),
+            Self::Hex(e) => e.fmt(f),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<FromHexError> for Error {
+    fn from(e: FromHexError) -> Self {
+        Self::Hex(e)
+    }
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        // Stop quietly when the reader of the output goes away, like `head` does.
+        Err(Error::Io(e)) if e.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(e @ Error::Usage(_)) => {
+            eprintln!("const-hex: {e}");
+            ExitCode::from(2)
+        }
+        Err(e) => {
+            eprintln!("const-hex: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<(), Error> {
+    let opts = match parse_args(std::env::args().skip(1))? {
+        Some(opts) => opts,
+        None => {
+            print!("{USAGE}");
+            return Ok(());
+        }
+    };
+    let mut input: Box<dyn Read> = match opts.file.as_deref() {
+        None | Some("-") => Box::new(io::stdin().lock()),
+        Some(path) => Box::new(File::open(path)?),
+    };
+    let mut output = BufWriter::new(io::stdout().lock());
+    match opts.command {
+        Command::Encode => encode(&opts, &mut input, &mut output)?,
+        Command::Decode => decode(&opts, &mut input, Some(&mut output))?,
+        Command::Check => decode(&opts, &mut input, None)?,
+        Command::Dump => dump(&opts, &mut input, &mut output)?,
+    }
+    output.flush()?;
+    Ok(())
+}
+
+/// Parses the command line, returning `None` if help was requested.
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Options>, Error> {
+    let mut command = None;
+    let mut opts = Options {
+        command: Command::Encode,
+        upper: false,
+        prefix: false,
+        separator: None,
+        wrap: None,
+        ignore_garbage: false,
+        file: None,
+    };
+    while let Some(arg) = args.next() {
+        let (name, inline_value) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+        let mut value = |name: &str| match inline_value.clone().or_else(|| args.next()) {
+            Some(value) => Ok(value),
+            None => Err(Error::Usage(format!("missing value for `{name}`"))),
+        };
+        match name {
+            "-h" | "--help" => return Ok(None),
+            "-u" | "--upper" => opts.upper = true,
+            "-p" | "--prefix" => opts.prefix = true,
+            "-i" | "--ignore-garbage" => opts.ignore_garbage = true,
+            "-s" | "--separator" => {
+                let value = value(name)?;
+                opts.separator = match *value.as_bytes() {
+                    [c] if c.is_ascii() && HEX_DECODE_LUT[c as usize] == NIL => Some(c),
+                    _ => {
+                        return Err(Error::Usage(format!(
+                            "invalid separator {value:?}: must be a single ASCII character that \
+                             is not a hex digit"
+                        )))
+                    }
+                };
+            }
+            "-w" | "--wrap" => {
+                let value = value(name)?;
+                match value.parse() {
+                    Ok(wrap) => opts.wrap = Some(wrap),
+                    Err(_) => return Err(Error::Usage(format!("invalid wrap width {value:?}"))),
+                }
+            }
+            _ if name.starts_with('-') && name != "-" => {
+                return Err(Error::Usage(format!("unknown option `{name}`")))
+            }
+            _ if command.is_none() => {
+                command = Some(match name {
+                    "encode" => Command::Encode,
+                    "decode" => Command::Decode,
+                    "check" => Command::Check,
+                    "dump" => Command::Dump,
+                    _ => return Err(Error::Usage(format!("unknown command `{name}`"))),
+                });
+            }
+            _ if opts.file.is_none() => opts.file = Some(arg),
+            _ => return Err(Error::Usage(format!("unexpected argument `{arg}`"))),
+        }
+    }
+    match command {
+        Some(command) => opts.command = command,
+        None => return Err(Error::Usage("missing command".to_string())),
+    }
+    Ok(Some(opts))
+}
+
+/// Reads into `buf` until it is full or the input ends, returning the number of bytes read.
+fn read_full(input: &mut dyn Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut len = 0;
+    while len < buf.len() {
+        match input.read(&mut buf[len..]) {
+            Ok(0) => break,
+            Ok(n) => len += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(len)
+}
+
+const fn case(opts: &Options) -> Case {
+    if opts.upper {
+        Case::Upper
+    } else {
+        Case::Lower
+    }
+}
+
+fn encode(opts: &Options, input: &mut dyn Read, output: &mut dyn Write) -> Result<(), Error> {
+    let engine = Engine::new(
+        Config::new()
+            .with_case(case(opts))
+            .with_separator(opts.separator),
+    );
+    let wrap = opts.wrap.unwrap_or(0);
+    let mut buf = vec![0; CHUNK_LEN];
+    let mut hex = Vec::new();
+    // The number of bytes on the current line.
+    let mut column = 0;
+    if opts.prefix {
+        output.write_all(b"0x")?;
+    }
+    let mut empty = true;
+    loop {
+        let n = read_full(input, &mut buf)?;
+        if n == 0 {
+            break;
+        }
+        empty = false;
+        let mut chunk = &buf[..n];
+        while !chunk.is_empty() {
+            let len = match wrap {
+                0 => chunk.len(),
+                _ => chunk.len().min(wrap - column),
+            };
+            let (line, rest) = chunk.split_at(len);
+            if let (Some(separator), true) = (opts.separator, column > 0) {
+                output.write_all(&[separator])?;
+            }
+            hex.resize(engine.encoded_len(line.len()), 0);
+            engine.encode_to_slice(line, &mut hex)?;
+            output.write_all(&hex)?;
+            column += len;
+            if column == wrap {
+                output.write_all(b"\n")?;
+                column = 0;
+            }
+            chunk = rest;
+        }
+    }
+    if column > 0 || (opts.prefix && empty) {
+        output.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+/// Decodes the input, writing the bytes to `output` if given. The index of an invalid character
+/// is its offset in the input.
+fn decode(
+    opts: &Options,
+    input: &mut dyn Read,
+    mut output: Option<&mut dyn Write>,
+) -> Result<(), Error> {
+    // Digits are filtered out of the input first, so that pairs can be split across chunks.
+    let engine = Engine::new(Config::new().with_decode_prefix(PrefixMode::Forbidden));
+    let skip = |c: u8| {
+        if opts.ignore_garbage {
+            HEX_DECODE_LUT[c as usize] == NIL
+        } else {
+            c == b'\n' || c == b'\r' || Some(c) == opts.separator
+        }
+    };
+    let mut buf = vec![0; CHUNK_LEN];
+    let mut digits = Vec::with_capacity(CHUNK_LEN + 1);
+    let mut bytes = Vec::new();
+    // The input offset of `buf[0]`.
+    let mut offset = 0;
+    // A digit whose pair continues in the next chunk, and its input offset.
+    let mut pending: Option<(u8, usize)> = None;
+    loop {
+        let n = read_full(input, &mut buf)?;
+        if n == 0 {
+            break;
+        }
+        let chunk = &buf[..n];
+        let start = match chunk {
+            [b'0', b'x' | b'X', ..] if offset == 0 => 2,
+            _ => 0,
+        };
+        // The input offset of the `i`th digit taken from this chunk.
+        let digit_offset = |i: usize| {
+            let position = (start..n).filter(|&j| !skip(chunk[j])).nth(i).unwrap();
+            offset + position
+        };
+
+        digits.clear();
+        digits.extend(pending.map(|(c, _)| c));
+        digits.extend(chunk[start..].iter().copied().filter(|&c| !skip(c)));
+        let even = digits.len() / 2 * 2;
+        bytes.resize(even / 2, 0);
+        if let Err(mut e) = engine.decode_to_slice(&digits[..even], &mut bytes) {
+            if let FromHexError::InvalidHexCharacter { ref mut index, .. } = e {
+                *index = match (pending, *index) {
+                    (Some((_, pending_offset)), 0) => pending_offset,
+                    (Some(_), i) => digit_offset(i - 1),
+                    (None, i) => digit_offset(i),
+                };
+            }
+            return Err(e.into());
+        }
+        if let Some(output) = output.as_mut() {
+            output.write_all(&bytes)?;
+        }
+
+        pending = match (digits.len() % 2, pending) {
+            (0, _) => None,
+            // The pending digit is still unpaired, since this chunk had no digits.
+            (_, Some(pending)) if even == 0 => Some(pending),
+            (_, Some(_)) => Some((digits[even], digit_offset(even - 1))),
+            (_, None) => Some((digits[even], digit_offset(even))),
+        };
+        offset += n;
+    }
+    match pending {
+        Some((c, index)) if HEX_DECODE_LUT[c as usize] == NIL => {
+            Err(FromHexError::InvalidHexCharacter { c: c.into(), index }.into())
+        }
+        Some(_) => Err(FromHexError::OddLength.into()),
+        None => Ok(()),
+    }
+}
+
+fn dump(opts: &Options, input: &mut dyn Read, output: &mut dyn Write) -> Result<(), Error> {
+    let separator = opts.separator.unwrap_or(b' ');
+    let engine = Engine::new(
+        Config::new()
+            .with_case(case(opts))
+            .with_separator(Some(separator)),
+    );
+    let mut line = Vec::new();
+    if opts.wrap == Some(0) {
+        // Without a limit, the whole input is a single row.
+        let mut data = Vec::new();
+        input.read_to_end(&mut data)?;
+        if !data.is_empty() {
+            let width = engine.encoded_len(data.len());
+            dump_row(opts, &engine, 0, &data, width, &mut line)?;
+            output.write_all(&line)?;
+        }
+        return Ok(());
+    }
+
+    let wrap = opts.wrap.unwrap_or(DUMP_WRAP);
+    let width = engine.encoded_len(wrap);
+    let mut buf = vec![0; (CHUNK_LEN / wrap).max(1) * wrap];
+    let mut offset = 0;
+    loop {
+        let n = read_full(input, &mut buf)?;
+        if n == 0 {
+            break;
+        }
+        for row in buf[..n].chunks(wrap) {
+            dump_row(opts, &engine, offset, row, width, &mut line)?;
+            output.write_all(&line)?;
+            offset += row.len();
+        }
+    }
+    Ok(())
+}
+
+/// Formats a row of `dump` at `offset` into `line`, padding the hex to `width` characters.
+fn dump_row(
+    opts: &Options,
+    engine: &Engine,
+    offset: usize,
+    row: &[u8],
+    width: usize,
+    line: &mut Vec<u8>,
+) -> Result<(), Error> {
+    line.clear();
+    if opts.prefix {
+        line.extend_from_slice(b"0x");
+    }
+    if opts.upper {
+        write!(line, "{offset:08X}: ")?;
+    } else {
+        write!(line, "{offset:08x}: ")?;
+    }
+    let hex_start = line.len();
+    line.resize(hex_start + engine.encoded_len(row.len()), 0);
+    engine.encode_to_slice(row, &mut line[hex_start..])?;
+    line.resize(hex_start + width, b' ');
+    line.extend_from_slice(b"  ");
+    line.extend(row.iter().map(|&b| match b {
+        0x20..=0x7e => b,
+        _ => b'.',
+    }));
+    line.push(b'\n');
+    Ok(())
+}
@@ -1,7 +1,11 @@
-use crate::{byte2hex, imp};
+use crate::{arch, byte2hex, invalid_hex_error, offset_error, strip_prefix, FromHexError};
+use core::borrow::Borrow;
+use core::cmp::Ordering;
 use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::Deref;
 use core::slice;
-use core::str;
+use core::str::{self, FromStr};
 
 #[cfg(feature = "alloc")]
 #[allow(unused_imports)]
@@ -16,6 +20,12 @@ use alloc::{string::String, vec::Vec};
 /// Note that this buffer will contain only the prefix, if specified, and null
 /// ('\0') bytes before any formatting is done.
 ///
+/// A buffer is a plain string value: it is [`Copy`], dereferences to [`str`](prim@str),
+/// and compares, orders, hashes and displays exactly like its string
+/// contents. It can be parsed from a hex string with [`FromStr`] or
+/// [`TryFrom<&str>`], and decoded back into bytes with
+/// [`decode`](Self::decode).
+///
 /// # Examples
 ///
 /// ```
@@ -23,9 +33,23 @@ use alloc::{string::String, vec::Vec};
 /// let printed = buffer.format(b"1234");
 /// assert_eq!(printed, "31323334");
 /// ```
+///
+/// ```
+/// use const_hex::Buffer;
+/// use std::collections::HashMap;
+///
+/// let key: Buffer<4, true> = "0x31323334".parse().unwrap();
+/// assert_eq!(key, "0x31323334");
+/// assert_eq!(key.to_string(), "0x31323334");
+/// assert_eq!(key.decode(), Ok(*b"1234"));
+///
+/// let mut map = HashMap::new();
+/// map.insert(key, 1);
+/// assert_eq!(map.get("0x31323334"), Some(&1));
+/// ```
 #[must_use]
 #[repr(C)]
-#[derive(Clone)]
+#[derive(Clone, Copy)]
 pub struct Buffer<const N: usize, const PREFIX: bool = false> {
     // Workaround for Rust issue #76560:
     // https://github.com/rust-lang/rust/issues/76560
@@ -48,10 +72,155 @@ impl<const N: usize, const PREFIX: bool> fmt::Debug for Buffer<N, PREFIX> {
     }
 }
 
-impl<const N: usize, const PREFIX: bool> AsRef<str> for Buffer<N, PREFIX> {
+/// Implements the traits that make a hex buffer behave like a string value,
+/// delegating to its `as_str` method.
+macro_rules! impl_string_value {
+    ($name:ident) => {
+        impl<const N: usize, const PREFIX: bool> fmt::Display for $name<N, PREFIX> {
+            #[inline]
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.pad(self.as_str())
+            }
+        }
+
+        impl<const N: usize, const PREFIX: bool> AsRef<str> for $name<N, PREFIX> {
+            #[inline]
+            fn as_ref(&self) -> &str {
+                self.as_str()
+            }
+        }
+
+        impl<const N: usize, const PREFIX: bool> AsRef<[u8]> for $name<N, PREFIX> {
+            #[inline]
+            fn as_ref(&self) -> &[u8] {
+                self.as_bytes()
+            }
+        }
+
+        impl<const N: usize, const PREFIX: bool> Borrow<str> for $name<N, PREFIX> {
+            #[inline]
+            fn borrow(&self) -> &str {
+                self.as_str()
+            }
+        }
+
+        impl<const N: usize, const PREFIX: bool> Deref for $name<N, PREFIX> {
+            type Target = str;
+
+            #[inline]
+            fn deref(&self) -> &str {
+                self.as_str()
+            }
+        }
+
+        impl<const N: usize, const PREFIX: bool> PartialEq for $name<N, PREFIX> {
+            #[inline]
+            fn eq(&self, other: &Self) -> bool {
+                self.as_str() == other.as_str()
+            }
+        }
+
+        impl<const N: usize, const PREFIX: bool> Eq for $name<N, PREFIX> {}
+
+        impl<const N: usize, const PREFIX: bool> PartialOrd for $name<N, PREFIX> {
+            #[inline]
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl<const N: usize, const PREFIX: bool> Ord for $name<N, PREFIX> {
+            #[inline]
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.as_str().cmp(other.as_str())
+            }
+        }
+
+        impl<const N: usize, const PREFIX: bool> Hash for $name<N, PREFIX> {
+            #[inline]
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.as_str().hash(state)
+            }
+        }
+
+        impl_string_value!(@eq $name, str);
+        impl_string_value!(@eq $name, &str);
+        #[cfg(feature = "alloc")]
+        impl_string_value!(@eq $name, alloc::string::String);
+    };
+    (@eq $name:ident, $t:ty) => {
+        impl<const N: usize, const PREFIX: bool> PartialEq<$t> for $name<N, PREFIX> {
+            #[inline]
+            fn eq(&self, other: &$t) -> bool {
+                self.as_str() == &other[..]
+            }
+        }
+
+        impl<const N: usize, const PREFIX: bool> PartialEq<$name<N, PREFIX>> for $t {
+            #[inline]
+            fn eq(&self, other: &$name<N, PREFIX>) -> bool {
+                &self[..] == other.as_str()
+            }
+        }
+    };
+}
+pub(crate) use impl_string_value;
+
+impl_string_value!(Buffer);
+
+impl<const N: usize, const PREFIX: bool> FromStr for Buffer<N, PREFIX> {
+    type Err = FromHexError;
+
+    /// Parses a hex string of exactly `N` bytes, with or without a `0x` prefix.
+    ///
+    /// The digits are stored as they are, without changing their case. The
+    /// prefix of the buffer is determined by `PREFIX`, not by the input.
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let input = s.as_bytes();
+        if input.len() % 2 != 0 {
+            return Err(FromHexError::OddLength);
+        }
+        let digits = strip_prefix(input);
+        if digits.len() != N * 2 {
+            return Err(FromHexError::InvalidStringLength);
+        }
+        if !arch::check(digits) {
+            let e = unsafe { invalid_hex_error(digits) };
+            return Err(offset_error(e, input.len() - digits.len()));
+        }
+        let mut buffer = Self::new();
+        // SAFETY: The digits are checked to be ASCII above.
+        unsafe { buffer.buffer().copy_from_slice(digits) };
+        Ok(buffer)
+    }
+}
+
+impl<const N: usize, const PREFIX: bool> TryFrom<&str> for Buffer<N, PREFIX> {
+    type Error = FromHexError;
+
+    /// See [`FromStr`].
+    #[inline]
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a, const N: usize, const PREFIX: bool> arbitrary::Arbitrary<'a> for Buffer<N, PREFIX> {
     #[inline]
-    fn as_ref(&self) -> &str {
-        self.as_str()
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let bytes = <[u8; N]>::arbitrary(u)?;
+        Ok(if bool::arbitrary(u)? {
+            Self::new().const_format_upper(&bytes)
+        } else {
+            Self::new().const_format(&bytes)
+        })
+    }
+
+    #[inline]
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        arbitrary::size_hint::and(<[u8; N]>::size_hint(depth), bool::size_hint(depth))
     }
 }
 
@@ -102,16 +271,37 @@ impl<const N: usize, const PREFIX: bool> Buffer<N, PREFIX> {
     /// *lower* hex string representation within the buffer.
     #[inline]
     pub fn format(&mut self, array: &[u8; N]) -> &mut str {
-        // length of array is guaranteed to be N.
-        self.format_inner::<false>(array)
+        self.format_array_inner::<false>(array)
     }
 
     /// Print an array of bytes into this buffer and return a reference to its
     /// *upper* hex string representation within the buffer.
     #[inline]
     pub fn format_upper(&mut self, array: &[u8; N]) -> &mut str {
-        // length of array is guaranteed to be N.
-        self.format_inner::<true>(array)
+        self.format_array_inner::<true>(array)
+    }
+
+    /// Print an array of bytes into this buffer in reverse byte order and
+    /// return a reference to its *lower* hex string representation within the
+    /// buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut buffer = const_hex::Buffer::<4, true>::new();
+    /// assert_eq!(buffer.format_reversed(&[0xde, 0xad, 0xbe, 0xef]), "0xefbeadde");
+    /// ```
+    #[inline]
+    pub fn format_reversed(&mut self, array: &[u8; N]) -> &mut str {
+        self.format_reversed_inner::<false>(array)
+    }
+
+    /// Print an array of bytes into this buffer in reverse byte order and
+    /// return a reference to its *upper* hex string representation within the
+    /// buffer.
+    #[inline]
+    pub fn format_reversed_upper(&mut self, array: &[u8; N]) -> &mut str {
+        self.format_reversed_inner::<true>(array)
     }
 
     /// Print a slice of bytes into this buffer and return a reference to its
@@ -145,6 +335,19 @@ impl<const N: usize, const PREFIX: bool> Buffer<N, PREFIX> {
         self.format_inner::<UPPER>(slice)
     }
 
+    // Length of array is guaranteed to be N; uses the fixed-size fast paths.
+    #[inline]
+    fn format_array_inner<const UPPER: bool>(&mut self, array: &[u8; N]) -> &mut str {
+        // SAFETY: The output is exactly `N * 2` bytes long;
+        // we only write only ASCII bytes.
+        unsafe {
+            let buf = self.as_mut_bytes();
+            let output = buf.get_unchecked_mut(PREFIX as usize * 2..);
+            arch::encode_fixed::<N, UPPER>(array, output);
+            str::from_utf8_unchecked_mut(buf)
+        }
+    }
+
     // Doesn't check length
     #[inline]
     fn format_inner<const UPPER: bool>(&mut self, input: &[u8]) -> &mut str {
@@ -153,15 +356,48 @@ impl<const N: usize, const PREFIX: bool> Buffer<N, PREFIX> {
         unsafe {
             let buf = self.as_mut_bytes();
             let output = buf.get_unchecked_mut(PREFIX as usize * 2..);
-            imp::encode::<UPPER>(input, output);
+            arch::encode::<UPPER>(input, output);
             str::from_utf8_unchecked_mut(buf)
         }
     }
 
+    // Doesn't check length
+    #[inline]
+    fn format_reversed_inner<const UPPER: bool>(&mut self, input: &[u8]) -> &mut str {
+        // SAFETY: Length was checked previously;
+        // we only write only ASCII bytes.
+        unsafe {
+            let buf = self.as_mut_bytes();
+            let output = buf.get_unchecked_mut(PREFIX as usize * 2..);
+            arch::encode_reversed::<UPPER>(input, output);
+            str::from_utf8_unchecked_mut(buf)
+        }
+    }
+
+    /// Decodes the contents of the buffer back into bytes.
+    ///
+    /// # Errors
+    ///
+    /// If the buffer does not contain valid hex, for example because nothing
+    /// has been formatted into it yet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut buffer = const_hex::Buffer::<4, true>::new();
+    /// assert!(buffer.decode().is_err());
+    /// buffer.format_upper(b"kiwi");
+    /// assert_eq!(buffer.decode(), Ok(*b"kiwi"));
+    /// ```
+    #[inline]
+    pub fn decode(&self) -> Result<[u8; N], FromHexError> {
+        crate::decode_to_array(self.as_bytes())
+    }
+
     /// Copies `self` into a new owned `String`.
     #[cfg(feature = "alloc")]
     #[inline]
-    #[allow(clippy::inherent_to_string)] // this is intentional
+    #[allow(clippy::inherent_to_string, clippy::inherent_to_string_shadow_display)] // this is intentional
     pub fn to_string(&self) -> String {
         // SAFETY: The buffer always contains valid UTF-8.
         unsafe { String::from_utf8_unchecked(self.as_bytes().to_vec()) }
@@ -0,0 +1,380 @@
+//! Compile-time composition of hex strings.
+//!
+//! [`ConstStr`] is a fixed-capacity string that can be built up in `const` contexts from string
+//! slices, such as the output of [`const_encode`](crate::const_encode), and raw bytes. The
+//! [`const_concat!`](crate::const_concat) and [`const_join!`](crate::const_join) macros compute
+//! the exact capacity themselves and evaluate to a `&'static str`.
+//!
+//! # Examples
+//!
+//! ```
+//! use const_hex::{const_concat, const_encode, const_join, const_str, Buffer, ConstStr};
+//!
+//! const ADDRESS: Buffer<4, true> = const_encode(&[0xde, 0xad, 0xbe, 0xef]);
+//! const WORD: &str = const_concat!("0x", "00000000", const_str::trim_prefix(ADDRESS.as_str()));
+//! assert_eq!(WORD, "0x00000000deadbeef");
+//!
+//! const SELECTORS: &str = const_join!(","; ADDRESS.as_str(), const_str::slice(WORD, 0, 6));
+//! assert_eq!(SELECTORS, "0xdeadbeef,0x0000");
+//!
+//! static PADDED: ConstStr<18> = ConstStr::new()
+//!     .push_str("0x")
+//!     .push_repeat("0", 8)
+//!     .push_hex(&[0xde, 0xad, 0xbe, 0xef]);
+//! assert_eq!(PADDED, WORD);
+//! ```
+
+use crate::byte2hex;
+use core::fmt;
+use core::ops::Deref;
+use core::slice;
+use core::str;
+
+/// A string of at most `CAP` bytes that can be built in `const` contexts.
+///
+/// All methods that add to the string panic if its capacity would be exceeded, which is a
+/// compile-time error when evaluated in a `const` or `static` item.
+///
+/// See the [module documentation](self) for more information.
+#[derive(Clone, Copy)]
+pub struct ConstStr<const CAP: usize> {
+    buf: [u8; CAP],
+    len: usize,
+}
+
+impl<const CAP: usize> Default for ConstStr<CAP> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const CAP: usize> fmt::Debug for ConstStr<CAP> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<const CAP: usize> fmt::Display for ConstStr<CAP> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+impl<const CAP: usize> Deref for ConstStr<CAP> {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const CAP: usize> AsRef<str> for ConstStr<CAP> {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const CAP: usize, const M: usize> PartialEq<ConstStr<M>> for ConstStr<CAP> {
+    #[inline]
+    fn eq(&self, other: &ConstStr<M>) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<const CAP: usize> Eq for ConstStr<CAP> {}
+
+impl<const CAP: usize> PartialEq<str> for ConstStr<CAP> {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<const CAP: usize> PartialEq<&str> for ConstStr<CAP> {
+    #[inline]
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl<const CAP: usize> ConstStr<CAP> {
+    /// Creates a new empty string.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            buf: [0; CAP],
+            len: 0,
+        }
+    }
+
+    /// Creates a string from the `parts` joined by `sep`.
+    ///
+    /// Use [`joined_len`] to compute the required capacity.
+    ///
+    /// # Panics
+    ///
+    /// If the result does not fit in `CAP` bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use const_hex::{const_str, ConstStr};
+    ///
+    /// const PARTS: &[&str] = &["01", "02", "03"];
+    /// const JOINED: ConstStr<{ const_str::joined_len(", ", PARTS) }> = ConstStr::join(", ", PARTS);
+    /// assert_eq!(JOINED, "01, 02, 03");
+    /// ```
+    #[inline]
+    pub const fn join(sep: &str, parts: &[&str]) -> Self {
+        let mut s = Self::new();
+        let mut i = 0;
+        while i < parts.len() {
+            if i > 0 {
+                s = s.push_str(sep);
+            }
+            s = s.push_str(parts[i]);
+            i += 1;
+        }
+        s
+    }
+
+    /// Appends a string slice.
+    ///
+    /// # Panics
+    ///
+    /// If the result does not fit in `CAP` bytes.
+    #[inline]
+    pub const fn push_str(mut self, s: &str) -> Self {
+        let bytes = s.as_bytes();
+        assert!(
+            bytes.len() <= CAP - self.len,
+            "`ConstStr` capacity exceeded"
+        );
+        let mut i = 0;
+        while i < bytes.len() {
+            self.buf[self.len + i] = bytes[i];
+            i += 1;
+        }
+        self.len += bytes.len();
+        self
+    }
+
+    /// Appends `s` repeated `n` times, for example to pad a hex string with zeros.
+    ///
+    /// # Panics
+    ///
+    /// If the result does not fit in `CAP` bytes.
+    #[inline]
+    pub const fn push_repeat(mut self, s: &str, n: usize) -> Self {
+        let mut i = 0;
+        while i < n {
+            self = self.push_str(s);
+            i += 1;
+        }
+        self
+    }
+
+    /// Appends the *lower* hex encoding of `bytes`, without a prefix.
+    ///
+    /// # Panics
+    ///
+    /// If the result does not fit in `CAP` bytes.
+    #[inline]
+    pub const fn push_hex(self, bytes: &[u8]) -> Self {
+        self.push_hex_inner::<false>(bytes)
+    }
+
+    /// Appends the *upper* hex encoding of `bytes`, without a prefix.
+    ///
+    /// # Panics
+    ///
+    /// If the result does not fit in `CAP` bytes.
+    #[inline]
+    pub const fn push_hex_upper(self, bytes: &[u8]) -> Self {
+        self.push_hex_inner::<true>(bytes)
+    }
+
+    const fn push_hex_inner<const UPPER: bool>(mut self, bytes: &[u8]) -> Self {
+        assert!(
+            bytes.len() <= (CAP - self.len) / 2,
+            "`ConstStr` capacity exceeded"
+        );
+        let mut i = 0;
+        while i < bytes.len() {
+            let (high, low) = byte2hex::<UPPER>(bytes[i]);
+            self.buf[self.len] = high;
+            self.buf[self.len + 1] = low;
+            self.len += 2;
+            i += 1;
+        }
+        self
+    }
+
+    /// Shortens the string to `len` bytes. Has no effect if `len` is greater than the current
+    /// length.
+    ///
+    /// # Panics
+    ///
+    /// If `len` does not lie on a `char` boundary.
+    #[inline]
+    pub const fn truncate(mut self, len: usize) -> Self {
+        if len < self.len {
+            assert!(
+                is_char_boundary(self.as_bytes(), len),
+                "not a char boundary"
+            );
+            self.len = len;
+        }
+        self
+    }
+
+    /// Returns the length of the string in bytes.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the string is empty.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the maximum length of the string in bytes, `CAP`.
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        CAP
+    }
+
+    /// Returns the contents as a string slice.
+    ///
+    /// When called on a `const` item, the result is a `&'static str`.
+    #[inline]
+    pub const fn as_str(&self) -> &str {
+        // SAFETY: Only whole string slices are pushed, and truncation checks char boundaries.
+        unsafe { str::from_utf8_unchecked(self.as_bytes()) }
+    }
+
+    /// Returns the contents as a byte slice.
+    #[inline]
+    pub const fn as_bytes(&self) -> &[u8] {
+        // SAFETY: `len <= CAP`.
+        unsafe { slice::from_raw_parts(self.buf.as_ptr(), self.len) }
+    }
+}
+
+/// Returns the length of the `parts` joined by `sep`, which is the capacity required by
+/// [`ConstStr::join`].
+#[inline]
+pub const fn joined_len(sep: &str, parts: &[&str]) -> usize {
+    let mut len = 0;
+    let mut i = 0;
+    while i < parts.len() {
+        if i > 0 {
+            len += sep.len();
+        }
+        len += parts[i].len();
+        i += 1;
+    }
+    len
+}
+
+/// Returns the subslice `s[start..end]`.
+///
+/// # Panics
+///
+/// If `start > end`, `end > s.len()`, or either index does not lie on a `char` boundary.
+///
+/// # Examples
+///
+/// ```
+/// const HASH: &str = "0x8c5be1e5ebec7d5bd14f71427d1e84f3dd0314c0f7b2291e5b200ac8c7c3b925";
+/// const SELECTOR: &str = const_hex::const_str::slice(HASH, 0, 10);
+/// assert_eq!(SELECTOR, "0x8c5be1e5");
+/// ```
+#[inline]
+pub const fn slice(s: &str, start: usize, end: usize) -> &str {
+    let bytes = s.as_bytes();
+    assert!(start <= end && end <= bytes.len(), "range out of bounds");
+    assert!(
+        is_char_boundary(bytes, start) && is_char_boundary(bytes, end),
+        "not a char boundary"
+    );
+    // SAFETY: The range is in bounds and on char boundaries, checked above.
+    unsafe {
+        let bytes = slice::from_raw_parts(bytes.as_ptr().add(start), end - start);
+        str::from_utf8_unchecked(bytes)
+    }
+}
+
+/// Removes a leading `0x` or `0X` from `s`, if present.
+///
+/// # Examples
+///
+/// ```
+/// use const_hex::const_str::trim_prefix;
+///
+/// const DIGITS: &str = trim_prefix(const_hex::const_encode::<2, true>(&[1, 2]).as_str());
+/// assert_eq!(DIGITS, "0102");
+/// assert_eq!(trim_prefix("0102"), "0102");
+/// ```
+#[inline]
+pub const fn trim_prefix(s: &str) -> &str {
+    match s.as_bytes() {
+        [b'0', b'x' | b'X', ..] => slice(s, 2, s.len()),
+        _ => s,
+    }
+}
+
+const fn is_char_boundary(bytes: &[u8], index: usize) -> bool {
+    // Not a UTF-8 continuation byte.
+    index == bytes.len() || (bytes[index] as i8) >= -0x40
+}
+
+/// Concatenates string slices into a `&'static str` at compile time.
+///
+/// Every argument must be a constant expression of type `&str`. Use
+/// [`Buffer::as_str`](crate::Buffer::as_str) to pass a [`Buffer`](crate::Buffer).
+///
+/// # Examples
+///
+/// ```
+/// const ADDRESS: const_hex::Buffer<2> = const_hex::const_encode(&[0xbe, 0xef]);
+/// const WORD: &str = const_hex::const_concat!("0x", "0000", ADDRESS.as_str());
+/// assert_eq!(WORD, "0x0000beef");
+/// ```
+#[macro_export]
+macro_rules! const_concat {
+    ($($s:expr),* $(,)?) => {
+        $crate::const_join!(""; $($s),*)
+    };
+}
+
+/// Joins string slices with a separator into a `&'static str` at compile time.
+///
+/// The separator comes first, followed by a `;`. Every argument must be a constant expression of
+/// type `&str`.
+///
+/// # Examples
+///
+/// ```
+/// const A: const_hex::Buffer<2, true> = const_hex::const_encode(&[0xab, 0xcd]);
+/// const B: const_hex::Buffer<2, true> = const_hex::const_encode(&[0x01, 0x23]);
+/// const LIST: &str = const_hex::const_join!(", "; A.as_str(), B.as_str());
+/// assert_eq!(LIST, "0xabcd, 0x0123");
+/// ```
+#[macro_export]
+macro_rules! const_join {
+    ($sep:expr; $($s:expr),* $(,)?) => {{
+        const __CONST_HEX_PARTS: &[&str] = &[$($s),*];
+        const __CONST_HEX_LEN: usize = $crate::const_str::joined_len($sep, __CONST_HEX_PARTS);
+        const __CONST_HEX_STR: $crate::ConstStr<__CONST_HEX_LEN> =
+            $crate::ConstStr::join($sep, __CONST_HEX_PARTS);
+        __CONST_HEX_STR.as_str()
+    }};
+}
@@ -0,0 +1,250 @@
+use core::fmt::{self, Write};
+
+/// The number of bytes per row.
+const ROW: usize = 16;
+/// The number of equal rows to keep around differing rows.
+const CONTEXT: usize = 1;
+/// The width of a row's hex column: two digits per byte, separated by spaces.
+const WIDTH: usize = ROW * 3 - 1;
+
+/// Returns a value that formats the differences between `left` and `right` as side-by-side
+/// hexdump rows.
+///
+/// Each row shows the offset followed by 16 bytes of `left` and `right`. Rows containing differing
+/// bytes are followed by a line marking them with `^^`, and runs of equal rows are collapsed into
+/// a single `*` line. Bytes past the end of the shorter input are left blank and marked as
+/// differing.
+///
+/// Supports [`fmt::LowerHex`], [`fmt::UpperHex`], and [`fmt::Display`]
+/// (which is the same as [`fmt::LowerHex`]). Formatting does not allocate.
+///
+/// See also [`assert_hex_eq!`](crate::assert_hex_eq).
+///
+/// # Examples
+///
+/// ```
+/// let left = [0u8; 64];
+/// let mut right = [0u8; 64];
+/// right[50] = 0xff;
+/// let diff = const_hex::diff(&left, &right).to_string();
+/// assert_eq!(
+///     diff,
+///     "\
+/// 1 differing byte, left: 64 bytes, right: 64 bytes
+/// *        (2 equal rows)
+/// 00000020  00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 | 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+/// 00000030  00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 | 00 00 ff 00 00 00 00 00 00 00 00 00 00 00 00 00
+///                 ^^                                                ^^
+/// "
+/// );
+/// ```
+#[inline]
+pub fn diff<L: AsRef<[u8]>, R: AsRef<[u8]>>(
+    left: L,
+    right: R,
+) -> impl fmt::Display + fmt::LowerHex + fmt::UpperHex {
+    Diff { left, right }
+}
+
+struct Diff<L, R> {
+    left: L,
+    right: R,
+}
+
+impl<L: AsRef<[u8]>, R: AsRef<[u8]>> fmt::Display for Diff<L, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(self, f)
+    }
+}
+
+impl<L: AsRef<[u8]>, R: AsRef<[u8]>> fmt::LowerHex for Diff<L, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Rows::new(self.left.as_ref(), self.right.as_ref()).write::<false>(f)
+    }
+}
+
+impl<L: AsRef<[u8]>, R: AsRef<[u8]>> fmt::UpperHex for Diff<L, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Rows::new(self.left.as_ref(), self.right.as_ref()).write::<true>(f)
+    }
+}
+
+struct Rows<'a> {
+    left: &'a [u8],
+    right: &'a [u8],
+    len: usize,
+}
+
+impl<'a> Rows<'a> {
+    fn new(left: &'a [u8], right: &'a [u8]) -> Self {
+        Self {
+            left,
+            right,
+            len: (left.len().max(right.len()) + ROW - 1) / ROW,
+        }
+    }
+
+    fn differs(&self, offset: usize) -> bool {
+        self.left.get(offset) != self.right.get(offset)
+    }
+
+    fn row_differs(&self, row: usize) -> bool {
+        (row * ROW..(row + 1) * ROW).any(|offset| self.differs(offset))
+    }
+
+    fn write<const UPPER: bool>(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let max = self.left.len().max(self.right.len());
+        let differing = (0..max).filter(|&offset| self.differs(offset)).count();
+        writeln!(
+            f,
+            "{differing} differing byte{}, left: {} bytes, right: {} bytes",
+            if differing == 1 { "" } else { "s" },
+            self.left.len(),
+            self.right.len(),
+        )?;
+
+        let mut skipped = 0;
+        for row in 0..self.len {
+            let start = row.saturating_sub(CONTEXT);
+            let end = (row + CONTEXT + 1).min(self.len);
+            if !(start..end).any(|r| self.row_differs(r)) {
+                skipped += 1;
+                continue;
+            }
+            write_skipped(f, &mut skipped)?;
+            self.write_row::<UPPER>(f, row)?;
+        }
+        write_skipped(f, &mut skipped)
+    }
+
+    fn write_row<const UPPER: bool>(&self, f: &mut fmt::Formatter<'_>, row: usize) -> fmt::Result {
+        let offset = row * ROW;
+        write!(f, "{offset:08x}  ")?;
+        write_half::<UPPER>(f, self.left, offset, true)?;
+        f.write_str(" |")?;
+        if offset < self.right.len() {
+            f.write_char(' ')?;
+            write_half::<UPPER>(f, self.right, offset, false)?;
+        }
+        f.write_char('\n')?;
+
+        if self.row_differs(row) {
+            // Align with the offset column.
+            f.write_str("          ")?;
+            self.write_markers(f, offset, true)?;
+            f.write_str("   ")?;
+            self.write_markers(f, offset, false)?;
+            f.write_char('\n')?;
+        }
+        Ok(())
+    }
+
+    fn write_markers(&self, f: &mut fmt::Formatter<'_>, offset: usize, pad: bool) -> fmt::Result {
+        let last = (offset..offset + ROW)
+            .rev()
+            .find(|&offset| self.differs(offset));
+        for i in offset..=last.unwrap_or(offset) {
+            if i != offset {
+                f.write_char(' ')?;
+            }
+            f.write_str(if self.differs(i) { "^^" } else { "  " })?;
+        }
+        if pad {
+            let written = last.map_or(0, |last| (last - offset) * 3 + 2);
+            write_padding(f, written)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes the bytes of a row, padding to [`WIDTH`] if `pad` is set.
+fn write_half<const UPPER: bool>(
+    f: &mut fmt::Formatter<'_>,
+    bytes: &[u8],
+    offset: usize,
+    pad: bool,
+) -> fmt::Result {
+    let row = bytes.get(offset..).unwrap_or_default();
+    let row = &row[..row.len().min(ROW)];
+    for (i, byte) in row.iter().enumerate() {
+        if i != 0 {
+            f.write_char(' ')?;
+        }
+        let byte = crate::display(core::slice::from_ref(byte));
+        if UPPER {
+            write!(f, "{byte:X}")?;
+        } else {
+            write!(f, "{byte:x}")?;
+        }
+    }
+    if pad {
+        write_padding(f, (row.len() * 3).saturating_sub(1))?;
+    }
+    Ok(())
+}
+
+fn write_padding(f: &mut fmt::Formatter<'_>, written: usize) -> fmt::Result {
+    for _ in written..WIDTH {
+        f.write_char(' ')?;
+    }
+    Ok(())
+}
+
+fn write_skipped(f: &mut fmt::Formatter<'_>, skipped: &mut usize) -> fmt::Result {
+    match core::mem::take(skipped) {
+        0 => Ok(()),
+        1 => f.write_str("*        (1 equal row)\n"),
+        n => writeln!(f, "*        ({n} equal rows)"),
+    }
+}
+
+/// Asserts that two byte sequences are equal, printing a hexdump [`diff`] of them on failure.
+///
+/// Both arguments must implement `AsRef<[u8]>`. Like [`assert_eq!`], an optional format message
+/// can be given after the two arguments.
+///
+/// This does not allocate, and so can be used in `no_std` environments.
+///
+/// # Examples
+///
+/// ```
+/// let bytes = const_hex::decode_to_array::<_, 4>("deadbeef").unwrap();
+/// const_hex::assert_hex_eq!(bytes, [0xde, 0xad, 0xbe, 0xef]);
+/// const_hex::assert_hex_eq!(&bytes[..2], b"\xde\xad", "prefix of {:?}", bytes);
+/// ```
+///
+/// ```should_panic
+/// const_hex::assert_hex_eq!([0xde, 0xad, 0xbe, 0xef], [0xde, 0xad, 0xbe, 0xee]);
+/// ```
+#[macro_export]
+macro_rules! assert_hex_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                let left: &[u8] = ::core::convert::AsRef::<[u8]>::as_ref(left);
+                let right: &[u8] = ::core::convert::AsRef::<[u8]>::as_ref(right);
+                if left != right {
+                    ::core::panic!(
+                        "assertion `left == right` failed\n{}",
+                        $crate::diff(left, right),
+                    );
+                }
+            }
+        }
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                let left: &[u8] = ::core::convert::AsRef::<[u8]>::as_ref(left);
+                let right: &[u8] = ::core::convert::AsRef::<[u8]>::as_ref(right);
+                if left != right {
+                    ::core::panic!(
+                        "assertion `left == right` failed: {}\n{}",
+                        ::core::format_args!($($arg)+),
+                        $crate::diff(left, right),
+                    );
+                }
+            }
+        }
+    };
+}
@@ -48,3 +48,61 @@ impl<T: AsRef<[u8]>> Display<T> {
         Ok(())
     }
 }
+
+/// Returns a value that formats `input` as hex in reverse byte order.
+///
+/// This works exactly like [`display`], but starts from the last byte of `input`. Formatting does
+/// not allocate.
+///
+/// # Examples
+///
+/// ```
+/// let bytes: &[u8] = &[0xde, 0xad, 0xbe, 0xef];
+/// let displayed = const_hex::display_reversed(bytes);
+/// let s = format!("{displayed} {displayed:#X}");
+/// assert_eq!(s, "efbeadde 0xEFBEADDE");
+/// ```
+#[inline]
+pub fn display_reversed<T: AsRef<[u8]>>(
+    input: T,
+) -> impl fmt::Display + fmt::LowerHex + fmt::UpperHex {
+    DisplayReversed(input)
+}
+
+struct DisplayReversed<T: AsRef<[u8]>>(T);
+
+impl<T: AsRef<[u8]>> fmt::Display for DisplayReversed<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(self, f)
+    }
+}
+
+impl<T: AsRef<[u8]>> fmt::LowerHex for DisplayReversed<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write::<false>(f)
+    }
+}
+
+impl<T: AsRef<[u8]>> fmt::UpperHex for DisplayReversed<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write::<true>(f)
+    }
+}
+
+impl<T: AsRef<[u8]>> DisplayReversed<T> {
+    fn write<const UPPER: bool>(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            f.write_str("0x")?;
+        }
+        // The reversed input is the concatenation of its reversed chunks, starting from the end.
+        let mut buf = [0u8; 128];
+        for chunk in self.0.as_ref().rchunks(buf.len() / 2) {
+            let buf = &mut buf[..chunk.len() * 2];
+            // SAFETY: `buf` is exactly `chunk.len() * 2` bytes long.
+            unsafe { crate::arch::encode_reversed::<UPPER>(chunk, buf) };
+            // SAFETY: We only write only ASCII bytes.
+            f.write_str(unsafe { core::str::from_utf8_unchecked(buf) })?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,620 @@
+//! A configurable hex [`Engine`].
+//!
+//! The free functions of this crate cover the common cases, with a separate function for each
+//! combination of case and prefix. An [`Engine`] instead captures all of these choices in a
+//! [`Config`], so that an application can define its hex format once, as a `const`, and use it
+//! everywhere.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "alloc")] {
+//! use const_hex::engine::{Case, Config, Engine, PrefixMode};
+//!
+//! const MAC: Engine = Engine::new(
+//!     Config::new()
+//!         .with_case(Case::Upper)
+//!         .with_separator(Some(b':'))
+//!         .with_decode_prefix(PrefixMode::Forbidden),
+//! );
+//!
+//! let mac = [0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e];
+//! assert_eq!(MAC.encode(mac), "00:1A:2B:3C:4D:5E");
+//! assert_eq!(MAC.decode("00:1a:2B:3c:4D:5e"), Ok(mac.to_vec()));
+//! assert!(MAC.check("001A2B3C4D5E").is_err());
+//! # }
+//! ```
+
+use crate::{arch, invalid_hex_error, unlikely, FromHexError, HEX_DECODE_LUT, NIL};
+use core::str;
+
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
+
+/// The number of bytes encoded or decoded at a time when a separator is used.
+const CHUNK_LEN: usize = 64;
+
+/// The standard engine: *lower* case output without a prefix; decoding accepts both cases and an
+/// optional `0x` prefix.
+///
+/// This matches [`encode`](crate::encode) and [`decode`](crate::decode).
+pub const STANDARD: Engine = Engine::new(Config::new());
+
+/// Like [`STANDARD`], but with *upper* case output.
+///
+/// This matches [`encode_upper`](crate::encode_upper) and [`decode`](crate::decode).
+pub const STANDARD_UPPER: Engine = Engine::new(Config::new().with_case(Case::Upper));
+
+/// Like [`STANDARD`], but output starts with a `0x` prefix.
+///
+/// This matches [`encode_prefixed`](crate::encode_prefixed) and [`decode`](crate::decode).
+pub const PREFIXED: Engine = Engine::new(Config::new().with_encode_prefix(true));
+
+/// Like [`PREFIXED`], but with *upper* case output.
+///
+/// This matches [`encode_upper_prefixed`](crate::encode_upper_prefixed) and
+/// [`decode`](crate::decode).
+pub const PREFIXED_UPPER: Engine = Engine::new(
+    Config::new()
+        .with_case(Case::Upper)
+        .with_encode_prefix(true),
+);
+
+/// The case of hex letters.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Case {
+    /// `a` to `f`.
+    #[default]
+    Lower,
+    /// `A` to `F`.
+    Upper,
+}
+
+/// Whether a `0x` prefix is accepted when decoding.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PrefixMode {
+    /// A `0x` or `0X` prefix is stripped if present.
+    #[default]
+    Optional,
+    /// A `0x` or `0X` prefix must be present.
+    ///
+    /// If it is missing, the first character that does not match it is reported as an
+    /// [`InvalidHexCharacter`](FromHexError::InvalidHexCharacter).
+    Required,
+    /// A prefix is not stripped, so its `x` is an invalid character.
+    Forbidden,
+}
+
+/// The configuration of an [`Engine`].
+///
+/// All methods are `const`, so that configurations can be built in constants.
+///
+/// # Examples
+///
+/// ```
+/// use const_hex::engine::{Case, Config, PrefixMode};
+///
+/// const CONFIG: Config = Config::new().with_case(Case::Upper).with_strict(true);
+/// assert_eq!(CONFIG.case(), Case::Upper);
+/// assert_eq!(CONFIG.decode_prefix(), PrefixMode::Optional);
+/// assert!(CONFIG.strict());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Config {
+    case: Case,
+    encode_prefix: bool,
+    decode_prefix: PrefixMode,
+    separator: Option<u8>,
+    strict: bool,
+}
+
+impl Default for Config {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Config {
+    /// Creates the configuration of the [`STANDARD`] engine.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            case: Case::Lower,
+            encode_prefix: false,
+            decode_prefix: PrefixMode::Optional,
+            separator: None,
+            strict: false,
+        }
+    }
+
+    /// Sets the case of encoded letters. If [strict](Self::with_strict), this is also the only case
+    /// accepted when decoding.
+    ///
+    /// Defaults to [`Case::Lower`].
+    #[inline]
+    pub const fn with_case(mut self, case: Case) -> Self {
+        self.case = case;
+        self
+    }
+
+    /// Sets whether encoded strings start with a `0x` prefix.
+    ///
+    /// Defaults to `false`.
+    #[inline]
+    pub const fn with_encode_prefix(mut self, encode_prefix: bool) -> Self {
+        self.encode_prefix = encode_prefix;
+        self
+    }
+
+    /// Sets whether a `0x` prefix is accepted when decoding.
+    ///
+    /// Defaults to [`PrefixMode::Optional`].
+    #[inline]
+    pub const fn with_decode_prefix(mut self, decode_prefix: PrefixMode) -> Self {
+        self.decode_prefix = decode_prefix;
+        self
+    }
+
+    /// Sets the separator between encoded bytes, such as `b':'` for `de:ad:be:ef`.
+    ///
+    /// When decoding, the separator is required between every two bytes and not allowed anywhere
+    /// else.
+    ///
+    /// Defaults to `None`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the separator is not ASCII, or if it is a hex digit.
+    #[inline]
+    #[track_caller]
+    pub const fn with_separator(mut self, separator: Option<u8>) -> Self {
+        if let Some(separator) = separator {
+            assert!(separator.is_ascii(), "separator must be ASCII");
+            assert!(
+                HEX_DECODE_LUT[separator as usize] == NIL,
+                "separator must not be a hex digit"
+            );
+        }
+        self.separator = separator;
+        self
+    }
+
+    /// Sets whether decoding only accepts letters of the configured [case](Self::with_case).
+    ///
+    /// Defaults to `false`, which accepts both cases, even mixed.
+    #[inline]
+    pub const fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Returns the case of encoded letters.
+    #[inline]
+    pub const fn case(&self) -> Case {
+        self.case
+    }
+
+    /// Returns whether encoded strings start with a `0x` prefix.
+    #[inline]
+    pub const fn encode_prefix(&self) -> bool {
+        self.encode_prefix
+    }
+
+    /// Returns whether a `0x` prefix is accepted when decoding.
+    #[inline]
+    pub const fn decode_prefix(&self) -> PrefixMode {
+        self.decode_prefix
+    }
+
+    /// Returns the separator between encoded bytes.
+    #[inline]
+    pub const fn separator(&self) -> Option<u8> {
+        self.separator
+    }
+
+    /// Returns whether decoding only accepts letters of the configured case.
+    #[inline]
+    pub const fn strict(&self) -> bool {
+        self.strict
+    }
+}
+
+/// Encodes and decodes hex strings according to a [`Config`].
+///
+/// Encoding and decoding use the same SIMD implementations as the rest of the crate.
+///
+/// # Examples
+///
+/// ```
+/// use const_hex::engine::{self, Case, Config, Engine};
+///
+/// const HEX: Engine = Engine::new(Config::new().with_case(Case::Upper).with_strict(true));
+///
+/// let mut buf = [0u8; 8];
+/// assert_eq!(&*HEX.encode_to_str(b"kiwi", &mut buf).unwrap(), "6B697769");
+/// assert_eq!(HEX.decode_to_array(b"6B697769"), Ok(*b"kiwi"));
+/// assert!(HEX.decode_to_array::<4>(b"6b697769").is_err());
+/// assert_eq!(engine::STANDARD.decode_to_array(b"6b697769"), Ok(*b"kiwi"));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Engine {
+    config: Config,
+}
+
+impl Engine {
+    /// Creates a new engine with the given configuration.
+    #[inline]
+    pub const fn new(config: Config) -> Self {
+        Self { config }
+    }
+
+    /// Returns the configuration of the engine.
+    #[inline]
+    pub const fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Returns the length of the encoding of `len` bytes, including the prefix and separators.
+    #[inline]
+    pub const fn encoded_len(&self, len: usize) -> usize {
+        let separators = match self.config.separator {
+            Some(_) if len > 0 => len - 1,
+            _ => 0,
+        };
+        self.config.encode_prefix as usize * 2 + len * 2 + separators
+    }
+
+    /// Returns the number of bytes that `input` decodes to, after checking its prefix and length,
+    /// but not its contents.
+    ///
+    /// # Errors
+    ///
+    /// If the prefix does not match the [`PrefixMode`], or if the length is not valid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use const_hex::engine::{Config, Engine};
+    ///
+    /// const HEX: Engine = Engine::new(Config::new().with_separator(Some(b' ')));
+    /// assert_eq!(HEX.decoded_len("0xde ad be ef"), Ok(4));
+    /// ```
+    #[inline]
+    pub fn decoded_len<T: AsRef<[u8]>>(&self, input: T) -> Result<usize, FromHexError> {
+        let input = input.as_ref();
+        let digits = &input[self.prefix_len(input)?..];
+        self.digits_decoded_len(digits)
+    }
+
+    /// Encodes `data` as a hex string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// assert_eq!(const_hex::engine::PREFIXED_UPPER.encode(b"kiwi"), "0x6B697769");
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn encode<T: AsRef<[u8]>>(&self, data: T) -> String {
+        let data = data.as_ref();
+        let mut buf = vec![0; self.encoded_len(data.len())];
+        self.encode_unchecked(data, &mut buf);
+        // SAFETY: We only write only ASCII bytes.
+        unsafe { String::from_utf8_unchecked(buf) }
+    }
+
+    /// Encodes `input` as a hex string into a mutable slice.
+    ///
+    /// # Errors
+    ///
+    /// If the output buffer is not exactly [`encoded_len`](Self::encoded_len) bytes long.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut buf = [0u8; 10];
+    /// const_hex::engine::PREFIXED.encode_to_slice(b"kiwi", &mut buf).unwrap();
+    /// assert_eq!(&buf, b"0x6b697769");
+    /// ```
+    #[inline]
+    pub fn encode_to_slice<T: AsRef<[u8]>>(
+        &self,
+        input: T,
+        output: &mut [u8],
+    ) -> Result<(), FromHexError> {
+        let input = input.as_ref();
+        if unlikely(output.len() != self.encoded_len(input.len())) {
+            return Err(FromHexError::InvalidStringLength);
+        }
+        self.encode_unchecked(input, output);
+        Ok(())
+    }
+
+    /// Encodes `input` as a hex string into a mutable slice, and returns it as a string.
+    ///
+    /// # Errors
+    ///
+    /// If the output buffer is not exactly [`encoded_len`](Self::encoded_len) bytes long.
+    #[inline]
+    pub fn encode_to_str<'o, T: AsRef<[u8]>>(
+        &self,
+        input: T,
+        output: &'o mut [u8],
+    ) -> Result<&'o mut str, FromHexError> {
+        self.encode_to_slice(input, output)?;
+        // SAFETY: `encode_to_slice` overwrites the whole output with only ASCII bytes.
+        Ok(unsafe { str::from_utf8_unchecked_mut(output) })
+    }
+
+    /// Decodes a hex string into raw bytes.
+    ///
+    /// # Errors
+    ///
+    /// If the prefix does not match the [`PrefixMode`], the length is not valid, or the input
+    /// contains an invalid character, including a letter of the wrong case when strict or a
+    /// misplaced separator. Like [`decode`](crate::decode), the index of an invalid character does
+    /// not count the prefix; use [`check`](Self::check) for an index into the whole `input`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use const_hex::engine::{Config, Engine, PrefixMode};
+    /// use const_hex::FromHexError;
+    ///
+    /// const HEX: Engine = Engine::new(Config::new().with_decode_prefix(PrefixMode::Required));
+    /// assert_eq!(HEX.decode("0x6b697769"), Ok(b"kiwi".to_vec()));
+    /// assert_eq!(HEX.decode("6b697769"), Err(FromHexError::InvalidHexCharacter { c: '6', index: 0 }));
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn decode<T: AsRef<[u8]>>(&self, input: T) -> Result<Vec<u8>, FromHexError> {
+        let input = input.as_ref();
+        let digits = &input[self.prefix_len(input)?..];
+        let len = self.digits_decoded_len(digits)?;
+        let mut output = vec![0; len];
+        self.decode_digits(digits, &mut output)?;
+        Ok(output)
+    }
+
+    /// Decodes a hex string into a mutable bytes slice.
+    ///
+    /// # Errors
+    ///
+    /// If the output buffer is not exactly [`decoded_len`](Self::decoded_len) bytes long.
+    /// Otherwise, see [`decode`](Self::decode).
+    #[inline]
+    pub fn decode_to_slice<T: AsRef<[u8]>>(
+        &self,
+        input: T,
+        output: &mut [u8],
+    ) -> Result<(), FromHexError> {
+        let input = input.as_ref();
+        let digits = &input[self.prefix_len(input)?..];
+        let len = self.digits_decoded_len(digits)?;
+        if unlikely(output.len() != len) {
+            return Err(FromHexError::InvalidStringLength);
+        }
+        self.decode_digits(digits, output)
+    }
+
+    /// Decodes a hex string into a fixed-length byte array.
+    ///
+    /// # Errors
+    ///
+    /// If the input does not decode to exactly `N` bytes. Otherwise, see [`decode`](Self::decode).
+    #[inline]
+    pub fn decode_to_array<const N: usize>(
+        &self,
+        input: impl AsRef<[u8]>,
+    ) -> Result<[u8; N], FromHexError> {
+        let mut output = [0; N];
+        self.decode_to_slice(input, &mut output)?;
+        Ok(output)
+    }
+
+    /// Checks that `input` is a valid hex string that can be decoded successfully.
+    ///
+    /// # Errors
+    ///
+    /// See [`decode`](Self::decode), except that the index of an invalid character is relative to
+    /// the start of `input`, including the prefix, like [`check`](crate::check).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use const_hex::engine::{Case, Config, Engine};
+    /// use const_hex::FromHexError;
+    ///
+    /// const HEX: Engine = Engine::new(Config::new().with_strict(true));
+    /// assert_eq!(HEX.check("0x6b697769"), Ok(()));
+    /// assert_eq!(HEX.check("0x6B697769"), Err(FromHexError::InvalidHexCharacter { c: 'B', index: 3 }));
+    /// ```
+    #[inline]
+    pub fn check<T: AsRef<[u8]>>(&self, input: T) -> Result<(), FromHexError> {
+        let input = input.as_ref();
+        let prefix_len = self.prefix_len(input)?;
+        let digits = &input[prefix_len..];
+        self.digits_decoded_len(digits)?;
+        let result = match self.config.separator {
+            None => self.check_digits(digits),
+            Some(separator) => {
+                for_each_separated(digits, separator, |digits, _| self.check_digits(digits))
+            }
+        };
+        result.map_err(|e| crate::offset_error(e, prefix_len))
+    }
+
+    /// Returns the length of the prefix of `input` to skip, according to the [`PrefixMode`].
+    fn prefix_len(&self, input: &[u8]) -> Result<usize, FromHexError> {
+        let has_prefix = matches!(input, [b'0', b'x' | b'X', ..]);
+        match self.config.decode_prefix {
+            PrefixMode::Optional => Ok(if has_prefix { 2 } else { 0 }),
+            PrefixMode::Forbidden => Ok(0),
+            PrefixMode::Required if has_prefix => Ok(2),
+            PrefixMode::Required => match *input {
+                [] | [b'0'] => Err(FromHexError::InvalidStringLength),
+                [b'0', c, ..] => Err(FromHexError::InvalidHexCharacter {
+                    c: c as char,
+                    index: 1,
+                }),
+                [c, ..] => Err(FromHexError::InvalidHexCharacter {
+                    c: c as char,
+                    index: 0,
+                }),
+            },
+        }
+    }
+
+    /// Returns the number of bytes that `digits`, without a prefix, decodes to.
+    const fn digits_decoded_len(&self, digits: &[u8]) -> Result<usize, FromHexError> {
+        match self.config.separator {
+            None if digits.len() % 2 != 0 => Err(FromHexError::OddLength),
+            None => Ok(digits.len() / 2),
+            Some(_) if digits.is_empty() => Ok(0),
+            Some(_) if (digits.len() + 1) % 3 != 0 => Err(FromHexError::InvalidStringLength),
+            Some(_) => Ok((digits.len() + 1) / 3),
+        }
+    }
+
+    /// Assumes `output.len() == self.encoded_len(input.len())`.
+    fn encode_unchecked(&self, input: &[u8], mut output: &mut [u8]) {
+        debug_assert_eq!(output.len(), self.encoded_len(input.len()));
+        if self.config.encode_prefix {
+            output[..2].copy_from_slice(b"0x");
+            output = &mut output[2..];
+        }
+        match (self.config.separator, self.config.case) {
+            // SAFETY: The output is exactly twice as long as the input.
+            (None, Case::Lower) => unsafe { arch::encode::<false>(input, output) },
+            (None, Case::Upper) => unsafe { arch::encode::<true>(input, output) },
+            (Some(separator), Case::Lower) => encode_separated::<false>(input, output, separator),
+            (Some(separator), Case::Upper) => encode_separated::<true>(input, output, separator),
+        }
+    }
+
+    /// Decodes `digits`, without a prefix, whose length has already been checked against `output`.
+    fn decode_digits(&self, digits: &[u8], output: &mut [u8]) -> Result<(), FromHexError> {
+        match self.config.separator {
+            None => self.decode_raw(digits, output),
+            Some(separator) => for_each_separated(digits, separator, |digits, start| {
+                let output = &mut output[start..start + digits.len() / 2];
+                self.decode_raw(digits, output)
+            }),
+        }
+    }
+
+    /// Decodes contiguous hex digits. Assumes `output.len() == digits.len() / 2`.
+    fn decode_raw(&self, digits: &[u8], output: &mut [u8]) -> Result<(), FromHexError> {
+        debug_assert_eq!(output.len(), digits.len() / 2);
+        if self.config.strict {
+            self.check_strict(digits)?;
+            // SAFETY: The digits are valid and the lengths are checked above.
+            unsafe { arch::decode_unchecked(digits, output) };
+            Ok(())
+        } else {
+            // SAFETY: The lengths are checked above.
+            unsafe { crate::decode_checked(digits, output) }
+        }
+    }
+
+    /// Checks contiguous hex digits.
+    fn check_digits(&self, digits: &[u8]) -> Result<(), FromHexError> {
+        if self.config.strict {
+            self.check_strict(digits)
+        } else if arch::check(digits) {
+            Ok(())
+        } else {
+            Err(unsafe { invalid_hex_error(digits) })
+        }
+    }
+
+    /// Checks that `digits` only contains digits and letters of the configured case.
+    fn check_strict(&self, digits: &[u8]) -> Result<(), FromHexError> {
+        let letters = match self.config.case {
+            Case::Lower => b'a'..=b'f',
+            Case::Upper => b'A'..=b'F',
+        };
+        let is_valid = |c: u8| c.is_ascii_digit() || letters.contains(&c);
+        for (i, chunk) in digits.chunks(32).enumerate() {
+            // Check the whole chunk without branching, so that the loop can be vectorized.
+            if chunk.iter().fold(true, |valid, &c| valid & is_valid(c)) {
+                continue;
+            }
+            let j = chunk.iter().position(|&c| !is_valid(c)).unwrap();
+            return Err(FromHexError::InvalidHexCharacter {
+                c: chunk[j] as char,
+                index: i * 32 + j,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Encodes `input` into `output`, with `separator` between every two bytes.
+///
+/// Assumes `output.len() == input.len() * 3 - 1`.
+fn encode_separated<const UPPER: bool>(input: &[u8], output: &mut [u8], separator: u8) {
+    let mut stage = [0u8; CHUNK_LEN * 2];
+    let mut out = output.iter_mut();
+    for (i, chunk) in input.chunks(CHUNK_LEN).enumerate() {
+        let stage = &mut stage[..chunk.len() * 2];
+        // SAFETY: `stage` is exactly twice as long as `chunk`.
+        unsafe { arch::encode::<UPPER>(chunk, &mut *stage) };
+        for (j, pair) in stage.chunks_exact(2).enumerate() {
+            if i > 0 || j > 0 {
+                *out.next().unwrap() = separator;
+            }
+            *out.next().unwrap() = pair[0];
+            *out.next().unwrap() = pair[1];
+        }
+    }
+    debug_assert!(out.next().is_none());
+}
+
+/// Gathers the hex digits of `input`, separated by `separator`, into chunks of contiguous digits,
+/// and calls `f` with each chunk and the index of its first decoded byte.
+///
+/// Assumes that the length of `input` is valid. The index of an invalid character reported by `f`
+/// is relative to the start of its chunk, and is mapped back to an index into `input`.
+fn for_each_separated(
+    input: &[u8],
+    separator: u8,
+    mut f: impl FnMut(&[u8], usize) -> Result<(), FromHexError>,
+) -> Result<(), FromHexError> {
+    let mut stage = [0u8; CHUNK_LEN * 2];
+    // Each byte is followed by a separator, except the last one.
+    for (i, chunk) in input.chunks(CHUNK_LEN * 3).enumerate() {
+        let mut len = 0;
+        let mut invalid_separator = None;
+        for (j, group) in chunk.chunks(3).enumerate() {
+            stage[j * 2..j * 2 + 2].copy_from_slice(&group[..2]);
+            len += 1;
+            if let Some(&c) = group.get(2) {
+                if unlikely(c != separator) {
+                    invalid_separator = Some(FromHexError::InvalidHexCharacter {
+                        c: c as char,
+                        index: j * 3 + 2,
+                    });
+                    break;
+                }
+            }
+        }
+
+        // Digits before an invalid separator are checked first, so that the first invalid
+        // character is reported.
+        f(&stage[..len * 2], i * CHUNK_LEN)
+            .map_err(|e| match e {
+                FromHexError::InvalidHexCharacter { c, index } => {
+                    FromHexError::InvalidHexCharacter {
+                        c,
+                        index: index / 2 * 3 + index % 2,
+                    }
+                }
+                e => e,
+            })
+            .and(invalid_separator.map_or(Ok(()), Err))
+            .map_err(|e| crate::offset_error(e, i * CHUNK_LEN * 3))?;
+    }
+    Ok(())
+}
@@ -1,6 +1,6 @@
 //! Modified from `hex::error`.
 
-use core::fmt;
+use core::fmt::{self, Write};
 
 /// The error type for decoding a hex string into `Vec<u8>` or `[u8; N]`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -39,6 +39,274 @@ impl fmt::Display for FromHexError {
     }
 }
 
+#[cfg(feature = "std")]
+impl From<FromHexError> for std::io::Error {
+    #[inline]
+    fn from(e: FromHexError) -> Self {
+        Self::new(std::io::ErrorKind::InvalidData, e)
+    }
+}
+
+impl FromHexError {
+    /// Attaches the input that produced this error, computing the line and
+    /// column of invalid characters.
+    ///
+    /// `input` must be the input that the index of an invalid character refers
+    /// to. [`check`](crate::check) counts the `0x` prefix in that index, but the
+    /// `decode` functions do not, so their errors should be attached to the
+    /// input without its prefix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use const_hex::FromHexError;
+    ///
+    /// let input = "0011\n22zz";
+    /// let err = FromHexError::InvalidHexCharacter { c: 'z', index: 7 }.with_input(input);
+    /// assert_eq!(err.line(), Some(2));
+    /// assert_eq!(err.column(), Some(3));
+    /// assert_eq!(err.to_string(), "invalid character 'z' at line 2, column 3");
+    /// ```
+    #[inline]
+    pub fn with_input<T: AsRef<[u8]>>(self, input: T) -> FromHexErrorDetails {
+        FromHexErrorDetails::new(self, input.as_ref())
+    }
+}
+
+/// A [`FromHexError`] with additional context about the input that produced it.
+///
+/// This is a companion to [`FromHexError`] that carries the expected and actual
+/// lengths of the input, the line and column of invalid characters, and can
+/// render a caret-annotated [snippet](Self::snippet) of the input.
+///
+/// Created with [`FromHexError::with_input`], or returned by
+/// [`decode_to_slice_detailed`](crate::decode_to_slice_detailed).
+/// It can be converted back into a [`FromHexError`] with [`From`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::module_name_repetitions)]
+pub struct FromHexErrorDetails {
+    error: FromHexError,
+    expected_len: Option<usize>,
+    actual_len: usize,
+    line: usize,
+    column: usize,
+}
+
+impl FromHexErrorDetails {
+    fn new(error: FromHexError, input: &[u8]) -> Self {
+        let (line, column) = match error {
+            FromHexError::InvalidHexCharacter { index, .. } => {
+                let index = index.min(input.len());
+                let before = &input[..index];
+                let line_start = before
+                    .iter()
+                    .rposition(|&b| b == b'\n')
+                    .map_or(0, |i| i + 1);
+                let line = before.iter().filter(|&&b| b == b'\n').count();
+                (line + 1, index - line_start + 1)
+            }
+            _ => (0, 0),
+        };
+        Self {
+            error,
+            expected_len: None,
+            actual_len: crate::strip_prefix(input).len(),
+            line,
+            column,
+        }
+    }
+
+    /// Sets the expected number of hex digits, excluding the `0x` prefix.
+    #[inline]
+    pub const fn with_expected_len(mut self, expected_len: usize) -> Self {
+        self.expected_len = Some(expected_len);
+        self
+    }
+
+    /// Returns the underlying [`FromHexError`].
+    #[inline]
+    pub const fn error(&self) -> FromHexError {
+        self.error
+    }
+
+    /// Returns the expected number of hex digits, excluding the `0x` prefix,
+    /// if known.
+    #[inline]
+    pub const fn expected_len(&self) -> Option<usize> {
+        self.expected_len
+    }
+
+    /// Returns the actual number of hex digits in the input, excluding the
+    /// `0x` prefix.
+    #[inline]
+    pub const fn actual_len(&self) -> usize {
+        self.actual_len
+    }
+
+    /// Returns the byte offset of the invalid character in the input, if any.
+    #[inline]
+    pub const fn index(&self) -> Option<usize> {
+        match self.error {
+            FromHexError::InvalidHexCharacter { index, .. } => Some(index),
+            _ => None,
+        }
+    }
+
+    /// Returns the 1-based line of the invalid character in the input, if any.
+    #[inline]
+    pub const fn line(&self) -> Option<usize> {
+        if self.line == 0 {
+            None
+        } else {
+            Some(self.line)
+        }
+    }
+
+    /// Returns the 1-based column, in bytes, of the invalid character in the
+    /// input, if any.
+    #[inline]
+    pub const fn column(&self) -> Option<usize> {
+        if self.column == 0 {
+            None
+        } else {
+            Some(self.column)
+        }
+    }
+
+    /// Returns a value that renders the line of `input` containing the invalid
+    /// character, with a caret pointing at it.
+    ///
+    /// `input` must be the same input that was used to create this error.
+    /// Renders nothing if the error is not an invalid character error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let input = "0x00zz11";
+    /// let err = const_hex::check(input).unwrap_err().with_input(input);
+    /// assert_eq!(err.snippet(input).to_string(), "1 | 0x00zz11\n  |     ^\n");
+    /// ```
+    #[inline]
+    pub fn snippet<'a, T: AsRef<[u8]> + ?Sized>(&self, input: &'a T) -> ErrorSnippet<'a> {
+        ErrorSnippet {
+            input: input.as_ref(),
+            line: self.line,
+            column: self.column,
+        }
+    }
+}
+
+impl From<FromHexErrorDetails> for FromHexError {
+    #[inline]
+    fn from(e: FromHexErrorDetails) -> Self {
+        e.error
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<FromHexErrorDetails> for std::io::Error {
+    #[inline]
+    fn from(e: FromHexErrorDetails) -> Self {
+        Self::new(std::io::ErrorKind::InvalidData, e)
+    }
+}
+
+#[cfg(feature = "core-error")]
+impl core::error::Error for FromHexErrorDetails {
+    #[inline]
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+#[cfg(all(feature = "std", not(feature = "core-error")))]
+impl std::error::Error for FromHexErrorDetails {
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl fmt::Display for FromHexErrorDetails {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.error {
+            FromHexError::InvalidHexCharacter { c, .. } => write!(
+                f,
+                "invalid character {c:?} at line {}, column {}",
+                self.line, self.column
+            ),
+            FromHexError::OddLength => {
+                write!(f, "odd number of digits ({})", self.actual_len)
+            }
+            FromHexError::InvalidStringLength => match self.expected_len {
+                Some(expected) => write!(
+                    f,
+                    "invalid string length: expected {expected} digits, got {}",
+                    self.actual_len
+                ),
+                None => write!(f, "invalid string length ({} digits)", self.actual_len),
+            },
+        }
+    }
+}
+
+/// A caret-annotated snippet of the input of a [`FromHexErrorDetails`].
+///
+/// Created with [`FromHexErrorDetails::snippet`].
+#[derive(Clone, Copy, Debug)]
+pub struct ErrorSnippet<'a> {
+    input: &'a [u8],
+    line: usize,
+    column: usize,
+}
+
+impl fmt::Display for ErrorSnippet<'_> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        /// Maximum number of bytes to render on each side of the caret.
+        const CONTEXT: usize = 32;
+
+        if self.line == 0 {
+            return Ok(());
+        }
+        let line = match self.input.split(|&b| b == b'\n').nth(self.line - 1) {
+            Some(line) => line,
+            None => return Ok(()),
+        };
+
+        let col = self.column - 1;
+        let start = col.saturating_sub(CONTEXT);
+        let end = line.len().min(col + CONTEXT + 1);
+        let lead = if start > 0 { "..." } else { "" };
+        let trail = if end < line.len() { "..." } else { "" };
+
+        write!(f, "{} | {lead}", self.line)?;
+        for &b in &line[start.min(end)..end] {
+            let c = if b.is_ascii_graphic() || b == b' ' {
+                b as char
+            } else {
+                '.'
+            };
+            f.write_char(c)?;
+        }
+        writeln!(f, "{trail}")?;
+
+        let mut width = 1;
+        let mut line_no = self.line;
+        while line_no >= 10 {
+            line_no /= 10;
+            width += 1;
+        }
+        writeln!(
+            f,
+            "{:width$} | {:pad$}^",
+            "",
+            "",
+            pad = lead.len() + col - start
+        )
+    }
+}
+
 #[cfg(all(test, feature = "alloc"))]
 mod tests {
     use super::*;
@@ -57,4 +325,47 @@ mod tests {
             "invalid string length"
         );
     }
+
+    #[test]
+    fn test_details() {
+        let input = "0x00\r\n0011\n\t22";
+        let e = FromHexError::InvalidHexCharacter { c: '\r', index: 4 }.with_input(input);
+        assert_eq!(
+            (e.line(), e.column(), e.index()),
+            (Some(1), Some(5), Some(4))
+        );
+        let e = FromHexError::InvalidHexCharacter { c: '\t', index: 11 }.with_input(input);
+        assert_eq!((e.line(), e.column()), (Some(3), Some(1)));
+        assert_eq!(e.to_string(), "invalid character '\\t' at line 3, column 1");
+        assert_eq!(e.snippet(input).to_string(), "3 | .22\n  | ^\n");
+        let e = FromHexError::InvalidHexCharacter { c: '\r', index: 4 }.with_input(input);
+        assert_eq!(e.snippet(input).to_string(), "1 | 0x00.\n  |     ^\n");
+
+        let e = FromHexError::OddLength.with_input("0x123");
+        assert_eq!((e.line(), e.column(), e.actual_len()), (None, None, 3));
+        assert_eq!(e.to_string(), "odd number of digits (3)");
+        assert_eq!(e.snippet("0x123").to_string(), "");
+
+        let e = FromHexError::InvalidStringLength
+            .with_input("0x1234")
+            .with_expected_len(6);
+        assert_eq!(
+            e.to_string(),
+            "invalid string length: expected 6 digits, got 4"
+        );
+        assert_eq!(FromHexError::from(e), FromHexError::InvalidStringLength);
+    }
+
+    #[test]
+    fn test_snippet_truncated() {
+        let input = "0".repeat(100) + "z" + &"0".repeat(100);
+        let e = FromHexError::InvalidHexCharacter { c: 'z', index: 100 }.with_input(&input);
+        let expected = format!(
+            "1 | ...{}z{}...\n  | {}^\n",
+            "0".repeat(32),
+            "0".repeat(32),
+            " ".repeat(35)
+        );
+        assert_eq!(e.snippet(&input).to_string(), expected);
+    }
 }
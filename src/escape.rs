@@ -0,0 +1,612 @@
+//! Escaped byte string encoding and decoding, for generating C and Rust source code.
+//!
+//! Three formats are supported, each with an encoder and a matching decoder:
+//! - `\xNN` escape sequences, as used in C and Rust string literals: [`encode`], [`decode`];
+//! - comma-separated `0xNN` lists, as used in array initializers: [`encode_list`],
+//!   [`decode_list`];
+//! - Rust byte string literals (`b"..."`): [`encode_byte_string`], [`decode_byte_string`].
+//!
+//! Like the crate's other `_to_slice` functions, the `_to_str` and `_to_slice` variants write into
+//! a caller-provided buffer and do not allocate. Use [`escaped_len`], [`list_len`] and
+//! [`byte_string_len`] to size the buffers.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "alloc")] {
+//! use const_hex::escape;
+//!
+//! let data = [0xde, 0xad, 0xbe, 0xef];
+//! assert_eq!(escape::encode(data), r"\xde\xad\xbe\xef");
+//! assert_eq!(escape::encode_list(data, 2), "0xde, 0xad,\n0xbe, 0xef,");
+//! assert_eq!(escape::encode_byte_string(data), r#"b"\xde\xad\xbe\xef""#);
+//!
+//! assert_eq!(escape::decode(r"\xde\xad\xbe\xef"), Ok(data.to_vec()));
+//! assert_eq!(escape::decode_list("{ 0xde, 0xAD, 0xbe, 0xef }".trim_matches(['{', '}'])), Ok(data.to_vec()));
+//! assert_eq!(escape::decode_byte_string(r#"b"\xde\xad\xbe\xef""#), Ok(data.to_vec()));
+//! # }
+//! ```
+
+use crate::{byte2hex, FromHexError, HEX_DECODE_LUT, NIL};
+
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
+
+/// Returns the length of the `\xNN` escaped encoding of `len` bytes.
+#[inline]
+pub const fn escaped_len(len: usize) -> usize {
+    len * 4
+}
+
+/// Returns the length of the `0xNN` list encoding of `len` bytes.
+///
+/// This does not depend on the line wrapping.
+#[inline]
+pub const fn list_len(len: usize) -> usize {
+    if len == 0 {
+        0
+    } else {
+        len * 6 - 1
+    }
+}
+
+/// Returns the length of the Rust byte string literal encoding of `len` bytes.
+#[inline]
+pub const fn byte_string_len(len: usize) -> usize {
+    escaped_len(len) + 3
+}
+
+/// Encodes `data` as `\xNN` escape sequences using lowercase characters.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(const_hex::escape::encode(b"\x00\x7f\xff"), r"\x00\x7f\xff");
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn encode<T: AsRef<[u8]>>(data: T) -> String {
+    let data = data.as_ref();
+    alloc_str(escaped_len(data.len()), |out| {
+        write_escaped::<false>(data, out)
+    })
+}
+
+/// Encodes `data` as `\xNN` escape sequences using uppercase characters.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(const_hex::escape::encode_upper(b"\x00\x7f\xff"), r"\x00\x7F\xFF");
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn encode_upper<T: AsRef<[u8]>>(data: T) -> String {
+    let data = data.as_ref();
+    alloc_str(escaped_len(data.len()), |out| {
+        write_escaped::<true>(data, out)
+    })
+}
+
+/// Encodes `data` as `\xNN` escape sequences using lowercase characters into `output`.
+///
+/// # Errors
+///
+/// If `output.len()` is not [`escaped_len(data.len())`](escaped_len), returns
+/// [`FromHexError::InvalidStringLength`].
+///
+/// # Examples
+///
+/// ```
+/// let mut buf = [0; const_hex::escape::escaped_len(2)];
+/// assert_eq!(const_hex::escape::encode_to_str([0xde, 0xad], &mut buf).unwrap(), r"\xde\xad");
+/// ```
+#[inline]
+pub fn encode_to_str<T: AsRef<[u8]>>(data: T, output: &mut [u8]) -> Result<&mut str, FromHexError> {
+    let data = data.as_ref();
+    to_str(escaped_len(data.len()), output, |out| {
+        write_escaped::<false>(data, out)
+    })
+}
+
+/// Encodes `data` as `\xNN` escape sequences using uppercase characters into `output`.
+///
+/// Apart from the characters' casing, this works exactly like [`encode_to_str`].
+///
+/// # Errors
+///
+/// If `output.len()` is not [`escaped_len(data.len())`](escaped_len), returns
+/// [`FromHexError::InvalidStringLength`].
+///
+/// # Examples
+///
+/// ```
+/// let mut buf = [0; const_hex::escape::escaped_len(2)];
+/// assert_eq!(const_hex::escape::encode_to_str_upper([0xde, 0xad], &mut buf).unwrap(), r"\xDE\xAD");
+/// ```
+#[inline]
+pub fn encode_to_str_upper<T: AsRef<[u8]>>(
+    data: T,
+    output: &mut [u8],
+) -> Result<&mut str, FromHexError> {
+    let data = data.as_ref();
+    to_str(escaped_len(data.len()), output, |out| {
+        write_escaped::<true>(data, out)
+    })
+}
+
+/// Decodes `\xNN` escape sequences into bytes.
+///
+/// Both upper and lower case hex digits are accepted, but the `x` must be lowercase.
+///
+/// # Errors
+///
+/// Returns [`FromHexError::InvalidHexCharacter`] for anything that is not part of an escape
+/// sequence, and [`FromHexError::InvalidStringLength`] if the input ends within one.
+///
+/// # Examples
+///
+/// ```
+/// use const_hex::FromHexError;
+///
+/// assert_eq!(const_hex::escape::decode(r"\xde\xAD"), Ok(vec![0xde, 0xad]));
+/// assert_eq!(
+///     const_hex::escape::decode(r"\xde xad"),
+///     Err(FromHexError::InvalidHexCharacter { c: ' ', index: 4 })
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn decode<T: AsRef<[u8]>>(input: T) -> Result<Vec<u8>, FromHexError> {
+    let input = input.as_ref();
+    let mut output = Vec::with_capacity(input.len() / 4);
+    parse_escaped(input, |byte| {
+        output.push(byte);
+        Ok(())
+    })?;
+    Ok(output)
+}
+
+/// Decodes `\xNN` escape sequences into `output`.
+///
+/// # Errors
+///
+/// See [`decode`]. Additionally returns [`FromHexError::InvalidStringLength`] if `output` is not
+/// exactly a quarter of the length of `input`.
+#[inline]
+pub fn decode_to_slice<T: AsRef<[u8]>>(input: T, output: &mut [u8]) -> Result<(), FromHexError> {
+    let input = input.as_ref();
+    if input.len() % 4 == 0 && output.len() != input.len() / 4 {
+        return Err(FromHexError::InvalidStringLength);
+    }
+    let mut output = output.iter_mut();
+    parse_escaped(input, |byte| {
+        *output.next().ok_or(FromHexError::InvalidStringLength)? = byte;
+        Ok(())
+    })
+}
+
+/// Encodes `data` as a comma-separated list of `0xNN` literals using lowercase characters, with
+/// `per_line` literals per line. Every literal is followed by a comma, including the last one.
+///
+/// If `per_line` is zero, all literals are written on a single line.
+///
+/// # Examples
+///
+/// ```
+/// let data = [1, 2, 3, 4, 5];
+/// assert_eq!(const_hex::escape::encode_list(data, 0), "0x01, 0x02, 0x03, 0x04, 0x05,");
+/// assert_eq!(const_hex::escape::encode_list(data, 2), "0x01, 0x02,\n0x03, 0x04,\n0x05,");
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn encode_list<T: AsRef<[u8]>>(data: T, per_line: usize) -> String {
+    let data = data.as_ref();
+    alloc_str(list_len(data.len()), |out| {
+        write_list::<false>(data, per_line, out)
+    })
+}
+
+/// Encodes `data` as a comma-separated list of `0xNN` literals using uppercase characters.
+///
+/// Apart from the characters' casing, this works exactly like [`encode_list`].
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn encode_list_upper<T: AsRef<[u8]>>(data: T, per_line: usize) -> String {
+    let data = data.as_ref();
+    alloc_str(list_len(data.len()), |out| {
+        write_list::<true>(data, per_line, out)
+    })
+}
+
+/// Encodes `data` as a comma-separated list of `0xNN` literals using lowercase characters into
+/// `output`.
+///
+/// See [`encode_list`] for more details.
+///
+/// # Errors
+///
+/// If `output.len()` is not [`list_len(data.len())`](list_len), returns
+/// [`FromHexError::InvalidStringLength`].
+#[inline]
+pub fn encode_list_to_str<T: AsRef<[u8]>>(
+    data: T,
+    per_line: usize,
+    output: &mut [u8],
+) -> Result<&mut str, FromHexError> {
+    let data = data.as_ref();
+    to_str(list_len(data.len()), output, |out| {
+        write_list::<false>(data, per_line, out)
+    })
+}
+
+/// Encodes `data` as a comma-separated list of `0xNN` literals using uppercase characters into
+/// `output`.
+///
+/// Apart from the characters' casing, this works exactly like [`encode_list_to_str`].
+///
+/// # Errors
+///
+/// If `output.len()` is not [`list_len(data.len())`](list_len), returns
+/// [`FromHexError::InvalidStringLength`].
+#[inline]
+pub fn encode_list_to_str_upper<T: AsRef<[u8]>>(
+    data: T,
+    per_line: usize,
+    output: &mut [u8],
+) -> Result<&mut str, FromHexError> {
+    let data = data.as_ref();
+    to_str(list_len(data.len()), output, |out| {
+        write_list::<true>(data, per_line, out)
+    })
+}
+
+/// Decodes a comma-separated list of `0xNN` literals into bytes.
+///
+/// Each literal consists of a `0x` or `0X` prefix and one or two hex digits. Literals may be
+/// surrounded by any ASCII whitespace, and a trailing comma is allowed.
+///
+/// # Errors
+///
+/// Returns [`FromHexError::InvalidHexCharacter`] at the first unexpected character, and
+/// [`FromHexError::InvalidStringLength`] if the input ends within a literal.
+///
+/// # Examples
+///
+/// ```
+/// use const_hex::FromHexError;
+///
+/// assert_eq!(const_hex::escape::decode_list("0xde,0xAD,\n  0x0"), Ok(vec![0xde, 0xad, 0x00]));
+/// assert_eq!(
+///     const_hex::escape::decode_list("0xde,, 0xad"),
+///     Err(FromHexError::InvalidHexCharacter { c: ',', index: 5 })
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn decode_list<T: AsRef<[u8]>>(input: T) -> Result<Vec<u8>, FromHexError> {
+    let input = input.as_ref();
+    let mut output = Vec::with_capacity(input.len() / 6 + 1);
+    parse_list(input, |byte| {
+        output.push(byte);
+        Ok(())
+    })?;
+    Ok(output)
+}
+
+/// Decodes a comma-separated list of `0xNN` literals into `output`, returning the number of bytes
+/// written.
+///
+/// # Errors
+///
+/// See [`decode_list`]. Additionally returns [`FromHexError::InvalidStringLength`] if `output` is
+/// too short.
+#[inline]
+pub fn decode_list_to_slice<T: AsRef<[u8]>>(
+    input: T,
+    output: &mut [u8],
+) -> Result<usize, FromHexError> {
+    let mut written = 0;
+    parse_list(input.as_ref(), |byte| {
+        *output
+            .get_mut(written)
+            .ok_or(FromHexError::InvalidStringLength)? = byte;
+        written += 1;
+        Ok(())
+    })?;
+    Ok(written)
+}
+
+/// Encodes `data` as a Rust byte string literal, escaping every byte as `\xNN` using lowercase
+/// characters.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(const_hex::escape::encode_byte_string(b"hi"), r#"b"\x68\x69""#);
+/// assert_eq!(const_hex::escape::encode_byte_string([]), r#"b"""#);
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn encode_byte_string<T: AsRef<[u8]>>(data: T) -> String {
+    let data = data.as_ref();
+    alloc_str(byte_string_len(data.len()), |out| {
+        write_byte_string(data, out)
+    })
+}
+
+/// Encodes `data` as a Rust byte string literal into `output`.
+///
+/// See [`encode_byte_string`] for more details.
+///
+/// # Errors
+///
+/// If `output.len()` is not [`byte_string_len(data.len())`](byte_string_len), returns
+/// [`FromHexError::InvalidStringLength`].
+#[inline]
+pub fn encode_byte_string_to_str<T: AsRef<[u8]>>(
+    data: T,
+    output: &mut [u8],
+) -> Result<&mut str, FromHexError> {
+    let data = data.as_ref();
+    to_str(byte_string_len(data.len()), output, |out| {
+        write_byte_string(data, out)
+    })
+}
+
+/// Decodes a Rust byte string literal (`b"..."`) into bytes.
+///
+/// Printable ASCII characters, tabs, line breaks, `\xNN` escapes, the `\n`, `\r`, `\t`, `\\`, `\0`,
+/// `\'` and `\"` escapes, and line continuations (`\` at the end of a line) are supported. As in
+/// Rust source, a `\r\n` line break is read as `\n`, and any other control character, including a
+/// bare `\r`, is invalid. Raw byte strings are not supported.
+///
+/// # Errors
+///
+/// Returns [`FromHexError::InvalidHexCharacter`] at the first invalid character, and
+/// [`FromHexError::InvalidStringLength`] if the literal is not terminated.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(const_hex::escape::decode_byte_string(r#"b"a\x00\n\"""#), Ok(b"a\0\n\"".to_vec()));
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn decode_byte_string<T: AsRef<[u8]>>(input: T) -> Result<Vec<u8>, FromHexError> {
+    let input = input.as_ref();
+    let mut output = Vec::with_capacity(input.len().saturating_sub(3) / 4);
+    parse_byte_string(input, |byte| {
+        output.push(byte);
+        Ok(())
+    })?;
+    Ok(output)
+}
+
+/// Decodes a Rust byte string literal into `output`, returning the number of bytes written.
+///
+/// # Errors
+///
+/// See [`decode_byte_string`]. Additionally returns [`FromHexError::InvalidStringLength`] if
+/// `output` is too short.
+#[inline]
+pub fn decode_byte_string_to_slice<T: AsRef<[u8]>>(
+    input: T,
+    output: &mut [u8],
+) -> Result<usize, FromHexError> {
+    let mut written = 0;
+    parse_byte_string(input.as_ref(), |byte| {
+        *output
+            .get_mut(written)
+            .ok_or(FromHexError::InvalidStringLength)? = byte;
+        written += 1;
+        Ok(())
+    })?;
+    Ok(written)
+}
+
+#[cfg(feature = "alloc")]
+fn alloc_str(len: usize, write: impl FnOnce(&mut [u8])) -> String {
+    let mut buf = vec![0; len];
+    write(&mut buf);
+    // SAFETY: The writers only write ASCII.
+    unsafe { String::from_utf8_unchecked(buf) }
+}
+
+fn to_str(
+    len: usize,
+    output: &mut [u8],
+    write: impl FnOnce(&mut [u8]),
+) -> Result<&mut str, FromHexError> {
+    if output.len() != len {
+        return Err(FromHexError::InvalidStringLength);
+    }
+    write(output);
+    // SAFETY: The writers only write ASCII.
+    Ok(unsafe { core::str::from_utf8_unchecked_mut(output) })
+}
+
+fn write_escaped<const UPPER: bool>(data: &[u8], output: &mut [u8]) {
+    debug_assert_eq!(output.len(), escaped_len(data.len()));
+    for (&byte, out) in data.iter().zip(output.chunks_exact_mut(4)) {
+        let (high, low) = byte2hex::<UPPER>(byte);
+        out.copy_from_slice(&[b'\\', b'x', high, low]);
+    }
+}
+
+fn write_list<const UPPER: bool>(data: &[u8], per_line: usize, output: &mut [u8]) {
+    debug_assert_eq!(output.len(), list_len(data.len()));
+    for (i, &byte) in data.iter().enumerate() {
+        let (high, low) = byte2hex::<UPPER>(byte);
+        let out = &mut output[i * 6..];
+        out[..5].copy_from_slice(&[b'0', b'x', high, low, b',']);
+        if i + 1 < data.len() {
+            out[5] = if per_line != 0 && (i + 1) % per_line == 0 {
+                b'\n'
+            } else {
+                b' '
+            };
+        }
+    }
+}
+
+fn write_byte_string(data: &[u8], output: &mut [u8]) {
+    debug_assert_eq!(output.len(), byte_string_len(data.len()));
+    let end = output.len() - 1;
+    output[..2].copy_from_slice(b"b\"");
+    write_escaped::<false>(data, &mut output[2..end]);
+    output[end] = b'"';
+}
+
+/// Decodes a single hex digit at `index`.
+fn digit(input: &[u8], index: usize) -> Result<u8, FromHexError> {
+    match input.get(index) {
+        Some(&c) => match HEX_DECODE_LUT[c as usize] {
+            NIL => Err(invalid(input, index)),
+            value => Ok(value),
+        },
+        None => Err(FromHexError::InvalidStringLength),
+    }
+}
+
+/// Expects `expected` at `index`.
+fn expect(input: &[u8], index: usize, expected: u8) -> Result<(), FromHexError> {
+    match input.get(index) {
+        Some(&c) if c == expected => Ok(()),
+        Some(_) => Err(invalid(input, index)),
+        None => Err(FromHexError::InvalidStringLength),
+    }
+}
+
+fn invalid(input: &[u8], index: usize) -> FromHexError {
+    FromHexError::InvalidHexCharacter {
+        c: input[index] as char,
+        index,
+    }
+}
+
+fn parse_escaped(
+    input: &[u8],
+    mut push: impl FnMut(u8) -> Result<(), FromHexError>,
+) -> Result<(), FromHexError> {
+    let mut i = 0;
+    while i < input.len() {
+        expect(input, i, b'\\')?;
+        expect(input, i + 1, b'x')?;
+        push(digit(input, i + 2)? << 4 | digit(input, i + 3)?)?;
+        i += 4;
+    }
+    Ok(())
+}
+
+fn parse_list(
+    input: &[u8],
+    mut push: impl FnMut(u8) -> Result<(), FromHexError>,
+) -> Result<(), FromHexError> {
+    let skip_whitespace = |mut i: usize| {
+        while input.get(i).map_or(false, u8::is_ascii_whitespace) {
+            i += 1;
+        }
+        i
+    };
+
+    let mut i = skip_whitespace(0);
+    while i < input.len() {
+        // Literal.
+        expect(input, i, b'0')?;
+        match input.get(i + 1) {
+            Some(b'x' | b'X') => {}
+            Some(_) => return Err(invalid(input, i + 1)),
+            None => return Err(FromHexError::InvalidStringLength),
+        }
+        let mut byte = digit(input, i + 2)?;
+        i += 3;
+        if let Ok(low) = digit(input, i) {
+            byte = byte << 4 | low;
+            i += 1;
+        }
+        push(byte)?;
+
+        // Separator.
+        i = skip_whitespace(i);
+        if i < input.len() {
+            expect(input, i, b',')?;
+            i = skip_whitespace(i + 1);
+        }
+    }
+    Ok(())
+}
+
+/// Returns the length of the line ending at `index`, which is either `\n` or `\r\n`.
+fn newline_len(input: &[u8], index: usize) -> Option<usize> {
+    match input.get(index..)? {
+        [b'\n', ..] => Some(1),
+        [b'\r', b'\n', ..] => Some(2),
+        _ => None,
+    }
+}
+
+fn parse_byte_string(
+    input: &[u8],
+    mut push: impl FnMut(u8) -> Result<(), FromHexError>,
+) -> Result<(), FromHexError> {
+    expect(input, 0, b'b')?;
+    expect(input, 1, b'"')?;
+    let mut i = 2;
+    loop {
+        let byte = match input.get(i) {
+            Some(b'"') => break,
+            Some(b'\\') => match input.get(i + 1) {
+                Some(b'x') => {
+                    let byte = digit(input, i + 2)? << 4 | digit(input, i + 3)?;
+                    i += 4;
+                    byte
+                }
+                Some(b'\n' | b'\r') => {
+                    i += 1;
+                    loop {
+                        match input.get(i) {
+                            Some(b' ' | b'\t') => i += 1,
+                            _ => match newline_len(input, i) {
+                                Some(len) => i += len,
+                                None => break,
+                            },
+                        }
+                    }
+                    continue;
+                }
+                Some(&c) => {
+                    let byte = match c {
+                        b'n' => b'\n',
+                        b'r' => b'\r',
+                        b't' => b'\t',
+                        b'0' => b'\0',
+                        b'\\' | b'\'' | b'"' => c,
+                        _ => return Err(invalid(input, i + 1)),
+                    };
+                    i += 2;
+                    byte
+                }
+                None => return Err(FromHexError::InvalidStringLength),
+            },
+            Some(&c) if c == b'\t' || (b' '..=b'~').contains(&c) => {
+                i += 1;
+                c
+            }
+            Some(b'\n' | b'\r') => match newline_len(input, i) {
+                Some(len) => {
+                    i += len;
+                    b'\n'
+                }
+                None => return Err(invalid(input, i)),
+            },
+            Some(_) => return Err(invalid(input, i)),
+            None => return Err(FromHexError::InvalidStringLength),
+        };
+        push(byte)?;
+    }
+    if i + 1 != input.len() {
+        return Err(invalid(input, i + 1));
+    }
+    Ok(())
+}
@@ -0,0 +1,173 @@
+//! C ABI for non-Rust consumers.
+//!
+//! Exports the crate's encoding, decoding and checking routines as `extern "C"` functions, which
+//! use the same SIMD kernels as their Rust counterparts. The declarations are in
+//! [`include/const_hex.h`](https://github.com/danipopes/const-hex/blob/master/include/const_hex.h)
+//! at the root of the repository.
+//!
+//! Build a static or dynamic library with, for example:
+//!
+//! ```sh
+//! cargo rustc --release --lib --features ffi --crate-type staticlib
+//! ```
+//!
+//! All buffers are passed as a pointer and an explicit length in bytes, and strings are neither
+//! read nor written with a NUL terminator. A pointer may be null if its length is 0; otherwise it
+//! must be valid for the given length.
+
+// `#[inline]` has no effect on exported functions.
+#![allow(clippy::missing_inline_in_public_items)]
+
+use crate::FromHexError;
+use core::ffi::{c_char, c_int};
+use core::slice;
+
+/// The result of an FFI function, mirroring [`FromHexError`].
+///
+/// Corresponds to `const_hex_status` in C.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Status {
+    /// The function succeeded. `CONST_HEX_OK` in C.
+    Ok = 0,
+    /// [`FromHexError::InvalidHexCharacter`]. `CONST_HEX_INVALID_CHARACTER` in C.
+    InvalidCharacter = 1,
+    /// [`FromHexError::OddLength`]. `CONST_HEX_ODD_LENGTH` in C.
+    OddLength = 2,
+    /// [`FromHexError::InvalidStringLength`]. `CONST_HEX_INVALID_LENGTH` in C.
+    InvalidLength = 3,
+}
+
+impl Status {
+    /// Converts a result into a status, writing the index of an invalid character to `index`.
+    #[inline]
+    fn new(result: Result<(), FromHexError>, index: *mut usize) -> Self {
+        match result {
+            Ok(()) => Self::Ok,
+            Err(FromHexError::InvalidHexCharacter { index: i, .. }) => {
+                if !index.is_null() {
+                    // SAFETY: Guaranteed by the caller.
+                    unsafe { *index = i };
+                }
+                Self::InvalidCharacter
+            }
+            Err(FromHexError::OddLength) => Self::OddLength,
+            Err(FromHexError::InvalidStringLength) => Self::InvalidLength,
+        }
+    }
+}
+
+/// Encodes `input_len` bytes from `input` as hex into `output`, which must be exactly
+/// `2 * input_len` bytes long.
+///
+/// Writes upper case letters if `upper` is `true`. Does not write a prefix or a NUL terminator.
+///
+/// Returns `CONST_HEX_INVALID_LENGTH` if `output_len` is wrong, in which case nothing is written.
+///
+/// # Safety
+///
+/// `input` and `output` must be valid for reads and writes of their lengths respectively, or be
+/// null if their length is 0. They must not overlap.
+#[no_mangle]
+pub unsafe extern "C" fn const_hex_encode(
+    input: *const u8,
+    input_len: usize,
+    output: *mut c_char,
+    output_len: usize,
+    upper: bool,
+) -> Status {
+    let input = from_raw_parts(input, input_len);
+    let output = from_raw_parts_mut(output.cast::<u8>(), output_len);
+    let result = if upper {
+        crate::encode_to_slice_upper(input, output)
+    } else {
+        crate::encode_to_slice(input, output)
+    };
+    Status::new(result, core::ptr::null_mut())
+}
+
+/// Decodes `input_len` hex characters from `input` into `output`, which must be exactly
+/// `output_len` bytes long. An optional `0x` prefix is ignored.
+///
+/// On `CONST_HEX_INVALID_CHARACTER`, the position of the first invalid character in `input` is
+/// written to `error_index`, unless it is null. The contents of `output` are unspecified if
+/// decoding fails.
+///
+/// # Safety
+///
+/// `input` and `output` must be valid for reads and writes of their lengths respectively, or be
+/// null if their length is 0. They must not overlap. `error_index` must be null or valid for
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn const_hex_decode(
+    input: *const c_char,
+    input_len: usize,
+    output: *mut u8,
+    output_len: usize,
+    error_index: *mut usize,
+) -> Status {
+    let input = from_raw_parts(input.cast::<u8>(), input_len);
+    let output = from_raw_parts_mut(output, output_len);
+    let prefix_len = input.len() - crate::strip_prefix(input).len();
+    let result = crate::decode_to_slice(input, output);
+    Status::new(
+        result.map_err(|e| crate::offset_error(e, prefix_len)),
+        error_index,
+    )
+}
+
+/// Checks that `input_len` characters from `input` are valid hex. An optional `0x` prefix is
+/// ignored.
+///
+/// On `CONST_HEX_INVALID_CHARACTER`, the position of the first invalid character in `input` is
+/// written to `error_index`, unless it is null.
+///
+/// # Safety
+///
+/// `input` must be valid for reads of `input_len` bytes, or be null if `input_len` is 0.
+/// `error_index` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn const_hex_check(
+    input: *const c_char,
+    input_len: usize,
+    error_index: *mut usize,
+) -> Status {
+    let input = from_raw_parts(input.cast::<u8>(), input_len);
+    Status::new(crate::check(input), error_index)
+}
+
+/// Returns a static, NUL-terminated description of `status`, such as `"odd number of digits"`.
+///
+/// The descriptions match the messages of [`FromHexError`], without the position of an invalid
+/// character. Unknown values return `"unknown status"`.
+#[no_mangle]
+pub const extern "C" fn const_hex_decode_error(status: c_int) -> *const c_char {
+    let message: &'static [u8] = match status {
+        0 => b"success\0",
+        1 => b"invalid character\0",
+        2 => b"odd number of digits\0",
+        3 => b"invalid string length\0",
+        _ => b"unknown status\0",
+    };
+    message.as_ptr().cast()
+}
+
+/// Like [`slice::from_raw_parts`], but allows a null pointer if `len` is 0.
+#[inline]
+const unsafe fn from_raw_parts<'a>(ptr: *const u8, len: usize) -> &'a [u8] {
+    if len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(ptr, len)
+    }
+}
+
+/// Like [`slice::from_raw_parts_mut`], but allows a null pointer if `len` is 0.
+#[inline]
+unsafe fn from_raw_parts_mut<'a>(ptr: *mut u8, len: usize) -> &'a mut [u8] {
+    if len == 0 {
+        &mut []
+    } else {
+        slice::from_raw_parts_mut(ptr, len)
+    }
+}
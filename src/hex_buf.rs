@@ -0,0 +1,357 @@
+use crate::buffer::impl_string_value;
+use crate::{arch, byte2hex, invalid_hex_error, offset_error, strip_prefix, FromHexError};
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::Deref;
+use core::slice;
+use core::str::{self, FromStr};
+
+#[cfg(feature = "alloc")]
+#[allow(unused_imports)]
+use alloc::{string::String, vec::Vec};
+
+/// A stack allocation that can hold the hex representation of any slice of
+/// up to `CAP` bytes.
+///
+/// Unlike [`Buffer`](crate::Buffer), which always holds exactly `N` bytes,
+/// this buffer keeps track of the length of the last formatted input, like
+/// an `ArrayString`. Formatting a slice that is longer than `CAP` returns an
+/// error instead of panicking.
+///
+/// `PREFIX` specifies whether the "0x" prefix is prepended to the output. An
+/// empty buffer contains only the prefix, if specified.
+///
+/// Like `Buffer`, this is a plain string value: it is [`Copy`], dereferences
+/// to [`str`](prim@str), and compares, orders, hashes and displays exactly
+/// like its string contents.
+///
+/// # Examples
+///
+/// ```
+/// use const_hex::{FromHexError, HexBuf};
+///
+/// let mut buf = HexBuf::<4, true>::new();
+/// assert_eq!(buf, "0x");
+/// assert_eq!(buf.format(b"ab").unwrap(), "0x6162");
+/// assert_eq!(buf.format_upper(b"\xde\xad\xbe\xef").unwrap(), "0xDEADBEEF");
+/// assert_eq!(buf.format(b"kiwis"), Err(FromHexError::InvalidStringLength));
+/// assert_eq!(buf, "0xDEADBEEF");
+/// ```
+#[must_use]
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct HexBuf<const CAP: usize, const PREFIX: bool = false> {
+    // Same layout as `Buffer`, see the comment there.
+    prefix: [u8; 2],
+    bytes: [[u8; 2]; CAP],
+    /// The number of formatted input bytes.
+    len: usize,
+}
+
+impl<const CAP: usize, const PREFIX: bool> Default for HexBuf<CAP, PREFIX> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const CAP: usize, const PREFIX: bool> fmt::Debug for HexBuf<CAP, PREFIX> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("HexBuf").field(&self.as_str()).finish()
+    }
+}
+
+impl_string_value!(HexBuf);
+
+impl<const CAP: usize, const PREFIX: bool> FromStr for HexBuf<CAP, PREFIX> {
+    type Err = FromHexError;
+
+    /// Parses a hex string of at most `CAP` bytes, with or without a `0x`
+    /// prefix.
+    ///
+    /// The digits are stored as they are, without changing their case. The
+    /// prefix of the buffer is determined by `PREFIX`, not by the input.
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let input = s.as_bytes();
+        if input.len() % 2 != 0 {
+            return Err(FromHexError::OddLength);
+        }
+        let digits = strip_prefix(input);
+        if digits.len() > CAP * 2 {
+            return Err(FromHexError::InvalidStringLength);
+        }
+        if !arch::check(digits) {
+            let e = unsafe { invalid_hex_error(digits) };
+            return Err(offset_error(e, input.len() - digits.len()));
+        }
+        let mut buf = Self::new();
+        buf.len = digits.len() / 2;
+        // SAFETY: The digits are checked to be ASCII above.
+        unsafe { buf.buffer().copy_from_slice(digits) };
+        Ok(buf)
+    }
+}
+
+impl<const CAP: usize, const PREFIX: bool> TryFrom<&str> for HexBuf<CAP, PREFIX> {
+    type Error = FromHexError;
+
+    /// See [`FromStr`].
+    #[inline]
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a, const CAP: usize, const PREFIX: bool> arbitrary::Arbitrary<'a> for HexBuf<CAP, PREFIX> {
+    #[inline]
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let len = u.int_in_range(0..=CAP)?;
+        let bytes = u.bytes(len)?;
+        let buf = if bool::arbitrary(u)? {
+            Self::new().const_format_upper(bytes)
+        } else {
+            Self::new().const_format(bytes)
+        };
+        Ok(buf.unwrap_or_default())
+    }
+
+    #[inline]
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        let _ = depth;
+        (1, None)
+    }
+}
+
+impl<const CAP: usize, const PREFIX: bool> HexBuf<CAP, PREFIX> {
+    /// The maximum length of the buffer in bytes, including the prefix.
+    pub const CAPACITY: usize = (CAP + PREFIX as usize) * 2;
+
+    /// Creates a new empty buffer.
+    ///
+    /// This is a cheap operation; you don't need to worry about reusing buffers
+    /// for efficiency.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            prefix: if PREFIX { *b"0x" } else { [0, 0] },
+            bytes: [[0; 2]; CAP],
+            len: 0,
+        }
+    }
+
+    /// Print a slice of bytes into this buffer, replacing its contents.
+    ///
+    /// # Errors
+    ///
+    /// If the slice is longer than `CAP` bytes.
+    #[inline]
+    pub const fn const_format(self, slice: &[u8]) -> Result<Self, FromHexError> {
+        self.const_format_inner::<false>(slice)
+    }
+
+    /// Print a slice of bytes into this buffer, replacing its contents.
+    ///
+    /// # Errors
+    ///
+    /// If the slice is longer than `CAP` bytes.
+    #[inline]
+    pub const fn const_format_upper(self, slice: &[u8]) -> Result<Self, FromHexError> {
+        self.const_format_inner::<true>(slice)
+    }
+
+    /// Same as `encode_to_slice_inner`, but const-stable.
+    const fn const_format_inner<const UPPER: bool>(
+        mut self,
+        slice: &[u8],
+    ) -> Result<Self, FromHexError> {
+        if slice.len() > CAP {
+            return Err(FromHexError::InvalidStringLength);
+        }
+        let mut i = 0;
+        while i < slice.len() {
+            let (high, low) = byte2hex::<UPPER>(slice[i]);
+            self.bytes[i][0] = high;
+            self.bytes[i][1] = low;
+            i += 1;
+        }
+        self.len = slice.len();
+        Ok(self)
+    }
+
+    /// Print a slice of bytes into this buffer, replacing its contents, and
+    /// return a reference to its *lower* hex string representation within the
+    /// buffer.
+    ///
+    /// # Errors
+    ///
+    /// If the slice is longer than `CAP` bytes, in which case the buffer is
+    /// left unchanged.
+    #[inline]
+    pub fn format<T: AsRef<[u8]>>(&mut self, slice: T) -> Result<&mut str, FromHexError> {
+        self.format_inner::<false>(slice.as_ref())
+    }
+
+    /// Print a slice of bytes into this buffer, replacing its contents, and
+    /// return a reference to its *upper* hex string representation within the
+    /// buffer.
+    ///
+    /// # Errors
+    ///
+    /// If the slice is longer than `CAP` bytes, in which case the buffer is
+    /// left unchanged.
+    #[inline]
+    pub fn format_upper<T: AsRef<[u8]>>(&mut self, slice: T) -> Result<&mut str, FromHexError> {
+        self.format_inner::<true>(slice.as_ref())
+    }
+
+    fn format_inner<const UPPER: bool>(&mut self, input: &[u8]) -> Result<&mut str, FromHexError> {
+        if input.len() > CAP {
+            return Err(FromHexError::InvalidStringLength);
+        }
+        self.len = input.len();
+        // SAFETY: The output is exactly `input.len() * 2` bytes long;
+        // we only write only ASCII bytes.
+        unsafe {
+            let buf = self.as_mut_bytes();
+            let output = buf.get_unchecked_mut(PREFIX as usize * 2..);
+            arch::encode::<UPPER>(input, output);
+            Ok(str::from_utf8_unchecked_mut(buf))
+        }
+    }
+
+    /// Empties the buffer, leaving only the prefix, if specified.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Returns the number of input bytes that the buffer currently holds, which
+    /// is at most `CAP`.
+    ///
+    /// The length of the hex string itself is available through
+    /// [`str::len`].
+    #[inline]
+    pub const fn byte_len(&self) -> usize {
+        self.len
+    }
+
+    /// The length of the hex string in bytes, including the prefix.
+    const fn str_len(&self) -> usize {
+        (self.len + PREFIX as usize) * 2
+    }
+
+    /// Decodes the contents of the buffer back into `output`, which must be
+    /// exactly [`byte_len`](Self::byte_len) bytes long.
+    ///
+    /// # Errors
+    ///
+    /// If the length of `output` does not match.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut buf = const_hex::HexBuf::<8>::new();
+    /// buf.format(b"kiwi").unwrap();
+    /// let mut bytes = [0; 4];
+    /// buf.decode_to_slice(&mut bytes).unwrap();
+    /// assert_eq!(&bytes, b"kiwi");
+    /// ```
+    #[inline]
+    pub fn decode_to_slice(&self, output: &mut [u8]) -> Result<(), FromHexError> {
+        crate::decode_to_slice(self.as_bytes(), output)
+    }
+
+    /// Decodes the contents of the buffer back into a new `Vec`.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn decode(&self) -> Vec<u8> {
+        let mut output = alloc::vec![0; self.len];
+        // The contents are always valid hex of the right length.
+        let _ = self.decode_to_slice(&mut output);
+        output
+    }
+
+    /// Copies `self` into a new owned `String`.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    #[allow(clippy::inherent_to_string, clippy::inherent_to_string_shadow_display)] // this is intentional
+    pub fn to_string(&self) -> String {
+        // SAFETY: The buffer always contains valid UTF-8.
+        unsafe { String::from_utf8_unchecked(self.as_bytes().to_vec()) }
+    }
+
+    /// Returns a reference to the underlying bytes casted to a string slice.
+    #[inline]
+    pub const fn as_str(&self) -> &str {
+        // SAFETY: The buffer always contains valid UTF-8.
+        unsafe { str::from_utf8_unchecked(self.as_bytes()) }
+    }
+
+    /// Returns a mutable reference to the underlying bytes casted to a string
+    /// slice.
+    #[inline]
+    pub fn as_mut_str(&mut self) -> &mut str {
+        // SAFETY: The buffer always contains valid UTF-8.
+        unsafe { str::from_utf8_unchecked_mut(self.as_mut_bytes()) }
+    }
+
+    /// Copies `self` into a new `Vec`.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+
+    /// Returns a reference to the underlying bytes.
+    #[inline]
+    pub const fn as_bytes(&self) -> &[u8] {
+        // SAFETY: `prefix` and `bytes` form a contiguous `[u8; (CAP + 1) * 2]`
+        // in this `repr(C)` struct, and `len <= CAP`.
+        unsafe { slice::from_raw_parts(self.as_ptr(), self.str_len()) }
+    }
+
+    /// Returns a mutable reference to the underlying bytes.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the content of the slice is valid UTF-8
+    /// before the borrow ends and the underlying `str` is used.
+    ///
+    /// Use of a `str` whose contents are not valid UTF-8 is undefined behavior.
+    #[inline]
+    pub unsafe fn as_mut_bytes(&mut self) -> &mut [u8] {
+        // SAFETY: `prefix` and `bytes` form a contiguous `[u8; (CAP + 1) * 2]`
+        // in this `repr(C)` struct, and `len <= CAP`.
+        let len = self.str_len();
+        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), len) }
+    }
+
+    /// Returns a mutable reference to the formatted part of the buffer,
+    /// excluding the prefix.
+    unsafe fn buffer(&mut self) -> &mut [u8] {
+        unsafe { slice::from_raw_parts_mut(self.bytes.as_mut_ptr().cast(), self.len * 2) }
+    }
+
+    /// Returns a raw pointer to the buffer.
+    ///
+    /// The caller must ensure that the buffer outlives the pointer this
+    /// function returns, or else it will end up pointing to garbage.
+    #[inline]
+    pub const fn as_ptr(&self) -> *const u8 {
+        unsafe { (self as *const Self).cast::<u8>().add(!PREFIX as usize * 2) }
+    }
+
+    /// Returns an unsafe mutable pointer to the slice's buffer.
+    ///
+    /// The caller must ensure that the slice outlives the pointer this
+    /// function returns, or else it will end up pointing to garbage.
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut u8 {
+        unsafe { (self as *mut Self).cast::<u8>().add(!PREFIX as usize * 2) }
+    }
+}
@@ -0,0 +1,551 @@
+//! [Intel HEX](https://en.wikipedia.org/wiki/Intel_HEX) reading and writing.
+//!
+//! Records are decoded and encoded with the same SIMD implementations as the rest of the crate.
+//! Files are read into and written from sparse memory [`Image`]s.
+//!
+//! # Examples
+//!
+//! ```
+//! use const_hex::ihex;
+//! use const_hex::image::Image;
+//!
+//! let mut image = Image::new();
+//! image.insert(0x0001_0000, b"hello").unwrap();
+//! image.insert(0x0001_0010, b"world").unwrap();
+//! image.set_start_address(Some(0x0001_0000));
+//!
+//! let hex = ihex::encode(&image, ihex::DEFAULT_RECORD_LEN);
+//! assert_eq!(
+//!     hex,
+//!     "\
+//! :020000040001F9
+//! :0500000068656C6C6FE7
+//! :05001000776F726C64C3
+//! :0400000500010000F6
+//! :00000001FF
+//! "
+//! );
+//! assert_eq!(ihex::decode(&hex), Ok(image));
+//! ```
+
+use crate::image::{self, trim_end, Image, InsertError};
+use crate::FromHexError;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// The default number of data bytes per record used when writing.
+pub const DEFAULT_RECORD_LEN: u8 = 16;
+
+/// The maximum length of a record in bytes, excluding the start code: byte count, address, record
+/// type, 255 data bytes and checksum.
+const MAX_RAW_LEN: usize = 1 + 2 + 1 + 255 + 1;
+
+/// The maximum length of a line, including the start code and the trailing newline.
+const MAX_LINE_LEN: usize = 1 + MAX_RAW_LEN * 2 + 1;
+
+const DATA: u8 = 0x00;
+const END_OF_FILE: u8 = 0x01;
+const EXTENDED_SEGMENT_ADDRESS: u8 = 0x02;
+const START_SEGMENT_ADDRESS: u8 = 0x03;
+const EXTENDED_LINEAR_ADDRESS: u8 = 0x04;
+const START_LINEAR_ADDRESS: u8 = 0x05;
+
+/// An Intel HEX record.
+///
+/// Formatting a record with [`Display`](fmt::Display) writes it as a line, without the trailing
+/// newline. Formatting fails with [`fmt::Error`] if the record's data is too long.
+///
+/// # Examples
+///
+/// ```
+/// use const_hex::ihex::Record;
+///
+/// let record = Record::parse(":0300300002337A1E").unwrap();
+/// assert_eq!(record, Record::Data { offset: 0x0030, data: vec![0x02, 0x33, 0x7a] });
+/// assert_eq!(record.to_string(), ":0300300002337A1E");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Record {
+    /// Data at `offset` from the current base address (type `00`).
+    ///
+    /// `data` must not be longer than 255 bytes to be formatted.
+    Data {
+        /// The offset of the data from the current base address.
+        offset: u16,
+        /// The data.
+        data: Vec<u8>,
+    },
+    /// End of file (type `01`).
+    EndOfFile,
+    /// Sets the base address to the segment multiplied by 16 (type `02`).
+    ExtendedSegmentAddress(u16),
+    /// The 80x86 start address (type `03`).
+    StartSegmentAddress {
+        /// The code segment.
+        cs: u16,
+        /// The instruction pointer.
+        ip: u16,
+    },
+    /// Sets the upper 16 bits of the base address (type `04`).
+    ExtendedLinearAddress(u16),
+    /// The 32-bit start address (type `05`).
+    StartLinearAddress(u32),
+}
+
+impl Record {
+    /// Parses a single record line. Trailing whitespace, such as a line terminator, is ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the line is not a valid record, or if its checksum does not match.
+    #[inline]
+    pub fn parse<T: AsRef<[u8]>>(line: T) -> Result<Self, ErrorKind> {
+        let mut raw = [0u8; MAX_RAW_LEN];
+        let (record_type, address, payload) = parse_raw(line.as_ref(), &mut raw)?;
+        let payload_len = |len: usize| {
+            if payload.len() == len {
+                Ok(())
+            } else {
+                Err(ErrorKind::InvalidRecordLength {
+                    record_type,
+                    len: payload.len() as u8,
+                })
+            }
+        };
+        Ok(match record_type {
+            DATA => Self::Data {
+                offset: address,
+                data: payload.to_vec(),
+            },
+            END_OF_FILE => {
+                payload_len(0)?;
+                Self::EndOfFile
+            }
+            EXTENDED_SEGMENT_ADDRESS => {
+                payload_len(2)?;
+                Self::ExtendedSegmentAddress(u16::from_be_bytes([payload[0], payload[1]]))
+            }
+            START_SEGMENT_ADDRESS => {
+                payload_len(4)?;
+                Self::StartSegmentAddress {
+                    cs: u16::from_be_bytes([payload[0], payload[1]]),
+                    ip: u16::from_be_bytes([payload[2], payload[3]]),
+                }
+            }
+            EXTENDED_LINEAR_ADDRESS => {
+                payload_len(2)?;
+                Self::ExtendedLinearAddress(u16::from_be_bytes([payload[0], payload[1]]))
+            }
+            START_LINEAR_ADDRESS => {
+                payload_len(4)?;
+                Self::StartLinearAddress(u32::from_be_bytes([
+                    payload[0], payload[1], payload[2], payload[3],
+                ]))
+            }
+            _ => return Err(ErrorKind::UnsupportedRecordType(record_type)),
+        })
+    }
+
+    /// Returns the record type.
+    #[inline]
+    pub const fn record_type(&self) -> u8 {
+        match self {
+            Self::Data { .. } => DATA,
+            Self::EndOfFile => END_OF_FILE,
+            Self::ExtendedSegmentAddress(_) => EXTENDED_SEGMENT_ADDRESS,
+            Self::StartSegmentAddress { .. } => START_SEGMENT_ADDRESS,
+            Self::ExtendedLinearAddress(_) => EXTENDED_LINEAR_ADDRESS,
+            Self::StartLinearAddress(_) => START_LINEAR_ADDRESS,
+        }
+    }
+
+    /// Formats the record into `line`, or returns an error if its data is too long.
+    fn format<'a>(&self, line: &'a mut [u8; MAX_LINE_LEN]) -> Result<&'a str, fmt::Error> {
+        let mut payload = [0u8; 4];
+        let (address, payload) = match *self {
+            Self::Data { offset, ref data } if data.len() <= 255 => (offset, &data[..]),
+            Self::Data { .. } => return Err(fmt::Error),
+            Self::EndOfFile => (0, &[][..]),
+            Self::ExtendedSegmentAddress(segment) | Self::ExtendedLinearAddress(segment) => {
+                payload[..2].copy_from_slice(&segment.to_be_bytes());
+                (0, &payload[..2])
+            }
+            Self::StartSegmentAddress { cs, ip } => {
+                payload[..2].copy_from_slice(&cs.to_be_bytes());
+                payload[2..].copy_from_slice(&ip.to_be_bytes());
+                (0, &payload[..])
+            }
+            Self::StartLinearAddress(address) => {
+                payload = address.to_be_bytes();
+                (0, &payload[..])
+            }
+        };
+        Ok(format_raw(self.record_type(), address, payload, line))
+    }
+}
+
+impl fmt::Display for Record {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut line = [0u8; MAX_LINE_LEN];
+        f.write_str(self.format(&mut line)?.trim_end())
+    }
+}
+
+/// Decodes an Intel HEX file into a memory image.
+///
+/// Start segment addresses (`CS:IP`) are converted to linear addresses. Blank lines are ignored.
+/// Use [`Record::parse`] to access the records directly.
+///
+/// # Errors
+///
+/// Returns an error with the line number if a record is invalid, if data overlaps, or if the end
+/// of file record is missing or followed by other records.
+///
+/// # Examples
+///
+/// ```
+/// use const_hex::ihex::{self, ErrorKind};
+///
+/// let image = ihex::decode(":0300300002337A1E\n:00000001FF\n").unwrap();
+/// assert_eq!(image.segments()[0].address, 0x30);
+/// assert_eq!(image.segments()[0].data, [0x02, 0x33, 0x7a]);
+///
+/// let err = ihex::decode(":0300300002337A1F\n:00000001FF\n").unwrap_err();
+/// assert_eq!(err.line(), 1);
+/// assert_eq!(*err.kind(), ErrorKind::ChecksumMismatch { expected: 0x1e, actual: 0x1f });
+/// ```
+#[inline]
+pub fn decode<T: AsRef<[u8]>>(input: T) -> Result<Image, Error> {
+    image::decode::<Record>(input.as_ref())
+}
+
+/// Encodes a memory image as an Intel HEX file, with at most `record_len` data bytes per record.
+///
+/// Extended linear address records are emitted as needed, and data records never cross a 64 KiB
+/// boundary. The start address, if any, is written as a start linear address record.
+///
+/// # Panics
+///
+/// Panics if `record_len` is zero.
+#[inline]
+pub fn encode(image: &Image, record_len: u8) -> String {
+    image::encode::<Record>(image, record_len)
+}
+
+/// An error that occurred while decoding an Intel HEX file.
+pub type Error = image::Error<ErrorKind>;
+
+/// The kind of an [`Error`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The line does not start with `:`.
+    MissingStartCode,
+    /// The line contains invalid hex. The index of an invalid character is relative to the start
+    /// of the line.
+    InvalidHex(FromHexError),
+    /// The line is too short to contain a record.
+    RecordTooShort,
+    /// The line length does not match the record's byte count.
+    LengthMismatch {
+        /// The expected line length, in characters.
+        expected: usize,
+        /// The actual line length, in characters.
+        actual: usize,
+    },
+    /// The record checksum is incorrect.
+    ChecksumMismatch {
+        /// The computed checksum.
+        expected: u8,
+        /// The checksum in the record.
+        actual: u8,
+    },
+    /// The record type is unknown.
+    UnsupportedRecordType(u8),
+    /// The record has an invalid byte count for its type.
+    InvalidRecordLength {
+        /// The record type.
+        record_type: u8,
+        /// The byte count.
+        len: u8,
+    },
+    /// The file does not end with an end of file record.
+    MissingEndOfFile,
+    /// A record follows the end of file record.
+    RecordAfterEndOfFile,
+    /// Data could not be inserted into the image.
+    Image(InsertError),
+}
+
+impl fmt::Display for ErrorKind {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::MissingStartCode => f.write_str("missing start code ':'"),
+            Self::InvalidHex(e) => e.fmt(f),
+            Self::RecordTooShort => f.write_str("record too short"),
+            Self::LengthMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "invalid record length: expected {expected} characters, got {actual}"
+                )
+            }
+            Self::ChecksumMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "checksum mismatch: expected {expected:02X}, got {actual:02X}"
+                )
+            }
+            Self::UnsupportedRecordType(t) => write!(f, "unsupported record type {t:02X}"),
+            Self::InvalidRecordLength { record_type, len } => {
+                write!(
+                    f,
+                    "invalid byte count {len} for record type {record_type:02X}"
+                )
+            }
+            Self::MissingEndOfFile => f.write_str("missing end of file record"),
+            Self::RecordAfterEndOfFile => f.write_str("record after end of file record"),
+            Self::Image(e) => e.fmt(f),
+        }
+    }
+}
+
+/// A streaming Intel HEX record reader.
+///
+/// Yields each record in a [`BufRead`](std::io::BufRead), skipping blank lines. Errors are
+/// returned as [`InvalidData`](std::io::ErrorKind::InvalidData) wrapping an [`Error`] with the
+/// line number.
+///
+/// # Examples
+///
+/// ```
+/// use const_hex::ihex::{Reader, Record};
+///
+/// let input = ":0300300002337A1E\r\n:00000001FF\r\n";
+/// let records = Reader::new(input.as_bytes()).collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(records[1], Record::EndOfFile);
+/// ```
+#[cfg(feature = "std")]
+pub type Reader<R> = image::Reader<R, Record>;
+
+/// Reads an Intel HEX file into a memory image.
+///
+/// See [`decode`] for more details.
+///
+/// # Errors
+///
+/// Returns an error if reading fails, or an [`InvalidData`](std::io::ErrorKind::InvalidData)
+/// error wrapping an [`Error`] if the file is invalid.
+#[cfg(feature = "std")]
+#[inline]
+pub fn read<R: std::io::Read>(reader: R) -> std::io::Result<Image> {
+    image::read::<Record>(reader)
+}
+
+/// Writes a memory image as an Intel HEX file.
+///
+/// See [`encode`] for more details.
+///
+/// # Errors
+///
+/// Returns an error if writing fails.
+///
+/// # Panics
+///
+/// Panics if `record_len` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use const_hex::ihex;
+/// use const_hex::image::Image;
+///
+/// let image = Image::from_bytes(0x100, &[0xaa; 40]).unwrap();
+/// let mut out = Vec::new();
+/// ihex::write(&mut out, &image, 32).unwrap();
+/// assert_eq!(ihex::read(&out[..]).unwrap(), image);
+/// ```
+#[cfg(feature = "std")]
+#[inline]
+pub fn write<W: std::io::Write>(writer: W, image: &Image, record_len: u8) -> std::io::Result<()> {
+    image::write::<Record>(writer, image, record_len)
+}
+
+impl image::Record for Record {}
+
+impl image::sealed::Sealed for Record {
+    type ErrorKind = ErrorKind;
+
+    #[inline]
+    fn parse(line: &[u8]) -> Result<Self, ErrorKind> {
+        Self::parse(line)
+    }
+}
+
+impl image::Format for Record {
+    type Loader = Loader;
+
+    #[inline]
+    fn write_lines<E>(
+        image: &Image,
+        record_len: u8,
+        mut emit: impl FnMut(&str) -> Result<(), E>,
+    ) -> Result<(), E> {
+        let mut line = [0u8; MAX_LINE_LEN];
+        write_records(image, record_len, |record_type, address, payload| {
+            emit(format_raw(record_type, address, payload, &mut line))
+        })
+    }
+}
+
+/// Builds an image from records.
+#[derive(Default)]
+pub(crate) struct Loader {
+    image: Image,
+    base: u32,
+    eof: bool,
+}
+
+impl image::Loader<Record> for Loader {
+    #[inline]
+    fn push(&mut self, record: Record) -> Result<(), ErrorKind> {
+        if self.eof {
+            return Err(ErrorKind::RecordAfterEndOfFile);
+        }
+        match record {
+            Record::Data { offset, data } => {
+                let address = self.base + offset as u32;
+                self.image
+                    .insert(address, &data)
+                    .map_err(ErrorKind::Image)?;
+            }
+            Record::EndOfFile => self.eof = true,
+            Record::ExtendedSegmentAddress(segment) => self.base = (segment as u32) << 4,
+            Record::StartSegmentAddress { cs, ip } => {
+                self.image
+                    .set_start_address(Some(((cs as u32) << 4) + ip as u32));
+            }
+            Record::ExtendedLinearAddress(upper) => self.base = (upper as u32) << 16,
+            Record::StartLinearAddress(address) => self.image.set_start_address(Some(address)),
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn finish(self) -> Result<Image, ErrorKind> {
+        if self.eof {
+            Ok(self.image)
+        } else {
+            Err(ErrorKind::MissingEndOfFile)
+        }
+    }
+}
+
+/// Splits `image` into raw records and passes them to `emit`.
+fn write_records<E>(
+    image: &Image,
+    record_len: u8,
+    mut emit: impl FnMut(u8, u16, &[u8]) -> Result<(), E>,
+) -> Result<(), E> {
+    assert!(record_len != 0, "record length must not be zero");
+    let mut upper = 0;
+    for segment in image.segments() {
+        let mut address = segment.address;
+        let mut data = &segment.data[..];
+        while !data.is_empty() {
+            if address >> 16 != upper {
+                upper = address >> 16;
+                emit(EXTENDED_LINEAR_ADDRESS, 0, &(upper as u16).to_be_bytes())?;
+            }
+            let until_boundary = 0x1_0000 - (address & 0xffff) as usize;
+            let len = data.len().min(record_len as usize).min(until_boundary);
+            emit(DATA, address as u16, &data[..len])?;
+            address = address.wrapping_add(len as u32);
+            data = &data[len..];
+        }
+    }
+    if let Some(start) = image.start_address() {
+        emit(START_LINEAR_ADDRESS, 0, &start.to_be_bytes())?;
+    }
+    emit(END_OF_FILE, 0, &[])
+}
+
+/// Parses a record line into `raw`, returning the record type, address and payload.
+fn parse_raw<'a>(
+    line: &[u8],
+    raw: &'a mut [u8; MAX_RAW_LEN],
+) -> Result<(u8, u16, &'a [u8]), ErrorKind> {
+    let line = trim_end(line);
+    let hex = match line {
+        [b':', hex @ ..] => hex,
+        _ => return Err(ErrorKind::MissingStartCode),
+    };
+    let invalid_hex = |e| ErrorKind::InvalidHex(crate::offset_error(e, 1));
+    if hex.len() < 10 {
+        return Err(ErrorKind::RecordTooShort);
+    }
+
+    let mut count = [0u8];
+    crate::decode_to_slice_unprefixed(&hex[..2], &mut count).map_err(invalid_hex)?;
+    let len = count[0] as usize + 5;
+    if hex.len() != len * 2 {
+        return Err(ErrorKind::LengthMismatch {
+            expected: 1 + len * 2,
+            actual: line.len(),
+        });
+    }
+
+    let raw = &mut raw[..len];
+    crate::decode_to_slice_unprefixed(hex, raw).map_err(invalid_hex)?;
+    let (checksum, record) = raw.split_last().unwrap();
+    let expected = checksum_of(record);
+    if *checksum != expected {
+        return Err(ErrorKind::ChecksumMismatch {
+            expected,
+            actual: *checksum,
+        });
+    }
+    Ok((
+        record[3],
+        u16::from_be_bytes([record[1], record[2]]),
+        &record[4..],
+    ))
+}
+
+/// Formats a record into `line`, including the trailing newline.
+fn format_raw<'a>(
+    record_type: u8,
+    address: u16,
+    payload: &[u8],
+    line: &'a mut [u8; MAX_LINE_LEN],
+) -> &'a str {
+    assert!(
+        payload.len() <= 255,
+        "record data must not be longer than 255 bytes"
+    );
+    let mut raw = [0u8; MAX_RAW_LEN];
+    let len = payload.len() + 5;
+    raw[0] = payload.len() as u8;
+    raw[1..3].copy_from_slice(&address.to_be_bytes());
+    raw[3] = record_type;
+    raw[4..len - 1].copy_from_slice(payload);
+    raw[len - 1] = checksum_of(&raw[..len - 1]);
+
+    let line = &mut line[..1 + len * 2 + 1];
+    line[0] = b':';
+    line[line.len() - 1] = b'\n';
+    let end = line.len() - 1;
+    crate::encode_to_slice_upper(&raw[..len], &mut line[1..end]).unwrap();
+    // SAFETY: Only ASCII was written.
+    unsafe { core::str::from_utf8_unchecked(line) }
+}
+
+/// Returns the two's complement of the sum of `bytes`.
+fn checksum_of(bytes: &[u8]) -> u8 {
+    bytes
+        .iter()
+        .fold(0u8, |sum, &b| sum.wrapping_add(b))
+        .wrapping_neg()
+}
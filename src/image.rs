@@ -0,0 +1,487 @@
+//! Sparse memory images, as read and written by the [`ihex`](crate::ihex) and [`srec`](crate::srec)
+//! modules.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// A contiguous run of bytes in an [`Image`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Segment {
+    /// The address of the first byte.
+    pub address: u32,
+    /// The bytes.
+    pub data: Vec<u8>,
+}
+
+impl Segment {
+    /// Returns the address one past the last byte of the segment.
+    ///
+    /// This is a `u64` because a segment may end exactly at the end of the 32-bit address space.
+    #[inline]
+    pub fn end(&self) -> u64 {
+        self.address as u64 + self.data.len() as u64
+    }
+}
+
+/// A sparse memory image: non-overlapping [`Segment`]s of data sorted by address, and an optional
+/// start address.
+///
+/// Adjacent data is merged into a single segment, so gaps in the address space are exactly the
+/// gaps between segments.
+///
+/// # Examples
+///
+/// ```
+/// use const_hex::image::Image;
+///
+/// let mut image = Image::new();
+/// image.insert(0x1000, &[1, 2]).unwrap();
+/// image.insert(0x1004, &[5]).unwrap();
+/// image.insert(0x1002, &[3, 4]).unwrap();
+/// assert_eq!(image.segments().len(), 1);
+/// assert_eq!(image.segments()[0].data, [1, 2, 3, 4, 5]);
+///
+/// assert!(image.insert(0x1003, &[0]).is_err());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Image {
+    segments: Vec<Segment>,
+    start_address: Option<u32>,
+}
+
+impl Image {
+    /// Creates a new empty image.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            segments: Vec::new(),
+            start_address: None,
+        }
+    }
+
+    /// Creates a new image containing `data` at `address`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InsertError::AddressOverflow`] if `data` does not fit in the 32-bit address space.
+    #[inline]
+    pub fn from_bytes(address: u32, data: &[u8]) -> Result<Self, InsertError> {
+        let mut image = Self::new();
+        image.insert(address, data)?;
+        Ok(image)
+    }
+
+    /// Returns the segments of the image, sorted by address.
+    #[inline]
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Consumes the image, returning its segments.
+    #[inline]
+    pub fn into_segments(self) -> Vec<Segment> {
+        self.segments
+    }
+
+    /// Returns `true` if the image contains no data.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Returns the total number of data bytes in the image, excluding gaps.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.segments.iter().map(|s| s.data.len()).sum()
+    }
+
+    /// Returns the start (entry point) address, if any.
+    #[inline]
+    pub const fn start_address(&self) -> Option<u32> {
+        self.start_address
+    }
+
+    /// Sets the start (entry point) address.
+    #[inline]
+    pub fn set_start_address(&mut self, address: Option<u32>) {
+        self.start_address = address;
+    }
+
+    /// Inserts `data` at `address`, merging it with any adjacent segments.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, leaving the image unchanged, if `data` overlaps existing data or does not
+    /// fit in the 32-bit address space.
+    #[inline]
+    pub fn insert(&mut self, address: u32, data: &[u8]) -> Result<(), InsertError> {
+        let end = address as u64 + data.len() as u64;
+        if end > 1 << 32 {
+            return Err(InsertError::AddressOverflow);
+        }
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        // Index of the first segment that starts after `address`.
+        let i = self.segments.partition_point(|s| s.address <= address);
+        if let Some(prev) = i.checked_sub(1).map(|i| &self.segments[i]) {
+            if prev.end() > address as u64 {
+                return Err(InsertError::Overlap { address });
+            }
+        }
+        if let Some(next) = self.segments.get(i) {
+            if end > next.address as u64 {
+                return Err(InsertError::Overlap {
+                    address: next.address,
+                });
+            }
+        }
+
+        let merge_prev = i > 0 && self.segments[i - 1].end() == address as u64;
+        let merge_next = self
+            .segments
+            .get(i)
+            .map_or(false, |next| next.address as u64 == end);
+        match (merge_prev, merge_next) {
+            (true, true) => {
+                let next = self.segments.remove(i);
+                let prev = &mut self.segments[i - 1];
+                prev.data.extend_from_slice(data);
+                prev.data.extend_from_slice(&next.data);
+            }
+            (true, false) => self.segments[i - 1].data.extend_from_slice(data),
+            (false, true) => {
+                let next = &mut self.segments[i];
+                next.data.splice(0..0, data.iter().copied());
+                next.address = address;
+            }
+            (false, false) => {
+                self.segments.insert(
+                    i,
+                    Segment {
+                        address,
+                        data: data.to_vec(),
+                    },
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the byte at `address`, if any.
+    #[inline]
+    pub fn get(&self, address: u32) -> Option<u8> {
+        let i = self
+            .segments
+            .partition_point(|s| s.address <= address)
+            .checked_sub(1)?;
+        let segment = &self.segments[i];
+        segment
+            .data
+            .get((address - segment.address) as usize)
+            .copied()
+    }
+
+    /// Returns the image as a single contiguous byte vector starting at the lowest address, filling
+    /// the gaps between segments with `fill`.
+    ///
+    /// Returns `None` if the image is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use const_hex::image::Image;
+    ///
+    /// let mut image = Image::new();
+    /// image.insert(0x10, &[1, 2]).unwrap();
+    /// image.insert(0x14, &[5]).unwrap();
+    /// assert_eq!(image.to_contiguous(0xff), Some((0x10, vec![1, 2, 0xff, 0xff, 5])));
+    /// ```
+    #[inline]
+    pub fn to_contiguous(&self, fill: u8) -> Option<(u32, Vec<u8>)> {
+        let first = self.segments.first()?;
+        let last = self.segments.last()?;
+        let mut bytes = Vec::with_capacity((last.end() - first.address as u64) as usize);
+        for segment in &self.segments {
+            let gap = (segment.address - first.address) as usize - bytes.len();
+            bytes.resize(bytes.len() + gap, fill);
+            bytes.extend_from_slice(&segment.data);
+        }
+        Some((first.address, bytes))
+    }
+}
+
+/// The error returned by [`Image::insert`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InsertError {
+    /// The data overlaps existing data, starting at `address`.
+    Overlap {
+        /// The first overlapping address.
+        address: u32,
+    },
+    /// The data does not fit in the 32-bit address space.
+    AddressOverflow,
+}
+
+#[cfg(feature = "core-error")]
+impl core::error::Error for InsertError {}
+#[cfg(all(feature = "std", not(feature = "core-error")))]
+impl std::error::Error for InsertError {}
+
+impl fmt::Display for InsertError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Overlap { address } => write!(f, "data overlaps at address {address:#010x}"),
+            Self::AddressOverflow => f.write_str("data does not fit in the 32-bit address space"),
+        }
+    }
+}
+
+/// A record of a line-based file format that describes an [`Image`], such as
+/// [`ihex::Record`](crate::ihex::Record) or [`srec::Record`](crate::srec::Record).
+///
+/// This trait is sealed and cannot be implemented outside of this crate.
+pub trait Record: sealed::Sealed {}
+
+pub(crate) mod sealed {
+    pub trait Sealed: Sized {
+        /// The format-specific kind of error.
+        type ErrorKind;
+
+        /// Parses a single record line. Trailing whitespace is ignored.
+        fn parse(line: &[u8]) -> Result<Self, Self::ErrorKind>;
+    }
+}
+
+/// Builds images from [`Record`]s and splits them back into record lines.
+pub(crate) trait Format: Record {
+    /// The state of an image being built.
+    type Loader: Loader<Self> + Default;
+
+    /// Splits `image` into record lines, including the trailing newline, and passes them to
+    /// `emit`.
+    fn write_lines<E>(
+        image: &Image,
+        record_len: u8,
+        emit: impl FnMut(&str) -> Result<(), E>,
+    ) -> Result<(), E>;
+}
+
+/// Builds an image from `T` records.
+pub(crate) trait Loader<T: Record> {
+    fn push(&mut self, record: T) -> Result<(), T::ErrorKind>;
+
+    fn finish(self) -> Result<Image, T::ErrorKind>;
+}
+
+/// An error that occurred while decoding a file of [`Record`]s, with the line at which it
+/// occurred.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error<K> {
+    line: usize,
+    kind: K,
+}
+
+impl<K> Error<K> {
+    /// Returns the 1-based line number at which the error occurred.
+    #[inline]
+    pub const fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the kind of error.
+    #[inline]
+    pub const fn kind(&self) -> &K {
+        &self.kind
+    }
+}
+
+#[cfg(feature = "core-error")]
+impl<K: fmt::Debug + fmt::Display> core::error::Error for Error<K> {}
+#[cfg(all(feature = "std", not(feature = "core-error")))]
+impl<K: fmt::Debug + fmt::Display> std::error::Error for Error<K> {}
+
+#[cfg(feature = "std")]
+impl<K: fmt::Debug + fmt::Display + Send + Sync + 'static> From<Error<K>> for std::io::Error {
+    #[inline]
+    fn from(e: Error<K>) -> Self {
+        Self::new(std::io::ErrorKind::InvalidData, e)
+    }
+}
+
+impl<K: fmt::Display> fmt::Display for Error<K> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.kind)
+    }
+}
+
+/// Decodes a file of `T` records into a memory image.
+pub(crate) fn decode<T: Format>(input: &[u8]) -> Result<Image, Error<T::ErrorKind>> {
+    let mut loader = T::Loader::default();
+    let mut line = 0;
+    for (i, text) in lines(input).enumerate() {
+        line = i + 1;
+        if !is_blank(text) {
+            let record = T::parse(text).map_err(|kind| Error { line, kind })?;
+            loader.push(record).map_err(|kind| Error { line, kind })?;
+        }
+    }
+    loader.finish().map_err(|kind| Error { line, kind })
+}
+
+/// Encodes a memory image as a file of `T` records.
+pub(crate) fn encode<T: Format>(image: &Image, record_len: u8) -> String {
+    let mut s = String::with_capacity(image.len() * 2 + image.len() / 4 + 32);
+    T::write_lines(image, record_len, |line| {
+        s.push_str(line);
+        Ok::<_, core::convert::Infallible>(())
+    })
+    .unwrap_or_else(|e| match e {});
+    s
+}
+
+#[cfg(feature = "std")]
+pub use io::Reader;
+#[cfg(feature = "std")]
+pub(crate) use io::{read, write};
+
+#[cfg(feature = "std")]
+mod io {
+    use super::*;
+    use core::marker::PhantomData;
+    use std::io::{self, BufRead, BufReader};
+
+    /// A streaming reader of `T` records.
+    ///
+    /// Yields each record in a [`BufRead`], skipping blank lines. Errors are returned as
+    /// [`io::ErrorKind::InvalidData`] wrapping an [`Error`](super::Error) with the line number.
+    ///
+    /// See [`ihex::Reader`](crate::ihex::Reader) and [`srec::Reader`](crate::srec::Reader).
+    pub struct Reader<R, T> {
+        reader: R,
+        line: usize,
+        buf: Vec<u8>,
+        _record: PhantomData<fn() -> T>,
+    }
+
+    impl<R: fmt::Debug, T> fmt::Debug for Reader<R, T> {
+        #[inline]
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("Reader")
+                .field("reader", &self.reader)
+                .field("line", &self.line)
+                .finish_non_exhaustive()
+        }
+    }
+
+    impl<R: BufRead, T: Record> Reader<R, T> {
+        /// Creates a new reader.
+        #[inline]
+        pub const fn new(reader: R) -> Self {
+            Self {
+                reader,
+                line: 0,
+                buf: Vec::new(),
+                _record: PhantomData,
+            }
+        }
+
+        /// Returns the 1-based number of the last line read.
+        #[inline]
+        pub const fn line(&self) -> usize {
+            self.line
+        }
+
+        /// Consumes the reader, returning the underlying reader.
+        #[inline]
+        pub fn into_inner(self) -> R {
+            self.reader
+        }
+    }
+
+    impl<R, T: Record> Reader<R, T>
+    where
+        T::ErrorKind: fmt::Debug + fmt::Display + Send + Sync + 'static,
+    {
+        fn error(&self, kind: T::ErrorKind) -> io::Error {
+            Error {
+                line: self.line,
+                kind,
+            }
+            .into()
+        }
+    }
+
+    impl<R: BufRead, T: Record> Iterator for Reader<R, T>
+    where
+        T::ErrorKind: fmt::Debug + fmt::Display + Send + Sync + 'static,
+    {
+        type Item = io::Result<T>;
+
+        #[inline]
+        fn next(&mut self) -> Option<Self::Item> {
+            loop {
+                self.buf.clear();
+                match self.reader.read_until(b'\n', &mut self.buf) {
+                    Ok(0) => return None,
+                    Ok(_) => {}
+                    Err(e) => return Some(Err(e)),
+                }
+                self.line += 1;
+                if !is_blank(&self.buf) {
+                    return Some(T::parse(&self.buf).map_err(|kind| self.error(kind)));
+                }
+            }
+        }
+    }
+
+    /// Reads a file of `T` records into a memory image.
+    pub(crate) fn read<T: Format>(reader: impl io::Read) -> io::Result<Image>
+    where
+        T::ErrorKind: fmt::Debug + fmt::Display + Send + Sync + 'static,
+    {
+        let mut reader = Reader::<_, T>::new(BufReader::new(reader));
+        let mut loader = T::Loader::default();
+        while let Some(record) = reader.next() {
+            loader.push(record?).map_err(|kind| reader.error(kind))?;
+        }
+        loader.finish().map_err(|kind| reader.error(kind))
+    }
+
+    /// Writes a memory image as a file of `T` records.
+    pub(crate) fn write<T: Format>(
+        mut writer: impl io::Write,
+        image: &Image,
+        record_len: u8,
+    ) -> io::Result<()> {
+        T::write_lines(image, record_len, |line| writer.write_all(line.as_bytes()))
+    }
+}
+
+/// Splits `input` into lines, ignoring a trailing newline.
+fn lines(input: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let input = input.strip_suffix(b"\n").unwrap_or(input);
+    (!input.is_empty())
+        .then(|| input.split(|&b| b == b'\n'))
+        .into_iter()
+        .flatten()
+}
+
+/// Trims trailing ASCII whitespace, such as line terminators.
+pub(crate) fn trim_end(mut s: &[u8]) -> &[u8] {
+    while let [rest @ .., last] = s {
+        if !last.is_ascii_whitespace() {
+            break;
+        }
+        s = rest;
+    }
+    s
+}
+
+fn is_blank(s: &[u8]) -> bool {
+    trim_end(s).is_empty()
+}
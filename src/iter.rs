@@ -0,0 +1,410 @@
+//! Lazy, iterator-based encoding and decoding.
+//!
+//! [`encode_iter`] yields the hex encoding of a byte slice one ASCII byte at a time, and
+//! [`encode_pairs`] one `[u8; 2]` digit pair per input byte. Both encode the input in chunks with
+//! the SIMD kernels, so they are much faster than encoding one byte at a time, and both are
+//! double-ended and exact-size.
+//!
+//! [`decode_iter`] adapts any iterator of hex characters into an iterator of decoded bytes.
+//!
+//! # Examples
+//!
+//! ```
+//! let hex: String = const_hex::encode_iter(b"kiwi").map(char::from).collect();
+//! assert_eq!(hex, "6b697769");
+//!
+//! let rev: Vec<u8> = const_hex::encode_iter_upper(&[0xab, 0xcd]).rev().collect();
+//! assert_eq!(rev, b"DCBA");
+//!
+//! let bytes: Result<Vec<u8>, _> = const_hex::decode_iter("0x6b697769".bytes()).collect();
+//! assert_eq!(bytes.unwrap(), b"kiwi");
+//! ```
+
+use crate::{arch, FromHexError, HEX_DECODE_LUT, NIL};
+use core::fmt;
+use core::iter::FusedIterator;
+use core::{slice, str};
+
+/// The number of input bytes encoded at once.
+const CHUNK_LEN: usize = 32;
+
+/// Returns an iterator over the *lower* hex encoding of `input`, as ASCII bytes.
+///
+/// The result is identical to the bytes of [`encode`](crate::encode).
+///
+/// # Examples
+///
+/// ```
+/// let mut iter = const_hex::encode_iter(&[0x01, 0xab]);
+/// assert_eq!(iter.len(), 4);
+/// assert_eq!(iter.next(), Some(b'0'));
+/// assert_eq!(iter.next_back(), Some(b'b'));
+/// assert_eq!(iter.collect::<Vec<_>>(), b"1a");
+/// ```
+#[inline]
+pub fn encode_iter<T: AsRef<[u8]> + ?Sized>(input: &T) -> EncodeIter<'_, false> {
+    EncodeIter::new(input.as_ref())
+}
+
+/// Returns an iterator over the *upper* hex encoding of `input`, as ASCII bytes.
+///
+/// The result is identical to the bytes of [`encode_upper`](crate::encode_upper).
+///
+/// # Examples
+///
+/// ```
+/// let hex: String = const_hex::encode_iter_upper(b"kiwi").map(char::from).collect();
+/// assert_eq!(hex, "6B697769");
+/// ```
+#[inline]
+pub fn encode_iter_upper<T: AsRef<[u8]> + ?Sized>(input: &T) -> EncodeIter<'_, true> {
+    EncodeIter::new(input.as_ref())
+}
+
+/// Returns an iterator over the *lower* hex digit pairs of `input`, one per input byte.
+///
+/// # Examples
+///
+/// ```
+/// let pairs: Vec<[u8; 2]> = const_hex::iter::encode_pairs(&[0x01, 0xab]).collect();
+/// assert_eq!(pairs, [*b"01", *b"ab"]);
+/// ```
+#[inline]
+pub fn encode_pairs<T: AsRef<[u8]> + ?Sized>(input: &T) -> EncodePairs<'_, false> {
+    EncodePairs::new(input.as_ref())
+}
+
+/// Returns an iterator over the *upper* hex digit pairs of `input`, one per input byte.
+///
+/// # Examples
+///
+/// ```
+/// let pairs: Vec<[u8; 2]> = const_hex::iter::encode_pairs_upper(&[0x01, 0xab]).collect();
+/// assert_eq!(pairs, [*b"01", *b"AB"]);
+/// ```
+#[inline]
+pub fn encode_pairs_upper<T: AsRef<[u8]> + ?Sized>(input: &T) -> EncodePairs<'_, true> {
+    EncodePairs::new(input.as_ref())
+}
+
+/// Returns an iterator that decodes the hex characters yielded by `input` into bytes.
+///
+/// A leading `0x` or `0X` prefix is skipped. Both upper and lower case characters are accepted.
+///
+/// Each item is either a decoded byte or the first error. After an error, the iterator yields
+/// nothing more. Since the length of the input is not known in advance, an odd length is only
+/// reported at the end, after all the preceding bytes have been decoded. The index of an invalid
+/// character is the same as in [`decode`](crate::decode).
+///
+/// # Examples
+///
+/// ```
+/// use const_hex::FromHexError;
+///
+/// let mut iter = const_hex::decode_iter("0x01zz".bytes());
+/// assert_eq!(iter.next(), Some(Ok(0x01)));
+/// assert_eq!(iter.next(), Some(Err(FromHexError::InvalidHexCharacter { c: 'z', index: 2 })));
+/// assert_eq!(iter.next(), None);
+///
+/// let odd: Vec<_> = const_hex::decode_iter(b"abc".iter().copied()).collect();
+/// assert_eq!(odd, [Ok(0xab), Err(FromHexError::OddLength)]);
+/// ```
+#[inline]
+pub fn decode_iter<I: IntoIterator<Item = u8>>(input: I) -> DecodeIter<I::IntoIter> {
+    DecodeIter {
+        input: input.into_iter(),
+        index: 0,
+        started: false,
+        done: false,
+    }
+}
+
+/// A staged chunk of encoded digit pairs, consumed from `start..end`.
+#[derive(Clone, Copy)]
+struct Chunk {
+    pairs: [[u8; 2]; CHUNK_LEN],
+    start: usize,
+    end: usize,
+}
+
+impl Chunk {
+    const EMPTY: Self = Self {
+        pairs: [[0; 2]; CHUNK_LEN],
+        start: 0,
+        end: 0,
+    };
+
+    #[inline]
+    fn fill<const UPPER: bool>(&mut self, input: &[u8]) {
+        debug_assert!(input.len() <= CHUNK_LEN);
+        // SAFETY: `[[u8; 2]; N]` is layout-compatible with `[u8; N * 2]`, so the output is exactly
+        // `input.len() * 2` bytes long.
+        unsafe {
+            let output = slice::from_raw_parts_mut(self.pairs.as_mut_ptr().cast(), input.len() * 2);
+            arch::encode::<UPPER>(input, output);
+        }
+        self.start = 0;
+        self.end = input.len();
+    }
+
+    #[inline]
+    const fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    #[inline]
+    fn as_str(&self) -> &str {
+        let pairs = &self.pairs[self.start..self.end];
+        // SAFETY: `[[u8; 2]; N]` is layout-compatible with `[u8; N * 2]`, and only ASCII is
+        // written.
+        unsafe {
+            let bytes = slice::from_raw_parts(pairs.as_ptr().cast::<u8>(), pairs.len() * 2);
+            str::from_utf8_unchecked(bytes)
+        }
+    }
+}
+
+/// An iterator over the hex digit pairs of a byte slice.
+///
+/// Created by [`encode_pairs`] and [`encode_pairs_upper`].
+#[derive(Clone)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct EncodePairs<'a, const UPPER: bool> {
+    input: &'a [u8],
+    front: Chunk,
+    back: Chunk,
+}
+
+impl<const UPPER: bool> fmt::Debug for EncodePairs<'_, UPPER> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EncodePairs")
+            .field("front", &self.front.as_str())
+            .field("input", &self.input)
+            .field("back", &self.back.as_str())
+            .finish()
+    }
+}
+
+impl<'a, const UPPER: bool> EncodePairs<'a, UPPER> {
+    #[inline]
+    const fn new(input: &'a [u8]) -> Self {
+        Self {
+            input,
+            front: Chunk::EMPTY,
+            back: Chunk::EMPTY,
+        }
+    }
+}
+
+impl<const UPPER: bool> Iterator for EncodePairs<'_, UPPER> {
+    type Item = [u8; 2];
+
+    #[inline]
+    fn next(&mut self) -> Option<[u8; 2]> {
+        if self.front.len() == 0 && !self.input.is_empty() {
+            let (chunk, rest) = self.input.split_at(self.input.len().min(CHUNK_LEN));
+            self.front.fill::<UPPER>(chunk);
+            self.input = rest;
+        }
+        let chunk = if self.front.len() != 0 {
+            &mut self.front
+        } else if self.back.len() != 0 {
+            &mut self.back
+        } else {
+            return None;
+        };
+        let pair = chunk.pairs[chunk.start];
+        chunk.start += 1;
+        Some(pair)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<const UPPER: bool> DoubleEndedIterator for EncodePairs<'_, UPPER> {
+    #[inline]
+    fn next_back(&mut self) -> Option<[u8; 2]> {
+        if self.back.len() == 0 && !self.input.is_empty() {
+            let mid = self.input.len().saturating_sub(CHUNK_LEN);
+            let (rest, chunk) = self.input.split_at(mid);
+            self.back.fill::<UPPER>(chunk);
+            self.input = rest;
+        }
+        let chunk = if self.back.len() != 0 {
+            &mut self.back
+        } else if self.front.len() != 0 {
+            &mut self.front
+        } else {
+            return None;
+        };
+        chunk.end -= 1;
+        Some(chunk.pairs[chunk.end])
+    }
+}
+
+impl<const UPPER: bool> ExactSizeIterator for EncodePairs<'_, UPPER> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.front.len() + self.input.len() + self.back.len()
+    }
+}
+
+impl<const UPPER: bool> FusedIterator for EncodePairs<'_, UPPER> {}
+
+/// An iterator over the hex encoding of a byte slice, as ASCII bytes.
+///
+/// Created by [`encode_iter`] and [`encode_iter_upper`].
+#[derive(Clone, Debug)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct EncodeIter<'a, const UPPER: bool> {
+    pairs: EncodePairs<'a, UPPER>,
+    /// The low digit of the pair most recently taken from the front.
+    front: Option<u8>,
+    /// The high digit of the pair most recently taken from the back.
+    back: Option<u8>,
+}
+
+impl<'a, const UPPER: bool> EncodeIter<'a, UPPER> {
+    #[inline]
+    const fn new(input: &'a [u8]) -> Self {
+        Self {
+            pairs: EncodePairs::new(input),
+            front: None,
+            back: None,
+        }
+    }
+}
+
+impl<const UPPER: bool> Iterator for EncodeIter<'_, UPPER> {
+    type Item = u8;
+
+    #[inline]
+    fn next(&mut self) -> Option<u8> {
+        if let Some(low) = self.front.take() {
+            return Some(low);
+        }
+        match self.pairs.next() {
+            Some([high, low]) => {
+                self.front = Some(low);
+                Some(high)
+            }
+            None => self.back.take(),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<const UPPER: bool> DoubleEndedIterator for EncodeIter<'_, UPPER> {
+    #[inline]
+    fn next_back(&mut self) -> Option<u8> {
+        if let Some(high) = self.back.take() {
+            return Some(high);
+        }
+        match self.pairs.next_back() {
+            Some([high, low]) => {
+                self.back = Some(high);
+                Some(low)
+            }
+            None => self.front.take(),
+        }
+    }
+}
+
+impl<const UPPER: bool> ExactSizeIterator for EncodeIter<'_, UPPER> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.pairs.len() * 2 + self.front.is_some() as usize + self.back.is_some() as usize
+    }
+}
+
+impl<const UPPER: bool> FusedIterator for EncodeIter<'_, UPPER> {}
+
+/// An iterator that decodes hex characters into bytes.
+///
+/// Created by [`decode_iter`].
+#[derive(Clone, Debug)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct DecodeIter<I> {
+    input: I,
+    /// The index of the next input character, not counting the prefix.
+    index: usize,
+    started: bool,
+    done: bool,
+}
+
+impl<I: Iterator<Item = u8>> DecodeIter<I> {
+    #[inline]
+    fn fail(&mut self, e: FromHexError) -> Option<Result<u8, FromHexError>> {
+        self.done = true;
+        Some(Err(e))
+    }
+
+    /// Returns the next two characters, without decoding them.
+    #[inline]
+    fn next_pair(&mut self) -> Option<Result<[u8; 2], FromHexError>> {
+        let high = self.input.next()?;
+        Some(match self.input.next() {
+            Some(low) => Ok([high, low]),
+            None => Err(FromHexError::OddLength),
+        })
+    }
+}
+
+impl<I: Iterator<Item = u8>> Iterator for DecodeIter<I> {
+    type Item = Result<u8, FromHexError>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let mut pair = self.next_pair();
+        if !self.started {
+            self.started = true;
+            if let Some(Ok([b'0', b'x' | b'X'])) = pair {
+                pair = self.next_pair();
+            }
+        }
+        let [high, low] = match pair {
+            Some(Ok(pair)) => pair,
+            Some(Err(e)) => return self.fail(e),
+            None => {
+                self.done = true;
+                return None;
+            }
+        };
+        let (h, l) = (HEX_DECODE_LUT[high as usize], HEX_DECODE_LUT[low as usize]);
+        if h == NIL || l == NIL {
+            let (c, index) = if h == NIL {
+                (high, self.index)
+            } else {
+                (low, self.index + 1)
+            };
+            let c = c as char;
+            return self.fail(FromHexError::InvalidHexCharacter { c, index });
+        }
+        self.index += 2;
+        Some(Ok(h << 4 | l))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.done {
+            return (0, Some(0));
+        }
+        let (_, upper) = self.input.size_hint();
+        (0, upper.map(|n| n / 2 + n % 2))
+    }
+}
+
+impl<I: Iterator<Item = u8>> FusedIterator for DecodeIter<I> {}
@@ -69,6 +69,12 @@ use cpufeatures as _;
 mod arch;
 use arch::{generic, imp};
 
+mod backend;
+pub use backend::{backend, force_backend, reset_backend, Backend};
+
+#[doc(hidden)]
+pub mod backends;
+
 mod impl_core;
 
 pub mod traits;
@@ -76,10 +82,51 @@ pub mod traits;
 pub use traits::ToHexExt;
 
 mod display;
-pub use display::display;
+pub use display::{display, display_reversed};
+
+pub mod iter;
+pub use iter::{decode_iter, encode_iter, encode_iter_upper};
+
+mod diff;
+pub use diff::diff;
+
+mod utf16;
+#[cfg(feature = "alloc")]
+pub use utf16::decode_utf16;
+pub use utf16::{check_utf16, decode_utf16_to_slice, encode_to_utf16, encode_to_utf16_upper};
+
+#[cfg(feature = "rayon")]
+mod par;
+#[cfg(feature = "rayon")]
+pub use par::{
+    par_check, par_decode, par_decode_to_slice, par_encode, par_encode_to_slice,
+    par_encode_to_slice_upper, par_encode_upper,
+};
+
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+pub mod async_io;
+
+pub mod batch;
+
+pub mod engine;
+pub use engine::Engine;
+
+pub mod escape;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+pub mod nibbles;
+
+#[cfg(feature = "alloc")]
+pub mod ihex;
+#[cfg(feature = "alloc")]
+pub mod image;
+#[cfg(feature = "alloc")]
+pub mod srec;
 
 mod error;
-pub use error::FromHexError;
+pub use error::{ErrorSnippet, FromHexError, FromHexErrorDetails};
 
 #[allow(deprecated)]
 pub use traits::{FromHex, ToHex};
@@ -119,11 +166,21 @@ cfg_if! {
     }
 }
 
+#[cfg(feature = "proptest")]
+pub mod proptest;
+
 mod buffer;
 pub use buffer::Buffer;
 
+mod hex_buf;
+pub use hex_buf::HexBuf;
+
+pub mod const_str;
+pub use const_str::ConstStr;
+
 mod output;
-use output::Output;
+pub use output::Output;
+use output::{RawOutput, Sink};
 
 /// The table of lowercase characters used for hex encoding.
 pub const HEX_CHARS_LOWER: &[u8; 16] = b"0123456789abcdef";
@@ -154,6 +211,78 @@ pub const fn const_encode<const N: usize, const PREFIX: bool>(
     Buffer::new().const_format(input)
 }
 
+/// Encodes `input` as a hex string using lowercase characters into a mutable
+/// slice of bytes `output`, in a `const` context.
+///
+/// Unlike [`const_encode`], the length of `input` does not have to be known
+/// at compile time.
+///
+/// Prefer using [`encode_to_slice`] instead when possible (at runtime), as it
+/// is likely to be faster.
+///
+/// This requires the `const-mut` feature and Rust 1.83 or newer.
+///
+/// # Errors
+///
+/// If the output buffer is not exactly `input.len() * 2` bytes long.
+///
+/// # Examples
+///
+/// ```
+/// const INPUT: &[u8] = b"kiwi";
+/// const OUTPUT: [u8; INPUT.len() * 2] = {
+///     let mut output = [0; INPUT.len() * 2];
+///     assert!(const_hex::const_encode_to_slice(INPUT, &mut output).is_ok());
+///     output
+/// };
+/// assert_eq!(&OUTPUT, b"6b697769");
+/// ```
+#[cfg(feature = "const-mut")]
+#[inline]
+pub const fn const_encode_to_slice(input: &[u8], output: &mut [u8]) -> Result<(), FromHexError> {
+    const_encode_to_slice_inner::<false>(input, output)
+}
+
+/// Encodes `input` as a hex string using uppercase characters into a mutable
+/// slice of bytes `output`, in a `const` context.
+///
+/// See [`const_encode_to_slice`] for more information.
+///
+/// # Errors
+///
+/// If the output buffer is not exactly `input.len() * 2` bytes long.
+///
+/// # Examples
+///
+/// ```
+/// const OUTPUT: [u8; 8] = {
+///     let mut output = [0; 8];
+///     assert!(const_hex::const_encode_to_slice_upper(b"kiwi", &mut output).is_ok());
+///     output
+/// };
+/// assert_eq!(&OUTPUT, b"6B697769");
+/// ```
+#[cfg(feature = "const-mut")]
+#[inline]
+pub const fn const_encode_to_slice_upper(
+    input: &[u8],
+    output: &mut [u8],
+) -> Result<(), FromHexError> {
+    const_encode_to_slice_inner::<true>(input, output)
+}
+
+#[cfg(feature = "const-mut")]
+const fn const_encode_to_slice_inner<const UPPER: bool>(
+    input: &[u8],
+    output: &mut [u8],
+) -> Result<(), FromHexError> {
+    if output.len() != 2 * input.len() {
+        return Err(FromHexError::InvalidStringLength);
+    }
+    generic::const_encode::<UPPER>(input, output);
+    Ok(())
+}
+
 /// Encodes `input` as a hex string using lowercase characters into a mutable
 /// slice of bytes `output`.
 ///
@@ -245,6 +374,57 @@ pub fn encode_to_str_upper<T: AsRef<[u8]>>(
     encode_to_str_inner::<true>(input.as_ref(), output)
 }
 
+/// Encodes `input` as a hex string using lowercase characters into an [`Output`].
+///
+/// The encoding is appended to the output, which can be any storage that implements [`Output`],
+/// such as a `String`, a `heapless::String` or a user-defined ring buffer.
+///
+/// # Errors
+///
+/// If the output cannot hold `input.len() * 2` more bytes, in which case nothing is written.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// let mut s = String::from("hash: ");
+/// const_hex::encode_into(b"kiwi", &mut s)?;
+/// assert_eq!(s, "hash: 6b697769");
+/// # }
+/// # Ok::<_, const_hex::FromHexError>(())
+/// ```
+#[inline]
+pub fn encode_into<T: AsRef<[u8]>, O: Output + ?Sized>(
+    input: T,
+    output: &mut O,
+) -> Result<(), FromHexError> {
+    encode_into_inner::<false, O>(input.as_ref(), output)
+}
+
+/// Encodes `input` as a hex string using uppercase characters into an [`Output`].
+///
+/// # Errors
+///
+/// If the output cannot hold `input.len() * 2` more bytes, in which case nothing is written.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// let mut bytes = Vec::new();
+/// const_hex::encode_upper_into(b"kiwi", &mut bytes)?;
+/// assert_eq!(bytes, b"6B697769");
+/// # }
+/// # Ok::<_, const_hex::FromHexError>(())
+/// ```
+#[inline]
+pub fn encode_upper_into<T: AsRef<[u8]>, O: Output + ?Sized>(
+    input: T,
+    output: &mut O,
+) -> Result<(), FromHexError> {
+    encode_into_inner::<true, O>(input.as_ref(), output)
+}
+
 /// Encodes `data` as a hex string using lowercase characters.
 ///
 /// Lowercase characters are used (e.g. `f9b4ca`). The resulting string's
@@ -312,6 +492,83 @@ pub fn encode_upper_prefixed<T: AsRef<[u8]>>(data: T) -> String {
     encode_inner::<true, true>(data.as_ref())
 }
 
+/// Encodes `data` as a hex string using lowercase characters, in reverse byte order.
+///
+/// This is how Bitcoin transaction IDs and block hashes are displayed, for example. The reversal is
+/// done while encoding, without copying the input.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(const_hex::encode_reversed([1, 2, 3, 15, 16]), "100f030201");
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn encode_reversed<T: AsRef<[u8]>>(data: T) -> String {
+    encode_reversed_inner::<false>(data.as_ref())
+}
+
+/// Encodes `data` as a hex string using uppercase characters, in reverse byte order.
+///
+/// See [`encode_reversed()`] for more details.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(const_hex::encode_upper_reversed([1, 2, 3, 15, 16]), "100F030201");
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn encode_upper_reversed<T: AsRef<[u8]>>(data: T) -> String {
+    encode_reversed_inner::<true>(data.as_ref())
+}
+
+/// Encodes `input` as a hex string using lowercase characters, in reverse byte order, into a
+/// mutable slice of bytes `output`.
+///
+/// # Errors
+///
+/// If the output buffer is not exactly `input.len() * 2` bytes long.
+///
+/// # Examples
+///
+/// ```
+/// let mut bytes = [0u8; 4 * 2];
+/// const_hex::encode_reversed_to_slice(b"kiwi", &mut bytes)?;
+/// assert_eq!(&bytes, b"6977696b");
+/// # Ok::<_, const_hex::FromHexError>(())
+/// ```
+#[inline]
+pub fn encode_reversed_to_slice<T: AsRef<[u8]>>(
+    input: T,
+    output: &mut [u8],
+) -> Result<(), FromHexError> {
+    encode_reversed_to_slice_inner::<false>(input.as_ref(), output)
+}
+
+/// Encodes `input` as a hex string using uppercase characters, in reverse byte order, into a
+/// mutable slice of bytes `output`.
+///
+/// # Errors
+///
+/// If the output buffer is not exactly `input.len() * 2` bytes long.
+///
+/// # Examples
+///
+/// ```
+/// let mut bytes = [0u8; 4 * 2];
+/// const_hex::encode_reversed_to_slice_upper(b"kiwi", &mut bytes)?;
+/// assert_eq!(&bytes, b"6977696B");
+/// # Ok::<_, const_hex::FromHexError>(())
+/// ```
+#[inline]
+pub fn encode_reversed_to_slice_upper<T: AsRef<[u8]>>(
+    input: T,
+    output: &mut [u8],
+) -> Result<(), FromHexError> {
+    encode_reversed_to_slice_inner::<true>(input.as_ref(), output)
+}
+
 /// Returns `true` if the input is a valid hex string and can be decoded successfully.
 ///
 /// Prefer using [`check`] instead when possible (at runtime), as it is likely to be faster.
@@ -387,14 +644,11 @@ pub fn check<T: AsRef<[u8]>>(input: T) -> Result<(), FromHexError> {
             return Err(FromHexError::OddLength);
         }
         let stripped = strip_prefix(input);
-        if imp::check(stripped) {
+        if arch::check(stripped) {
             Ok(())
         } else {
-            let mut e = unsafe { invalid_hex_error(stripped) };
-            if let FromHexError::InvalidHexCharacter { ref mut index, .. } = e {
-                *index += input.len() - stripped.len();
-            }
-            Err(e)
+            let e = unsafe { invalid_hex_error(stripped) };
+            Err(offset_error(e, input.len() - stripped.len()))
         }
     }
 
@@ -420,7 +674,7 @@ pub fn check<T: AsRef<[u8]>>(input: T) -> Result<(), FromHexError> {
 /// ```
 #[inline]
 pub fn check_raw<T: AsRef<[u8]>>(input: T) -> bool {
-    imp::check(input.as_ref())
+    arch::check(input.as_ref())
 }
 
 /// Decode a hex string into a fixed-length byte-array.
@@ -490,6 +744,56 @@ const fn const_decode_to_array_impl<const N: usize>(input: &[u8]) -> Option<[u8;
     Some(output)
 }
 
+/// Decode a hex string into a mutable bytes slice, in a `const` context.
+///
+/// Both, upper and lower case characters are valid in the input string and can
+/// even be mixed (e.g. `f9b4ca`, `F9B4CA` and `f9B4Ca` are all valid strings).
+///
+/// Strips the `0x` prefix if present.
+///
+/// Unlike [`const_decode_to_array`], the length of the output does not have
+/// to be known at compile time.
+///
+/// Prefer using [`decode_to_slice`] instead when possible (at runtime), as it
+/// is likely to be faster.
+///
+/// This requires the `const-mut` feature and Rust 1.83 or newer.
+///
+/// # Errors
+///
+/// This function returns an error if the input is not an even number of
+/// characters long or contains invalid hex characters, or if the output is not
+/// exactly half as long as the input without its prefix. `output` may be
+/// partially written to on error.
+///
+/// # Example
+///
+/// ```
+/// const INPUT: &[u8] = b"0x6b697769";
+/// const OUTPUT: [u8; INPUT.len() / 2 - 1] = {
+///     let mut output = [0; INPUT.len() / 2 - 1];
+///     assert!(const_hex::const_decode_to_slice(INPUT, &mut output).is_ok());
+///     output
+/// };
+/// assert_eq!(&OUTPUT, b"kiwi");
+/// ```
+#[cfg(feature = "const-mut")]
+#[inline]
+pub const fn const_decode_to_slice(input: &[u8], output: &mut [u8]) -> Result<(), FromHexError> {
+    if input.len() % 2 != 0 {
+        return Err(FromHexError::OddLength);
+    }
+    let stripped = strip_prefix(input);
+    if output.len() != stripped.len() / 2 {
+        return Err(FromHexError::InvalidStringLength);
+    }
+    if generic::const_decode_checked(stripped, output) {
+        Ok(())
+    } else {
+        Err(unsafe { invalid_hex_error(stripped) })
+    }
+}
+
 /// Decodes a hex string into raw bytes.
 ///
 /// Both, upper and lower case characters are valid in the input string and can
@@ -524,10 +828,10 @@ pub fn decode<T: AsRef<[u8]>>(input: T) -> Result<Vec<u8>, FromHexError> {
         if unlikely(input.len() % 2 != 0) {
             return Err(FromHexError::OddLength);
         }
-        let input = strip_prefix(input);
+        let stripped = strip_prefix(input);
 
         // Do not initialize memory since it will be entirely overwritten.
-        let len = input.len() / 2;
+        let len = stripped.len() / 2;
         let mut output = Vec::with_capacity(len);
         // SAFETY: The entire vec is never read from, and gets dropped if decoding fails.
         #[allow(clippy::uninit_vec)]
@@ -536,7 +840,7 @@ pub fn decode<T: AsRef<[u8]>>(input: T) -> Result<Vec<u8>, FromHexError> {
         }
 
         // SAFETY: Lengths are checked above.
-        unsafe { decode_checked(input, &mut output) }.map(|()| output)
+        unsafe { decode_checked(stripped, &mut output) }.map(|()| output)
     }
 
     decode_inner(input.as_ref())
@@ -595,6 +899,19 @@ pub fn decode_to_slice<T: AsRef<[u8]>>(input: T, output: &mut [u8]) -> Result<()
 #[inline]
 pub fn decode_to_array<T: AsRef<[u8]>, const N: usize>(input: T) -> Result<[u8; N], FromHexError> {
     fn decode_to_array_inner<const N: usize>(input: &[u8]) -> Result<[u8; N], FromHexError> {
+        if arch::has_fixed(N) {
+            let stripped = strip_prefix(input);
+            if stripped.len() == N * 2 {
+                let mut output = [0; N];
+                // SAFETY: The length is checked above.
+                match unsafe { arch::decode_fixed(stripped, &mut output) } {
+                    Some(true) => return Ok(output),
+                    Some(false) => return Err(unsafe { invalid_hex_error(stripped) }),
+                    None => {}
+                }
+            }
+        }
+
         let mut output = impl_core::uninit_array();
         // SAFETY: The entire array is never read from.
         let output_slice = unsafe { impl_core::slice_assume_init_mut(&mut output) };
@@ -606,6 +923,112 @@ pub fn decode_to_array<T: AsRef<[u8]>, const N: usize>(input: T) -> Result<[u8;
     decode_to_array_inner(input.as_ref())
 }
 
+/// Decodes a hex string into raw bytes, in reverse byte order.
+///
+/// This is the inverse of [`encode_reversed`]. The bytes are reversed in place after decoding.
+///
+/// Strips the `0x` prefix if present.
+///
+/// # Errors
+///
+/// See [`decode`].
+///
+/// # Example
+///
+/// ```
+/// assert_eq!(const_hex::decode_reversed("0x100f030201"), Ok(vec![1, 2, 3, 15, 16]));
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn decode_reversed<T: AsRef<[u8]>>(input: T) -> Result<Vec<u8>, FromHexError> {
+    let mut output = decode(input)?;
+    output.reverse();
+    Ok(output)
+}
+
+/// Decodes a hex string into a mutable bytes slice, in reverse byte order.
+///
+/// # Errors
+///
+/// See [`decode_to_slice`].
+///
+/// # Example
+///
+/// ```
+/// let mut bytes = [0u8; 4];
+/// const_hex::decode_reversed_to_slice("6977696b", &mut bytes).unwrap();
+/// assert_eq!(&bytes, b"kiwi");
+/// ```
+#[inline]
+pub fn decode_reversed_to_slice<T: AsRef<[u8]>>(
+    input: T,
+    output: &mut [u8],
+) -> Result<(), FromHexError> {
+    decode_to_slice_inner(input.as_ref(), output)?;
+    output.reverse();
+    Ok(())
+}
+
+/// Decodes a hex string into a fixed-length byte-array, in reverse byte order.
+///
+/// # Errors
+///
+/// See [`decode_to_array`].
+///
+/// # Example
+///
+/// ```
+/// let bytes = const_hex::decode_reversed_to_array(b"0x6977696b").unwrap();
+/// assert_eq!(&bytes, b"kiwi");
+/// ```
+#[inline]
+pub fn decode_reversed_to_array<T: AsRef<[u8]>, const N: usize>(
+    input: T,
+) -> Result<[u8; N], FromHexError> {
+    let mut output = decode_to_array(input)?;
+    output.reverse();
+    Ok(output)
+}
+
+/// Decode a hex string into a mutable bytes slice, returning a
+/// [`FromHexErrorDetails`] on failure.
+///
+/// This works exactly like [`decode_to_slice`], but the returned error
+/// additionally carries the expected and actual lengths of the input, as well as
+/// the line and column of invalid characters. Unlike with [`decode_to_slice`],
+/// the index of an invalid character counts the `0x` prefix, if any.
+///
+/// # Errors
+///
+/// See [`decode_to_slice`].
+///
+/// # Example
+///
+/// ```
+/// let mut bytes = [0u8; 4];
+/// let err = const_hex::decode_to_slice_detailed("0x6b6977", &mut bytes).unwrap_err();
+/// assert_eq!(err.expected_len(), Some(8));
+/// assert_eq!(err.actual_len(), 6);
+/// assert_eq!(err.to_string(), "invalid string length: expected 8 digits, got 6");
+/// ```
+#[inline]
+pub fn decode_to_slice_detailed<T: AsRef<[u8]>>(
+    input: T,
+    output: &mut [u8],
+) -> Result<(), FromHexErrorDetails> {
+    fn decode_to_slice_detailed_inner(
+        input: &[u8],
+        output: &mut [u8],
+    ) -> Result<(), FromHexErrorDetails> {
+        decode_to_slice_inner(input, output).map_err(|e| {
+            let e = offset_error(e, input.len() - strip_prefix(input).len());
+            e.with_input(input).with_expected_len(output.len() * 2)
+        })
+    }
+
+    decode_to_slice_detailed_inner(input.as_ref(), output)
+}
+
 #[cfg(feature = "alloc")]
 fn encode_inner<const UPPER: bool, const PREFIX: bool>(data: &[u8]) -> String {
     let capacity = PREFIX as usize * 2 + data.len() * 2;
@@ -625,7 +1048,7 @@ fn encode_inner<const UPPER: bool, const PREFIX: bool>(data: &[u8]) -> String {
         }
     }
     // SAFETY: `output` is long enough (input.len() * 2).
-    unsafe { imp::encode::<UPPER>(data, output) };
+    unsafe { arch::encode::<UPPER>(data, output) };
     // SAFETY: We only write only ASCII bytes.
     unsafe { String::from_utf8_unchecked(buf) }
 }
@@ -638,7 +1061,46 @@ fn encode_to_slice_inner<const UPPER: bool>(
         return Err(FromHexError::InvalidStringLength);
     }
     // SAFETY: Lengths are checked above.
-    unsafe { imp::encode::<UPPER>(input, output) };
+    unsafe { arch::encode::<UPPER>(input, output) };
+    Ok(())
+}
+
+#[cfg(feature = "alloc")]
+fn encode_reversed_inner<const UPPER: bool>(data: &[u8]) -> String {
+    let capacity = data.len() * 2;
+    let mut buf = Vec::<u8>::with_capacity(capacity);
+    // SAFETY: The entire vec is never read from, and gets dropped if decoding fails.
+    #[allow(clippy::uninit_vec)]
+    unsafe {
+        buf.set_len(capacity)
+    };
+    // SAFETY: `buf` is exactly `data.len() * 2` bytes long.
+    unsafe { arch::encode_reversed::<UPPER>(data, &mut buf) };
+    // SAFETY: We only write only ASCII bytes.
+    unsafe { String::from_utf8_unchecked(buf) }
+}
+
+fn encode_reversed_to_slice_inner<const UPPER: bool>(
+    input: &[u8],
+    output: &mut [u8],
+) -> Result<(), FromHexError> {
+    if unlikely(output.len() != 2 * input.len()) {
+        return Err(FromHexError::InvalidStringLength);
+    }
+    // SAFETY: Lengths are checked above.
+    unsafe { arch::encode_reversed::<UPPER>(input, output) };
+    Ok(())
+}
+
+fn encode_into_inner<const UPPER: bool, O: Output + ?Sized>(
+    input: &[u8],
+    output: &mut O,
+) -> Result<(), FromHexError> {
+    if unlikely(!output.reserve(input.len() * 2)) {
+        return Err(FromHexError::InvalidStringLength);
+    }
+    // SAFETY: The kernels only write ASCII bytes.
+    unsafe { imp::encode::<UPPER>(input, Sink(output)) };
     Ok(())
 }
 
@@ -658,7 +1120,23 @@ fn decode_to_slice_inner(input: &[u8], output: &mut [u8]) -> Result<(), FromHexE
     if unlikely(input.len() % 2 != 0) {
         return Err(FromHexError::OddLength);
     }
-    let input = strip_prefix(input);
+    let stripped = strip_prefix(input);
+    if unlikely(output.len() != stripped.len() / 2) {
+        return Err(FromHexError::InvalidStringLength);
+    }
+    // SAFETY: Lengths are checked above.
+    unsafe { decode_checked(stripped, output) }
+}
+
+/// Like [`decode_to_slice`], but does not strip the `0x` prefix.
+#[cfg(feature = "alloc")]
+pub(crate) fn decode_to_slice_unprefixed(
+    input: &[u8],
+    output: &mut [u8],
+) -> Result<(), FromHexError> {
+    if unlikely(input.len() % 2 != 0) {
+        return Err(FromHexError::OddLength);
+    }
     if unlikely(output.len() != input.len() / 2) {
         return Err(FromHexError::InvalidStringLength);
     }
@@ -675,13 +1153,13 @@ unsafe fn decode_checked(input: &[u8], output: &mut [u8]) -> Result<(), FromHexE
 
     if imp::USE_CHECK_FN {
         // Check then decode.
-        if imp::check(input) {
-            unsafe { imp::decode_unchecked(input, output) };
+        if arch::check(input) {
+            unsafe { arch::decode_unchecked(input, output) };
             return Ok(());
         }
     } else {
         // Check and decode at the same time.
-        if unsafe { imp::decode_checked(input, output) } {
+        if unsafe { arch::decode_checked(input, output) } {
             return Ok(());
         }
     }
@@ -705,6 +1183,18 @@ const fn strip_prefix(bytes: &[u8]) -> &[u8] {
     }
 }
 
+/// Offsets the index of an invalid character error by the length of a stripped prefix.
+#[inline]
+const fn offset_error(e: FromHexError, offset: usize) -> FromHexError {
+    match e {
+        FromHexError::InvalidHexCharacter { c, index } => FromHexError::InvalidHexCharacter {
+            c,
+            index: index + offset,
+        },
+        e => e,
+    }
+}
+
 /// Creates an invalid hex error from the input.
 ///
 /// # Safety
@@ -779,13 +1269,130 @@ const fn make_decode_lut() -> [u8; 256] {
 #[doc(hidden)]
 pub mod fuzzing {
     use super::*;
-    use proptest::test_runner::TestCaseResult;
-    use proptest::{prop_assert, prop_assert_eq};
+    use ::proptest::test_runner::TestCaseResult;
+    use ::proptest::{prop_assert, prop_assert_eq};
     use std::fmt::Write;
 
     pub fn fuzz(data: &[u8]) -> TestCaseResult {
         self::encode(&data)?;
         self::decode(&data)?;
+        self::backends(&data)?;
+        Ok(())
+    }
+
+    /// Cross-checks every available backend against the generic one.
+    pub fn backends(input: &[u8]) -> TestCaseResult {
+        let generic = crate::backends::get(Backend::Generic).unwrap();
+
+        let mut expected_lower = vec![0; input.len() * 2];
+        let mut expected_upper = vec![0; input.len() * 2];
+        generic.encode::<false>(input, &mut expected_lower);
+        generic.encode::<true>(input, &mut expected_upper);
+
+        let mut expected_reversed = vec![0; input.len() * 2];
+        generic.encode_reversed::<false>(input, &mut expected_reversed);
+        let mut reversed = input.to_vec();
+        reversed.reverse();
+        prop_assert_eq!(&crate::encode(&reversed).into_bytes(), &expected_reversed);
+
+        let even = &input[..input.len() & !1];
+        let is_valid = generic.check(input);
+        let mut expected_decoded = vec![0; even.len() / 2];
+        let decoded_ok = generic.decode_checked(even, &mut expected_decoded);
+
+        let mut expected_unpacked = vec![0; input.len() * 2];
+        generic.unpack(input, &mut expected_unpacked);
+        let mut expected_packed = vec![0; even.len() / 2];
+        let packed_ok = generic.pack(even, &mut expected_packed);
+
+        let mut expected_utf16_lower = vec![0; input.len() * 2];
+        let mut expected_utf16_upper = vec![0; input.len() * 2];
+        generic.encode_utf16::<false>(input, &mut expected_utf16_lower);
+        generic.encode_utf16::<true>(input, &mut expected_utf16_upper);
+        let widened = |bytes: &[u8]| bytes.iter().map(|&b| b as u16).collect::<Vec<_>>();
+        prop_assert_eq!(&expected_utf16_lower, &widened(&expected_lower));
+        prop_assert_eq!(&expected_utf16_upper, &widened(&expected_upper));
+
+        // Both the input bytes as code units, and pairs of input bytes as arbitrary code units.
+        let utf16_inputs = [
+            widened(input),
+            input
+                .chunks_exact(2)
+                .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+                .collect(),
+        ];
+        let mut expected_utf16 = Vec::new();
+        for utf16 in &utf16_inputs {
+            let even = &utf16[..utf16.len() & !1];
+            let mut decoded = vec![0; even.len() / 2];
+            let decoded_ok = generic.decode_utf16_checked(even, &mut decoded);
+            prop_assert_eq!(generic.check_utf16(even), decoded_ok);
+            expected_utf16.push((generic.check_utf16(utf16), decoded_ok, decoded));
+        }
+
+        for imp in crate::backends::all() {
+            let backend = imp.backend();
+            let mut out = vec![0; input.len() * 2];
+            imp.encode::<false>(input, &mut out);
+            prop_assert_eq!(&out, &expected_lower, "{}", backend);
+            imp.encode::<true>(input, &mut out);
+            prop_assert_eq!(&out, &expected_upper, "{}", backend);
+            imp.encode_reversed::<false>(input, &mut out);
+            prop_assert_eq!(&out, &expected_reversed, "{}", backend);
+            imp.encode_reversed::<true>(input, &mut out);
+            prop_assert_eq!(&out, &expected_reversed.to_ascii_uppercase(), "{}", backend);
+
+            prop_assert_eq!(imp.check(input), is_valid, "{}", backend);
+
+            let mut out = vec![0; even.len() / 2];
+            prop_assert_eq!(
+                imp.decode_checked(even, &mut out),
+                decoded_ok,
+                "{}",
+                backend
+            );
+            if decoded_ok {
+                prop_assert_eq!(&out, &expected_decoded, "{}", backend);
+
+                let mut out = vec![0; even.len() / 2];
+                unsafe { imp.decode_unchecked(even, &mut out) };
+                prop_assert_eq!(&out, &expected_decoded, "{}", backend);
+            }
+
+            let mut out = vec![0; input.len() * 2];
+            imp.unpack(input, &mut out);
+            prop_assert_eq!(&out, &expected_unpacked, "{}", backend);
+
+            let mut out = vec![0; even.len() / 2];
+            prop_assert_eq!(imp.pack(even, &mut out), packed_ok, "{}", backend);
+            if packed_ok {
+                prop_assert_eq!(&out, &expected_packed, "{}", backend);
+            }
+
+            let mut out = vec![0; input.len() * 2];
+            imp.encode_utf16::<false>(input, &mut out);
+            prop_assert_eq!(&out, &expected_utf16_lower, "{}", backend);
+            imp.encode_utf16::<true>(input, &mut out);
+            prop_assert_eq!(&out, &expected_utf16_upper, "{}", backend);
+
+            for (utf16, (is_valid, decoded_ok, decoded)) in utf16_inputs.iter().zip(&expected_utf16)
+            {
+                prop_assert_eq!(imp.check_utf16(utf16), *is_valid, "{}", backend);
+
+                let even = &utf16[..utf16.len() & !1];
+                let mut out = vec![0; even.len() / 2];
+                prop_assert_eq!(
+                    imp.decode_utf16_checked(even, &mut out),
+                    *decoded_ok,
+                    "{}",
+                    backend
+                );
+                if *decoded_ok {
+                    prop_assert_eq!(&out, decoded, "{}", backend);
+                }
+            }
+        }
+
         Ok(())
     }
 
@@ -847,8 +1454,8 @@ pub mod fuzzing {
         Ok(())
     }
 
-    proptest::proptest! {
-        #![proptest_config(proptest::prelude::ProptestConfig {
+    ::proptest::proptest! {
+        #![proptest_config(::proptest::prelude::ProptestConfig {
             cases: 1024,
             ..Default::default()
         })]
@@ -858,6 +1465,76 @@ pub mod fuzzing {
             encode(s.as_bytes())?;
         }
 
+        #[test]
+        fn fuzz_backends(s in ".*") {
+            backends(s.as_bytes())?;
+        }
+
+        #[test]
+        fn fuzz_backends_valid(s in "[0-9a-fA-F]*") {
+            backends(s.as_bytes())?;
+        }
+
+        #[test]
+        fn fuzz_backends_encode(bytes in ::proptest::collection::vec(::proptest::num::u8::ANY, 0..256)) {
+            backends(&bytes)?;
+        }
+
+        #[test]
+        fn fuzz_backends_nibbles(nibbles in ::proptest::collection::vec(0u8..16, 0..256)) {
+            backends(&nibbles)?;
+        }
+
+        #[test]
+        fn fuzz_valid(s in crate::proptest::valid(0..256)) {
+            prop_assert!(crate::check(&s).is_ok());
+            let encoded = crate::encode(crate::decode(&s).unwrap());
+            prop_assert_eq!(encoded.as_bytes(), strip_prefix(s.as_bytes()).to_ascii_lowercase());
+        }
+
+        #[test]
+        fn fuzz_invalid(h in crate::proptest::invalid(0..256)) {
+            prop_assert_eq!(crate::check(&h.string), Err(h.error()));
+            prop_assert_eq!(crate::decode(&h.string), Err(h.decode_error()));
+            prop_assert!(!crate::check_raw(&h.string));
+        }
+
+        #[test]
+        fn fuzz_invalid_prefix(s in crate::proptest::mixed_case(1..256)) {
+            // Replacing the second digit of a leading `0` must not create a prefix.
+            let s = format!("0{}", &s[1..]);
+            for c in ['x', 'X'] {
+                let h = crate::proptest::replace_digit(s.clone(), |_| 1, c);
+                prop_assert_eq!(crate::check(&h.string), Err(h.error()));
+                prop_assert_eq!(crate::decode(&h.string), Err(h.decode_error()));
+            }
+        }
+
+        #[test]
+        fn fuzz_valid_utf16(s in crate::proptest::valid(0..256)) {
+            let utf16: Vec<u16> = s.encode_utf16().collect();
+            prop_assert!(crate::check_utf16(&utf16).is_ok());
+            let decoded = crate::decode_utf16(&utf16).unwrap();
+            prop_assert_eq!(&decoded, &crate::decode(&s).unwrap());
+            let mut encoded = vec![0; decoded.len() * 2];
+            crate::encode_to_utf16(&decoded, &mut encoded).unwrap();
+            prop_assert_eq!(String::from_utf16(&encoded).unwrap(), crate::encode(&decoded));
+        }
+
+        #[test]
+        fn fuzz_invalid_utf16(h in crate::proptest::invalid(0..256), unit in 0x80u16..) {
+            let mut utf16: Vec<u16> = h.string.encode_utf16().collect();
+            prop_assert_eq!(crate::check_utf16(&utf16), Err(h.error()));
+            prop_assert_eq!(crate::decode_utf16(&utf16), Err(h.decode_error()));
+
+            // Non-ASCII code units are invalid characters too.
+            utf16[h.index] = unit;
+            let c = char::from_u32(unit as u32).unwrap_or(char::REPLACEMENT_CHARACTER);
+            let h = crate::proptest::InvalidHex { c, ..h };
+            prop_assert_eq!(crate::check_utf16(&utf16), Err(h.error()));
+            prop_assert_eq!(crate::decode_utf16(&utf16), Err(h.decode_error()));
+        }
+
         #[test]
         fn fuzz_check_true(s in "[0-9a-fA-F]+") {
             let s = s.as_bytes();
@@ -0,0 +1,377 @@
+//! Nibble-level encoding, as used by Merkle-Patricia trie paths.
+//!
+//! A nibble is a 4-bit value, stored here as one `u8` in `0..=0xf`. Bytes are split into nibbles
+//! high nibble first, so `[0xab]` unpacks to `[0xa, 0xb]`.
+//!
+//! Nibble paths of any length are stored compactly with the hex-prefix (HP) encoding described in
+//! appendix C of the Ethereum yellow paper: the first byte holds a flag nibble, encoding whether
+//! the path has an odd length and whether it belongs to a leaf node, followed by either the first
+//! nibble of an odd-length path or a zero padding nibble.
+//!
+//! Unpacking and packing use the same SIMD kernels as the rest of the crate.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "alloc")] {
+//! use const_hex::nibbles;
+//!
+//! let path = nibbles::unpack([0x12, 0x34]);
+//! assert_eq!(path, [1, 2, 3, 4]);
+//! assert_eq!(nibbles::pack(&path), Ok(vec![0x12, 0x34]));
+//!
+//! let encoded = nibbles::encode_hex_prefix(&path[1..], true).unwrap();
+//! assert_eq!(encoded, [0x32, 0x34]);
+//! assert_eq!(nibbles::decode_hex_prefix(&encoded), Ok((vec![2, 3, 4], true)));
+//! assert_eq!(nibbles::display(&path[1..]).to_string(), "234");
+//! # }
+//! ```
+
+use crate::{arch, get_chars_table};
+use core::fmt;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// The hex-prefix flag bit set for odd-length paths.
+const ODD_FLAG: u8 = 0b01;
+/// The hex-prefix flag bit set for leaf nodes.
+const LEAF_FLAG: u8 = 0b10;
+
+/// Splits `bytes` into nibbles, high nibble first.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(const_hex::nibbles::unpack([0xde, 0xad]), [0xd, 0xe, 0xa, 0xd]);
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn unpack<T: AsRef<[u8]>>(bytes: T) -> Vec<u8> {
+    let bytes = bytes.as_ref();
+    let mut nibbles = vec![0; bytes.len() * 2];
+    // SAFETY: The output length is correct.
+    unsafe { arch::unpack(bytes, &mut nibbles) };
+    nibbles
+}
+
+/// Splits `bytes` into nibbles in `output`, high nibble first.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidLength`] if `output.len()` is not twice `bytes.len()`.
+///
+/// # Examples
+///
+/// ```
+/// let mut nibbles = [0; 4];
+/// const_hex::nibbles::unpack_to_slice([0xde, 0xad], &mut nibbles).unwrap();
+/// assert_eq!(nibbles, [0xd, 0xe, 0xa, 0xd]);
+/// ```
+#[inline]
+pub fn unpack_to_slice<T: AsRef<[u8]>>(bytes: T, output: &mut [u8]) -> Result<(), Error> {
+    let bytes = bytes.as_ref();
+    if output.len() != bytes.len() * 2 {
+        return Err(Error::InvalidLength);
+    }
+    // SAFETY: The output length is checked above.
+    unsafe { arch::unpack(bytes, output) };
+    Ok(())
+}
+
+/// Joins pairs of nibbles into bytes. This is the inverse of [`unpack`].
+///
+/// # Errors
+///
+/// Returns [`Error::OddLength`] if there is an odd number of nibbles, and
+/// [`Error::InvalidNibble`] if any nibble is greater than `0xf`.
+///
+/// # Examples
+///
+/// ```
+/// use const_hex::nibbles::{self, Error};
+///
+/// assert_eq!(nibbles::pack([0xd, 0xe, 0xa, 0xd]), Ok(vec![0xde, 0xad]));
+/// assert_eq!(nibbles::pack([0xd, 0xe, 0xa]), Err(Error::OddLength));
+/// assert_eq!(nibbles::pack([0xd, 0x10]), Err(Error::InvalidNibble { value: 0x10, index: 1 }));
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn pack<T: AsRef<[u8]>>(nibbles: T) -> Result<Vec<u8>, Error> {
+    let nibbles = nibbles.as_ref();
+    let mut bytes = vec![0; nibbles.len() / 2];
+    pack_to_slice(nibbles, &mut bytes)?;
+    Ok(bytes)
+}
+
+/// Joins pairs of nibbles into bytes in `output`.
+///
+/// # Errors
+///
+/// See [`pack`]. Additionally returns [`Error::InvalidLength`] if `output.len()` is not half of
+/// `nibbles.len()`.
+#[inline]
+pub fn pack_to_slice<T: AsRef<[u8]>>(nibbles: T, output: &mut [u8]) -> Result<(), Error> {
+    let nibbles = nibbles.as_ref();
+    if nibbles.len() % 2 != 0 {
+        return Err(Error::OddLength);
+    }
+    if output.len() != nibbles.len() / 2 {
+        return Err(Error::InvalidLength);
+    }
+    // SAFETY: The output length is checked above.
+    if unsafe { arch::pack(nibbles, output) } {
+        Ok(())
+    } else {
+        Err(invalid_nibble(nibbles))
+    }
+}
+
+/// Returns the length of the hex-prefix encoding of a path of `len` nibbles.
+#[inline]
+pub const fn hex_prefix_len(len: usize) -> usize {
+    len / 2 + 1
+}
+
+/// Encodes a nibble path using the hex-prefix encoding, setting the leaf flag if `leaf` is `true`.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidNibble`] if any nibble is greater than `0xf`.
+///
+/// # Examples
+///
+/// ```
+/// use const_hex::nibbles::encode_hex_prefix;
+///
+/// assert_eq!(encode_hex_prefix([1, 2, 3, 4, 5], false), Ok(vec![0x11, 0x23, 0x45]));
+/// assert_eq!(encode_hex_prefix([0, 1, 2, 3, 4, 5], false), Ok(vec![0x00, 0x01, 0x23, 0x45]));
+/// assert_eq!(encode_hex_prefix([0, 0xf, 1, 0xc, 0xb, 8], true), Ok(vec![0x20, 0x0f, 0x1c, 0xb8]));
+/// assert_eq!(encode_hex_prefix([0xf, 1, 0xc, 0xb, 8], true), Ok(vec![0x3f, 0x1c, 0xb8]));
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn encode_hex_prefix<T: AsRef<[u8]>>(nibbles: T, leaf: bool) -> Result<Vec<u8>, Error> {
+    let nibbles = nibbles.as_ref();
+    let mut bytes = vec![0; hex_prefix_len(nibbles.len())];
+    encode_hex_prefix_to_slice(nibbles, leaf, &mut bytes)?;
+    Ok(bytes)
+}
+
+/// Encodes a nibble path using the hex-prefix encoding into `output`.
+///
+/// See [`encode_hex_prefix`] for more details.
+///
+/// # Errors
+///
+/// See [`encode_hex_prefix`]. Additionally returns [`Error::InvalidLength`] if `output.len()` is
+/// not [`hex_prefix_len(nibbles.len())`](hex_prefix_len).
+#[inline]
+pub fn encode_hex_prefix_to_slice<T: AsRef<[u8]>>(
+    nibbles: T,
+    leaf: bool,
+    output: &mut [u8],
+) -> Result<(), Error> {
+    let nibbles = nibbles.as_ref();
+    if output.len() != hex_prefix_len(nibbles.len()) {
+        return Err(Error::InvalidLength);
+    }
+    let odd = nibbles.len() % 2 != 0;
+    let (first, rest) = if odd {
+        (nibbles[0], &nibbles[1..])
+    } else {
+        (0, nibbles)
+    };
+    if first > 0xf {
+        return Err(Error::InvalidNibble {
+            value: first,
+            index: 0,
+        });
+    }
+    let flag = if leaf { LEAF_FLAG } else { 0 } | if odd { ODD_FLAG } else { 0 };
+    output[0] = flag << 4 | first;
+    pack_to_slice(rest, &mut output[1..]).map_err(|e| match e {
+        Error::InvalidNibble { value, index } => Error::InvalidNibble {
+            value,
+            index: index + odd as usize,
+        },
+        e => e,
+    })
+}
+
+/// Decodes a hex-prefix encoded nibble path, returning the nibbles and whether the leaf flag is
+/// set. This is the inverse of [`encode_hex_prefix`].
+///
+/// # Errors
+///
+/// Returns [`Error::Empty`] if `encoded` is empty, [`Error::InvalidFlag`] if the flag nibble is
+/// greater than 3, and [`Error::InvalidPadding`] if the padding nibble of an even-length path is
+/// not zero.
+///
+/// # Examples
+///
+/// ```
+/// use const_hex::nibbles::{decode_hex_prefix, Error};
+///
+/// assert_eq!(decode_hex_prefix([0x3f, 0x1c, 0xb8]), Ok((vec![0xf, 1, 0xc, 0xb, 8], true)));
+/// assert_eq!(decode_hex_prefix([0x00, 0x01]), Ok((vec![0, 1], false)));
+/// assert_eq!(decode_hex_prefix([0x01, 0x01]), Err(Error::InvalidPadding));
+/// assert_eq!(decode_hex_prefix([0x40]), Err(Error::InvalidFlag(4)));
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn decode_hex_prefix<T: AsRef<[u8]>>(encoded: T) -> Result<(Vec<u8>, bool), Error> {
+    let encoded = encoded.as_ref();
+    let mut nibbles = vec![0; encoded.len() * 2];
+    let (len, leaf) = decode_hex_prefix_to_slice(encoded, &mut nibbles)?;
+    nibbles.truncate(len);
+    Ok((nibbles, leaf))
+}
+
+/// Decodes a hex-prefix encoded nibble path into `output`, returning the number of nibbles written
+/// and whether the leaf flag is set.
+///
+/// A path of `n` encoded bytes decodes to at most `2 * n - 1` nibbles.
+///
+/// # Errors
+///
+/// See [`decode_hex_prefix`]. Additionally returns [`Error::InvalidLength`] if `output` is too
+/// short.
+#[inline]
+pub fn decode_hex_prefix_to_slice<T: AsRef<[u8]>>(
+    encoded: T,
+    output: &mut [u8],
+) -> Result<(usize, bool), Error> {
+    let encoded = encoded.as_ref();
+    let (&first, rest) = encoded.split_first().ok_or(Error::Empty)?;
+    let flag = first >> 4;
+    if flag > (LEAF_FLAG | ODD_FLAG) {
+        return Err(Error::InvalidFlag(flag));
+    }
+    let odd = flag & ODD_FLAG != 0;
+    if !odd && first & 0xf != 0 {
+        return Err(Error::InvalidPadding);
+    }
+
+    let len = rest.len() * 2 + odd as usize;
+    let output = output.get_mut(..len).ok_or(Error::InvalidLength)?;
+    let output = if odd {
+        output[0] = first & 0xf;
+        &mut output[1..]
+    } else {
+        output
+    };
+    // SAFETY: The output length is `rest.len() * 2`.
+    unsafe { arch::unpack(rest, output) };
+    Ok((len, flag & LEAF_FLAG != 0))
+}
+
+/// Returns a value that formats a nibble path of any length as hex, one character per nibble.
+///
+/// Supports [`fmt::LowerHex`], [`fmt::UpperHex`], and [`fmt::Display`]
+/// (which is the same as [`fmt::LowerHex`]),
+/// as well as using the alternate flag (`:#`) to write the hex prefix.
+///
+/// Only the low 4 bits of each nibble are formatted.
+///
+/// # Examples
+///
+/// ```
+/// let path = const_hex::nibbles::display([0xa, 0xb, 0xc]);
+/// assert_eq!(format!("{path} {path:#X}"), "abc 0xABC");
+/// ```
+#[inline]
+pub fn display<T: AsRef<[u8]>>(nibbles: T) -> impl fmt::Display + fmt::LowerHex + fmt::UpperHex {
+    Display(nibbles)
+}
+
+struct Display<T: AsRef<[u8]>>(T);
+
+impl<T: AsRef<[u8]>> fmt::Display for Display<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(self, f)
+    }
+}
+
+impl<T: AsRef<[u8]>> fmt::LowerHex for Display<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write::<false>(f)
+    }
+}
+
+impl<T: AsRef<[u8]>> fmt::UpperHex for Display<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write::<true>(f)
+    }
+}
+
+impl<T: AsRef<[u8]>> Display<T> {
+    fn write<const UPPER: bool>(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            f.write_str("0x")?;
+        }
+        let table = get_chars_table::<UPPER>();
+        let mut buf = [0u8; 64];
+        for chunk in self.0.as_ref().chunks(buf.len()) {
+            let buf = &mut buf[..chunk.len()];
+            for (out, &nibble) in buf.iter_mut().zip(chunk) {
+                *out = table[(nibble & 0xf) as usize];
+            }
+            // SAFETY: The table only contains ASCII characters.
+            f.write_str(unsafe { core::str::from_utf8_unchecked(buf) })?;
+        }
+        Ok(())
+    }
+}
+
+#[cold]
+fn invalid_nibble(nibbles: &[u8]) -> Error {
+    let index = nibbles.iter().position(|&n| n > 0xf).unwrap_or_default();
+    Error::InvalidNibble {
+        value: nibbles[index],
+        index,
+    }
+}
+
+/// The error type for nibble packing and hex-prefix encoding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// A nibble is greater than `0xf`.
+    InvalidNibble {
+        /// The invalid value.
+        value: u8,
+        /// The index of the invalid value.
+        index: usize,
+    },
+    /// An odd number of nibbles was given to [`pack`].
+    OddLength,
+    /// The output buffer has the wrong length.
+    InvalidLength,
+    /// The hex-prefix encoded path is empty.
+    Empty,
+    /// The hex-prefix flag nibble is greater than 3.
+    InvalidFlag(u8),
+    /// The padding nibble of an even-length hex-prefix encoded path is not zero.
+    InvalidPadding,
+}
+
+#[cfg(feature = "core-error")]
+impl core::error::Error for Error {}
+#[cfg(all(feature = "std", not(feature = "core-error")))]
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::InvalidNibble { value, index } => {
+                write!(f, "invalid nibble {value:#04x} at position {index}")
+            }
+            Self::OddLength => f.write_str("odd number of nibbles"),
+            Self::InvalidLength => f.write_str("invalid output length"),
+            Self::Empty => f.write_str("empty hex-prefix encoded path"),
+            Self::InvalidFlag(flag) => write!(f, "invalid hex-prefix flag {flag:#x}"),
+            Self::InvalidPadding => f.write_str("non-zero hex-prefix padding nibble"),
+        }
+    }
+}
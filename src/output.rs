@@ -1,7 +1,182 @@
 use core::fmt::{self, Write};
 
+/// A sink that encoded hex strings are written to, with [`encode_into`](crate::encode_into).
+///
+/// Encoding functions first call [`reserve`](Self::reserve) with the total length of the
+/// encoding, and then call [`write_str`](Self::write_str) one or more times with consecutive
+/// parts of it, which are always ASCII. The SIMD kernels write directly into the sink, without an
+/// intermediate buffer.
+///
+/// Implementations are provided for `String` and `Vec<u8>`, as well as, behind features of the
+/// same name:
+/// - `bytes::BytesMut`;
+/// - `heapless::String<N>` and `heapless::Vec<u8, N>`;
+/// - `arrayvec::ArrayString<N>` and `arrayvec::ArrayVec<u8, N>`;
+/// - `smallvec::SmallVec<[u8; N]>`.
+///
+/// # Examples
+///
+/// ```
+/// use const_hex::Output;
+///
+/// /// A fixed-size ring buffer that overwrites its oldest bytes.
+/// struct Ring {
+///     buf: [u8; 8],
+///     pos: usize,
+/// }
+///
+/// impl Output for Ring {
+///     fn write_str(&mut self, s: &str) {
+///         for &b in s.as_bytes() {
+///             self.buf[self.pos % self.buf.len()] = b;
+///             self.pos += 1;
+///         }
+///     }
+/// }
+///
+/// let mut ring = Ring { buf: [0; 8], pos: 0 };
+/// const_hex::encode_into(b"\x01\x02\x03\x04\x05", &mut ring).unwrap();
+/// assert_eq!(&ring.buf, b"05020304");
+/// ```
+pub trait Output {
+    /// Prepares the sink for `additional` more bytes.
+    ///
+    /// Returns `false` if the sink cannot hold them, in which case nothing is written. The
+    /// default implementation does nothing and returns `true`.
+    #[inline]
+    fn reserve(&mut self, additional: usize) -> bool {
+        let _ = additional;
+        true
+    }
+
+    /// Appends `s` to the sink.
+    ///
+    /// This is only called after a successful [`reserve`](Self::reserve) call covering `s`.
+    fn write_str(&mut self, s: &str);
+}
+
+impl<O: Output + ?Sized> Output for &mut O {
+    #[inline]
+    fn reserve(&mut self, additional: usize) -> bool {
+        (**self).reserve(additional)
+    }
+
+    #[inline]
+    fn write_str(&mut self, s: &str) {
+        (**self).write_str(s)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Output for alloc::string::String {
+    #[inline]
+    fn reserve(&mut self, additional: usize) -> bool {
+        self.reserve(additional);
+        true
+    }
+
+    #[inline]
+    fn write_str(&mut self, s: &str) {
+        self.push_str(s);
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Output for alloc::vec::Vec<u8> {
+    #[inline]
+    fn reserve(&mut self, additional: usize) -> bool {
+        self.reserve(additional);
+        true
+    }
+
+    #[inline]
+    fn write_str(&mut self, s: &str) {
+        self.extend_from_slice(s.as_bytes());
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl Output for bytes::BytesMut {
+    #[inline]
+    fn reserve(&mut self, additional: usize) -> bool {
+        self.reserve(additional);
+        true
+    }
+
+    #[inline]
+    fn write_str(&mut self, s: &str) {
+        self.extend_from_slice(s.as_bytes());
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<const N: usize> Output for heapless::String<N> {
+    #[inline]
+    fn reserve(&mut self, additional: usize) -> bool {
+        N - self.len() >= additional
+    }
+
+    #[inline]
+    fn write_str(&mut self, s: &str) {
+        let _ = self.push_str(s);
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<const N: usize> Output for heapless::Vec<u8, N> {
+    #[inline]
+    fn reserve(&mut self, additional: usize) -> bool {
+        N - self.len() >= additional
+    }
+
+    #[inline]
+    fn write_str(&mut self, s: &str) {
+        let _ = self.extend_from_slice(s.as_bytes());
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+impl<const N: usize> Output for arrayvec::ArrayString<N> {
+    #[inline]
+    fn reserve(&mut self, additional: usize) -> bool {
+        self.remaining_capacity() >= additional
+    }
+
+    #[inline]
+    fn write_str(&mut self, s: &str) {
+        let _ = self.try_push_str(s);
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+impl<const N: usize> Output for arrayvec::ArrayVec<u8, N> {
+    #[inline]
+    fn reserve(&mut self, additional: usize) -> bool {
+        self.remaining_capacity() >= additional
+    }
+
+    #[inline]
+    fn write_str(&mut self, s: &str) {
+        let _ = self.try_extend_from_slice(s.as_bytes());
+    }
+}
+
+#[cfg(feature = "smallvec")]
+impl<const N: usize> Output for smallvec::SmallVec<[u8; N]> {
+    #[inline]
+    fn reserve(&mut self, additional: usize) -> bool {
+        self.reserve(additional);
+        true
+    }
+
+    #[inline]
+    fn write_str(&mut self, s: &str) {
+        self.extend_from_slice(s.as_bytes());
+    }
+}
+
 /// Internal trait for abstracting over output buffer types.
-pub(crate) trait Output {
+pub(crate) trait RawOutput {
     fn write(&mut self, bytes: &[u8]);
 
     #[inline]
@@ -15,7 +190,7 @@ pub(crate) trait Output {
     }
 }
 
-impl Output for &mut [u8] {
+impl RawOutput for &mut [u8] {
     #[inline]
     fn write(&mut self, bytes: &[u8]) {
         let src = bytes.as_ptr();
@@ -34,7 +209,7 @@ impl Output for &mut [u8] {
     }
 }
 
-impl Output for &mut fmt::Formatter<'_> {
+impl RawOutput for &mut fmt::Formatter<'_> {
     #[inline]
     fn write(&mut self, bytes: &[u8]) {
         if cfg!(debug_assertions) {
@@ -48,3 +223,18 @@ impl Output for &mut fmt::Formatter<'_> {
         let _ = self.write_char(byte as char);
     }
 }
+
+/// Adapts a public [`Output`] to the encoding kernels.
+///
+/// Only ASCII bytes may be written through this adapter.
+pub(crate) struct Sink<'a, O: ?Sized>(pub(crate) &'a mut O);
+
+impl<O: Output + ?Sized> RawOutput for Sink<'_, O> {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        debug_assert!(bytes.is_ascii());
+        // SAFETY: Only ASCII bytes are written, see above.
+        self.0
+            .write_str(unsafe { core::str::from_utf8_unchecked(bytes) });
+    }
+}
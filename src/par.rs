@@ -0,0 +1,249 @@
+//! Multi-threaded encoding and decoding using [`rayon`].
+
+use crate::{
+    arch, decode_checked, invalid_hex_error, offset_error, strip_prefix, unlikely, FromHexError,
+};
+use alloc::{string::String, vec::Vec};
+use rayon::prelude::*;
+
+/// The number of input bytes encoded by each task. Decoding tasks handle twice as many characters,
+/// so that every split happens at an even boundary.
+const CHUNK_LEN: usize = 1 << 16;
+
+/// Encodes `data` as a *lower* hex string, using multiple threads for large inputs.
+///
+/// The result is identical to [`encode`](crate::encode).
+///
+/// # Examples
+///
+/// ```
+/// let data = vec![0xab; 1 << 20];
+/// assert_eq!(const_hex::par_encode(&data), const_hex::encode(&data));
+/// ```
+#[inline]
+pub fn par_encode<T: AsRef<[u8]>>(data: T) -> String {
+    par_encode_inner::<false>(data.as_ref())
+}
+
+/// Encodes `data` as an *upper* hex string, using multiple threads for large inputs.
+///
+/// The result is identical to [`encode_upper`](crate::encode_upper).
+///
+/// # Examples
+///
+/// ```
+/// let data = vec![0xab; 1 << 20];
+/// assert_eq!(const_hex::par_encode_upper(&data), const_hex::encode_upper(&data));
+/// ```
+#[inline]
+pub fn par_encode_upper<T: AsRef<[u8]>>(data: T) -> String {
+    par_encode_inner::<true>(data.as_ref())
+}
+
+/// Encodes `input` as a *lower* hex string into a mutable slice, using multiple threads for large
+/// inputs.
+///
+/// # Errors
+///
+/// If the output buffer is not exactly `input.len() * 2` bytes long.
+///
+/// # Examples
+///
+/// ```
+/// let mut output = [0u8; 8];
+/// const_hex::par_encode_to_slice(b"kiwi", &mut output).unwrap();
+/// assert_eq!(&output, b"6b697769");
+/// ```
+#[inline]
+pub fn par_encode_to_slice<T: AsRef<[u8]>>(
+    input: T,
+    output: &mut [u8],
+) -> Result<(), FromHexError> {
+    par_encode_to_slice_inner::<false>(input.as_ref(), output)
+}
+
+/// Encodes `input` as an *upper* hex string into a mutable slice, using multiple threads for large
+/// inputs.
+///
+/// # Errors
+///
+/// If the output buffer is not exactly `input.len() * 2` bytes long.
+///
+/// # Examples
+///
+/// ```
+/// let mut output = [0u8; 8];
+/// const_hex::par_encode_to_slice_upper(b"kiwi", &mut output).unwrap();
+/// assert_eq!(&output, b"6B697769");
+/// ```
+#[inline]
+pub fn par_encode_to_slice_upper<T: AsRef<[u8]>>(
+    input: T,
+    output: &mut [u8],
+) -> Result<(), FromHexError> {
+    par_encode_to_slice_inner::<true>(input.as_ref(), output)
+}
+
+/// Decodes a hex string into raw bytes, using multiple threads for large inputs.
+///
+/// The result, including the index of an invalid character, is identical to
+/// [`decode`](crate::decode).
+///
+/// # Examples
+///
+/// ```
+/// let hex = "ab".repeat(1 << 20);
+/// assert_eq!(const_hex::par_decode(&hex), const_hex::decode(&hex));
+///
+/// let mut invalid = hex.clone();
+/// invalid.replace_range(1_000_001..1_000_002, "g");
+/// assert_eq!(
+///     const_hex::par_decode(&invalid),
+///     Err(const_hex::FromHexError::InvalidHexCharacter { c: 'g', index: 1_000_001 })
+/// );
+/// ```
+#[inline]
+pub fn par_decode<T: AsRef<[u8]>>(input: T) -> Result<Vec<u8>, FromHexError> {
+    fn par_decode_inner(input: &[u8]) -> Result<Vec<u8>, FromHexError> {
+        if unlikely(input.len() % 2 != 0) {
+            return Err(FromHexError::OddLength);
+        }
+        let stripped = strip_prefix(input);
+
+        // Do not initialize memory since it will be entirely overwritten.
+        let len = stripped.len() / 2;
+        let mut output = Vec::with_capacity(len);
+        // SAFETY: The entire vec is never read from, and gets dropped if decoding fails.
+        #[allow(clippy::uninit_vec)]
+        unsafe {
+            output.set_len(len);
+        }
+
+        par_decode_checked(stripped, &mut output)?;
+        Ok(output)
+    }
+
+    par_decode_inner(input.as_ref())
+}
+
+/// Decodes a hex string into a mutable bytes slice, using multiple threads for large inputs.
+///
+/// The result, including the index of an invalid character, is identical to
+/// [`decode_to_slice`](crate::decode_to_slice).
+///
+/// # Errors
+///
+/// See [`decode_to_slice`](crate::decode_to_slice).
+///
+/// # Examples
+///
+/// ```
+/// let mut bytes = [0u8; 4];
+/// const_hex::par_decode_to_slice("0x6b697769", &mut bytes).unwrap();
+/// assert_eq!(&bytes, b"kiwi");
+/// ```
+#[inline]
+pub fn par_decode_to_slice<T: AsRef<[u8]>>(
+    input: T,
+    output: &mut [u8],
+) -> Result<(), FromHexError> {
+    fn par_decode_to_slice_inner(input: &[u8], output: &mut [u8]) -> Result<(), FromHexError> {
+        if unlikely(input.len() % 2 != 0) {
+            return Err(FromHexError::OddLength);
+        }
+        let stripped = strip_prefix(input);
+        if unlikely(output.len() != stripped.len() / 2) {
+            return Err(FromHexError::InvalidStringLength);
+        }
+        par_decode_checked(stripped, output)
+    }
+
+    par_decode_to_slice_inner(input.as_ref(), output)
+}
+
+/// Checks that the input is a valid hex string, using multiple threads for large inputs.
+///
+/// The result, including the index of an invalid character, is identical to
+/// [`check`](crate::check).
+///
+/// # Examples
+///
+/// ```
+/// assert!(const_hex::par_check("0x48656c6c6f20776f726c6421").is_ok());
+/// assert!(const_hex::par_check("48656c6c6f20776f726c642").is_err());
+/// ```
+#[inline]
+pub fn par_check<T: AsRef<[u8]>>(input: T) -> Result<(), FromHexError> {
+    fn par_check_inner(input: &[u8]) -> Result<(), FromHexError> {
+        if input.len() % 2 != 0 {
+            return Err(FromHexError::OddLength);
+        }
+        let stripped = strip_prefix(input);
+        let invalid = stripped
+            .par_chunks(CHUNK_LEN * 2)
+            .enumerate()
+            .find_map_first(|(i, chunk)| {
+                if arch::check(chunk) {
+                    return None;
+                }
+                let e = unsafe { invalid_hex_error(chunk) };
+                Some(offset_error(e, i * CHUNK_LEN * 2))
+            });
+        match invalid {
+            None => Ok(()),
+            Some(e) => Err(offset_error(e, input.len() - stripped.len())),
+        }
+    }
+
+    par_check_inner(input.as_ref())
+}
+
+fn par_encode_inner<const UPPER: bool>(data: &[u8]) -> String {
+    let capacity = data.len() * 2;
+    let mut buf = Vec::<u8>::with_capacity(capacity);
+    // SAFETY: The entire vec is never read from, and gets dropped if decoding fails.
+    #[allow(clippy::uninit_vec)]
+    unsafe {
+        buf.set_len(capacity)
+    };
+    par_encode_unchecked::<UPPER>(data, &mut buf);
+    // SAFETY: We only write only ASCII bytes.
+    unsafe { String::from_utf8_unchecked(buf) }
+}
+
+fn par_encode_to_slice_inner<const UPPER: bool>(
+    input: &[u8],
+    output: &mut [u8],
+) -> Result<(), FromHexError> {
+    if unlikely(output.len() != 2 * input.len()) {
+        return Err(FromHexError::InvalidStringLength);
+    }
+    par_encode_unchecked::<UPPER>(input, output);
+    Ok(())
+}
+
+/// Assumes `output.len() == input.len() * 2`.
+fn par_encode_unchecked<const UPPER: bool>(input: &[u8], output: &mut [u8]) {
+    debug_assert_eq!(output.len(), input.len() * 2);
+    input
+        .par_chunks(CHUNK_LEN)
+        .zip(output.par_chunks_mut(CHUNK_LEN * 2))
+        // SAFETY: Each output chunk is exactly twice as long as its input chunk.
+        .for_each(|(input, output)| unsafe { arch::encode::<UPPER>(input, output) });
+}
+
+/// Assumes `output.len() == input.len() / 2`. The index of an invalid character is relative to
+/// the start of `input`, and refers to the first invalid character.
+fn par_decode_checked(input: &[u8], output: &mut [u8]) -> Result<(), FromHexError> {
+    debug_assert_eq!(output.len(), input.len() / 2);
+    let invalid = input
+        .par_chunks(CHUNK_LEN * 2)
+        .zip(output.par_chunks_mut(CHUNK_LEN))
+        .enumerate()
+        .find_map_first(|(i, (input, output))| {
+            // SAFETY: Each output chunk is exactly half as long as its input chunk.
+            let e = unsafe { decode_checked(input, output) }.err()?;
+            Some(offset_error(e, i * CHUNK_LEN * 2))
+        });
+    invalid.map_or(Ok(()), Err)
+}
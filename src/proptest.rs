@@ -0,0 +1,180 @@
+//! Strategies for generating hex strings with [`proptest`](https://docs.rs/proptest).
+//!
+//! All lengths are given in decoded bytes, so the generated strings are twice as long, plus two
+//! if a `0x` prefix is added.
+//!
+//! # Examples
+//!
+//! ```
+//! use proptest::prelude::*;
+//! use proptest::test_runner::TestRunner;
+//!
+//! let mut runner = TestRunner::default();
+//! runner
+//!     .run(&const_hex::proptest::valid(0..64), |s| {
+//!         let bytes = const_hex::decode(&s).unwrap();
+//!         prop_assert_eq!(const_hex::encode(bytes), s.trim_start_matches("0x").to_lowercase());
+//!         Ok(())
+//!     })
+//!     .unwrap();
+//! runner
+//!     .run(&const_hex::proptest::invalid(1..64), |h| {
+//!         prop_assert_eq!(const_hex::decode(&h.string), Err(h.decode_error()));
+//!         Ok(())
+//!     })
+//!     .unwrap();
+//! ```
+
+use crate::{FromHexError, HEX_CHARS_LOWER, HEX_CHARS_UPPER};
+use ::proptest::collection::{self, SizeRange};
+use ::proptest::prelude::*;
+use ::proptest::sample::Index;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Returns a strategy that generates byte vectors of `len` bytes.
+#[inline]
+pub fn bytes(len: impl Into<SizeRange>) -> impl Strategy<Value = Vec<u8>> {
+    collection::vec(any::<u8>(), len)
+}
+
+/// Returns a strategy that generates lowercase hex strings of `len` bytes, without a prefix.
+///
+/// # Examples
+///
+/// ```
+/// use proptest::prelude::*;
+/// use proptest::test_runner::TestRunner;
+///
+/// let mut runner = TestRunner::default();
+/// runner
+///     .run(&const_hex::proptest::lower(4), |s| {
+///         prop_assert_eq!(s.len(), 8);
+///         prop_assert!(s.bytes().all(|c| matches!(c, b'0'..=b'9' | b'a'..=b'f')));
+///         Ok(())
+///     })
+///     .unwrap();
+/// ```
+#[inline]
+pub fn lower(len: impl Into<SizeRange>) -> impl Strategy<Value = String> {
+    bytes(len).prop_map(crate::encode)
+}
+
+/// Returns a strategy that generates uppercase hex strings of `len` bytes, without a prefix.
+#[inline]
+pub fn upper(len: impl Into<SizeRange>) -> impl Strategy<Value = String> {
+    bytes(len).prop_map(crate::encode_upper)
+}
+
+/// Returns a strategy that generates hex strings of `len` bytes with randomly mixed case, without
+/// a prefix.
+#[inline]
+pub fn mixed_case(len: impl Into<SizeRange>) -> impl Strategy<Value = String> {
+    collection::vec(any::<(u8, u8)>(), len).prop_map(|pairs| {
+        let mut s = String::with_capacity(pairs.len() * 2);
+        for (byte, case) in pairs {
+            s.push(nibble_char(byte >> 4, case & 1 != 0));
+            s.push(nibble_char(byte & 0xf, case & 2 != 0));
+        }
+        s
+    })
+}
+
+/// Returns a strategy that prepends `0x` to the strings generated by `strategy`.
+#[inline]
+pub fn prefixed<S: Strategy<Value = String>>(strategy: S) -> impl Strategy<Value = String> {
+    strategy.prop_map(|s| format!("0x{s}"))
+}
+
+/// Returns a strategy that prepends `0x` to the strings generated by `strategy` about half of the
+/// time.
+#[inline]
+pub fn maybe_prefixed<S: Strategy<Value = String>>(strategy: S) -> impl Strategy<Value = String> {
+    (strategy, any::<bool>()).prop_map(|(s, prefix)| if prefix { format!("0x{s}") } else { s })
+}
+
+/// Returns a strategy that generates any hex string of `len` bytes accepted by
+/// [`decode`](crate::decode): mixed case, optionally prefixed with `0x`.
+#[inline]
+pub fn valid(len: impl Into<SizeRange>) -> impl Strategy<Value = String> {
+    maybe_prefixed(mixed_case(len))
+}
+
+/// A near-valid hex string with exactly one invalid character, generated by [`invalid`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvalidHex {
+    /// The string, which has an even number of digits.
+    pub string: String,
+    /// The byte index of the invalid character in `string`, including any prefix.
+    pub index: usize,
+    /// The invalid character. This is always ASCII.
+    pub c: char,
+}
+
+impl InvalidHex {
+    /// Returns the error that checking `string` produces.
+    #[inline]
+    pub const fn error(&self) -> FromHexError {
+        FromHexError::InvalidHexCharacter {
+            c: self.c,
+            index: self.index,
+        }
+    }
+
+    /// Returns the error that decoding `string` produces, whose index does not count the prefix.
+    #[inline]
+    pub fn decode_error(&self) -> FromHexError {
+        let prefix_len = self.string.len() - crate::strip_prefix(self.string.as_bytes()).len();
+        FromHexError::InvalidHexCharacter {
+            c: self.c,
+            index: self.index - prefix_len,
+        }
+    }
+}
+
+/// Returns a strategy that generates [`valid`] hex strings of `len` bytes with exactly one digit
+/// replaced by an invalid ASCII character.
+///
+/// A `len` of zero is treated as one, since there must be a digit to replace.
+#[inline]
+pub fn invalid(len: impl Into<SizeRange>) -> impl Strategy<Value = InvalidHex> {
+    (valid(len), any::<Index>(), invalid_char())
+        .prop_map(|(string, index, c)| replace_digit(string, |len| index.index(len), c))
+}
+
+/// Replaces the digit of `string` at `pick(number of digits)` with `c`.
+pub(crate) fn replace_digit(
+    mut string: String,
+    pick: impl FnOnce(usize) -> usize,
+    c: char,
+) -> InvalidHex {
+    let start = string.len() - crate::strip_prefix(string.as_bytes()).len();
+    if string.len() == start {
+        string.push_str("00");
+    }
+    let index = start + pick(string.len() - start);
+    string.replace_range(index..index + 1, c.encode_utf8(&mut [0; 4]));
+    // Don't accidentally create a prefix.
+    if start == 0 && matches!(string.as_bytes(), [b'0', b'x' | b'X', ..]) {
+        string.replace_range(..1, "1");
+    }
+    InvalidHex { string, index, c }
+}
+
+/// Returns a strategy that generates ASCII characters that are not hex digits.
+#[inline]
+pub fn invalid_char() -> impl Strategy<Value = char> {
+    (0u8..0x80)
+        .prop_filter("must not be a hex digit", |c| !c.is_ascii_hexdigit())
+        .prop_map(char::from)
+}
+
+#[inline]
+const fn nibble_char(nibble: u8, upper: bool) -> char {
+    let table = if upper {
+        HEX_CHARS_UPPER
+    } else {
+        HEX_CHARS_LOWER
+    };
+    table[nibble as usize] as char
+}
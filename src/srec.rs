@@ -0,0 +1,639 @@
+//! [Motorola S-record](https://en.wikipedia.org/wiki/SREC_(file_format)) reading and writing.
+//!
+//! This mirrors the [`ihex`](crate::ihex) module: records are decoded and encoded with the same
+//! SIMD implementations as the rest of the crate, and files are read into and written from sparse
+//! memory [`Image`]s.
+//!
+//! # Examples
+//!
+//! ```
+//! use const_hex::image::Image;
+//! use const_hex::srec;
+//!
+//! let mut image = Image::new();
+//! image.insert(0x1000, b"hello").unwrap();
+//! image.insert(0x1010, b"world").unwrap();
+//! image.set_start_address(Some(0x1000));
+//!
+//! let s = srec::encode(&image, srec::DEFAULT_RECORD_LEN);
+//! assert_eq!(
+//!     s,
+//!     "\
+//! S0030000FC
+//! S108100068656C6C6FD3
+//! S1081010776F726C64AF
+//! S5030002FA
+//! S9031000EC
+//! "
+//! );
+//! assert_eq!(srec::decode(&s), Ok(image));
+//! ```
+
+use crate::image::{self, trim_end, Image, InsertError};
+use crate::FromHexError;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// The default number of data bytes per record used when writing.
+pub const DEFAULT_RECORD_LEN: u8 = 32;
+
+/// The maximum length of a record in bytes, excluding the type: byte count, and up to 255 bytes
+/// of address, data and checksum.
+const MAX_RAW_LEN: usize = 1 + 255;
+
+/// The maximum length of a line, including the type and the trailing newline.
+const MAX_LINE_LEN: usize = 2 + MAX_RAW_LEN * 2 + 1;
+
+/// The size of the address field of a record.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum AddressSize {
+    /// 16-bit addresses (`S1`, `S5`, `S9`).
+    Bits16,
+    /// 24-bit addresses (`S2`, `S6`, `S8`).
+    Bits24,
+    /// 32-bit addresses (`S3`, `S7`).
+    Bits32,
+}
+
+impl AddressSize {
+    /// Returns the smallest address size that can represent `address`.
+    #[inline]
+    pub const fn for_address(address: u32) -> Self {
+        if address <= 0xffff {
+            Self::Bits16
+        } else if address <= 0xff_ffff {
+            Self::Bits24
+        } else {
+            Self::Bits32
+        }
+    }
+
+    /// Returns the length of the address field in bytes.
+    #[inline]
+    pub const fn bytes(self) -> usize {
+        match self {
+            Self::Bits16 => 2,
+            Self::Bits24 => 3,
+            Self::Bits32 => 4,
+        }
+    }
+
+    /// Returns `true` if `address` can be represented with this size.
+    #[inline]
+    pub const fn fits(self, address: u32) -> bool {
+        self as u8 >= Self::for_address(address) as u8
+    }
+
+    const fn data_type(self) -> u8 {
+        match self {
+            Self::Bits16 => 1,
+            Self::Bits24 => 2,
+            Self::Bits32 => 3,
+        }
+    }
+
+    const fn start_type(self) -> u8 {
+        match self {
+            Self::Bits16 => 9,
+            Self::Bits24 => 8,
+            Self::Bits32 => 7,
+        }
+    }
+}
+
+/// A Motorola S-record.
+///
+/// Formatting a record with [`Display`](fmt::Display) writes it as a line, without the trailing
+/// newline. Formatting fails with [`fmt::Error`] if the record does not meet the requirements
+/// below.
+///
+/// # Examples
+///
+/// ```
+/// use const_hex::srec::{AddressSize, Record};
+///
+/// let record = Record::parse("S1137AF00A0A0D0000000000000000000000000061").unwrap();
+/// assert_eq!(
+///     record,
+///     Record::Data { size: AddressSize::Bits16, address: 0x7af0, data: vec![0x0a, 0x0a, 0x0d, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0] },
+/// );
+/// assert_eq!(record.to_string(), "S1137AF00A0A0D0000000000000000000000000061");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Record {
+    /// Header data, usually a module name (`S0`).
+    ///
+    /// The data must fit in a record.
+    Header(Vec<u8>),
+    /// Data at `address` (`S1`, `S2` or `S3`, depending on `size`).
+    ///
+    /// `address` must fit in `size`, and `data` must fit in a record.
+    Data {
+        /// The size of the address field.
+        size: AddressSize,
+        /// The address of the data.
+        address: u32,
+        /// The data.
+        data: Vec<u8>,
+    },
+    /// The number of preceding data records (`S5`, or `S6` if the count does not fit in 16 bits).
+    ///
+    /// The count must fit in 24 bits.
+    Count(u32),
+    /// The start address, terminating the file (`S9`, `S8` or `S7`, depending on `size`).
+    ///
+    /// `address` must fit in `size`.
+    Start {
+        /// The size of the address field.
+        size: AddressSize,
+        /// The start address.
+        address: u32,
+    },
+}
+
+impl Record {
+    /// Parses a single record line. Trailing whitespace, such as a line terminator, is ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the line is not a valid record, or if its checksum does not match.
+    #[inline]
+    pub fn parse<T: AsRef<[u8]>>(line: T) -> Result<Self, ErrorKind> {
+        let mut raw = [0u8; MAX_RAW_LEN];
+        let (record_type, address, payload) = parse_raw(line.as_ref(), &mut raw)?;
+        let size = |record_type: u8| match record_type {
+            0 | 1 | 5 | 9 => AddressSize::Bits16,
+            2 | 6 | 8 => AddressSize::Bits24,
+            _ => AddressSize::Bits32,
+        };
+        let no_payload = || {
+            if payload.is_empty() {
+                Ok(())
+            } else {
+                let len = (size(record_type).bytes() + payload.len() + 1) as u8;
+                Err(ErrorKind::InvalidRecordLength { record_type, len })
+            }
+        };
+        Ok(match record_type {
+            0 => Self::Header(payload.to_vec()),
+            1..=3 => Self::Data {
+                size: size(record_type),
+                address,
+                data: payload.to_vec(),
+            },
+            5 | 6 => {
+                no_payload()?;
+                Self::Count(address)
+            }
+            7..=9 => {
+                no_payload()?;
+                Self::Start {
+                    size: size(record_type),
+                    address,
+                }
+            }
+            _ => unreachable!(),
+        })
+    }
+
+    /// Returns the record type, from 0 to 9.
+    #[inline]
+    pub const fn record_type(&self) -> u8 {
+        match *self {
+            Self::Header(_) => 0,
+            Self::Data { size, .. } => size.data_type(),
+            Self::Count(count) => {
+                if count <= 0xffff {
+                    5
+                } else {
+                    6
+                }
+            }
+            Self::Start { size, .. } => size.start_type(),
+        }
+    }
+
+    /// Formats the record into `line`, or returns an error if it does not fit in a record.
+    fn format<'a>(&self, line: &'a mut [u8; MAX_LINE_LEN]) -> Result<&'a str, fmt::Error> {
+        let (size, address, payload) = match *self {
+            Self::Header(ref data) => (AddressSize::Bits16, 0, &data[..]),
+            Self::Data {
+                size,
+                address,
+                ref data,
+            } => (size, address, &data[..]),
+            Self::Count(count) if count <= 0xff_ffff => {
+                (AddressSize::for_address(count), count, &[][..])
+            }
+            Self::Count(_) => return Err(fmt::Error),
+            Self::Start { size, address } => (size, address, &[][..]),
+        };
+        if !size.fits(address) || size.bytes() + payload.len() + 1 > 255 {
+            return Err(fmt::Error);
+        }
+        Ok(format_raw(self.record_type(), size, address, payload, line))
+    }
+}
+
+impl fmt::Display for Record {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut line = [0u8; MAX_LINE_LEN];
+        f.write_str(self.format(&mut line)?.trim_end())
+    }
+}
+
+/// Decodes an S-record file into a memory image.
+///
+/// Header records are ignored, and count records are checked against the number of preceding
+/// data records. A start address of zero is treated as no start address. Blank lines are ignored.
+/// Use [`Record::parse`] to access the records directly.
+///
+/// # Errors
+///
+/// Returns an error with the line number if a record is invalid, if data overlaps, if a count
+/// record does not match, or if the termination record is missing or followed by other records.
+///
+/// # Examples
+///
+/// ```
+/// use const_hex::srec::{self, ErrorKind};
+///
+/// let image = srec::decode("S107003001020304BE\nS9030000FC\n").unwrap();
+/// assert_eq!(image.segments()[0].address, 0x30);
+/// assert_eq!(image.segments()[0].data, [1, 2, 3, 4]);
+///
+/// let err = srec::decode("S107003001020304BE\nS5030002FA\nS9030000FC\n").unwrap_err();
+/// assert_eq!(err.line(), 2);
+/// assert_eq!(*err.kind(), ErrorKind::CountMismatch { expected: 1, actual: 2 });
+/// ```
+#[inline]
+pub fn decode<T: AsRef<[u8]>>(input: T) -> Result<Image, Error> {
+    image::decode::<Record>(input.as_ref())
+}
+
+/// Encodes a memory image as an S-record file, with at most `record_len` data bytes per record.
+///
+/// The smallest address size that fits all the data and the start address is used. The file
+/// starts with an empty header record, and ends with a count record, if the number of data
+/// records fits in 24 bits, and a termination record with the start address, or zero.
+///
+/// `record_len` is limited to the 255-byte maximum record length.
+///
+/// # Panics
+///
+/// Panics if `record_len` is zero.
+#[inline]
+pub fn encode(image: &Image, record_len: u8) -> String {
+    image::encode::<Record>(image, record_len)
+}
+
+/// An error that occurred while decoding an S-record file.
+pub type Error = image::Error<ErrorKind>;
+
+/// The kind of an [`Error`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The line does not start with `S`.
+    MissingStartCode,
+    /// The record type is not a digit, or is the reserved `S4`.
+    UnsupportedRecordType(char),
+    /// The line contains invalid hex. The index of an invalid character is relative to the start
+    /// of the line.
+    InvalidHex(FromHexError),
+    /// The line is too short to contain a record.
+    RecordTooShort,
+    /// The line length does not match the record's byte count.
+    LengthMismatch {
+        /// The expected line length, in characters.
+        expected: usize,
+        /// The actual line length, in characters.
+        actual: usize,
+    },
+    /// The record checksum is incorrect.
+    ChecksumMismatch {
+        /// The computed checksum.
+        expected: u8,
+        /// The checksum in the record.
+        actual: u8,
+    },
+    /// The record has an invalid byte count for its type.
+    InvalidRecordLength {
+        /// The record type, from 0 to 9.
+        record_type: u8,
+        /// The byte count.
+        len: u8,
+    },
+    /// A count record does not match the number of preceding data records.
+    CountMismatch {
+        /// The number of preceding data records.
+        expected: u32,
+        /// The count in the record.
+        actual: u32,
+    },
+    /// The file does not end with a termination record (`S7`, `S8` or `S9`).
+    MissingTermination,
+    /// A record follows the termination record.
+    RecordAfterTermination,
+    /// Data could not be inserted into the image.
+    Image(InsertError),
+}
+
+impl fmt::Display for ErrorKind {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::MissingStartCode => f.write_str("missing start code 'S'"),
+            Self::UnsupportedRecordType(t) => write!(f, "unsupported record type {t:?}"),
+            Self::InvalidHex(e) => e.fmt(f),
+            Self::RecordTooShort => f.write_str("record too short"),
+            Self::LengthMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "invalid record length: expected {expected} characters, got {actual}"
+                )
+            }
+            Self::ChecksumMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "checksum mismatch: expected {expected:02X}, got {actual:02X}"
+                )
+            }
+            Self::InvalidRecordLength { record_type, len } => {
+                write!(f, "invalid byte count {len} for record type S{record_type}")
+            }
+            Self::CountMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "record count mismatch: expected {expected}, got {actual}"
+                )
+            }
+            Self::MissingTermination => f.write_str("missing termination record"),
+            Self::RecordAfterTermination => f.write_str("record after termination record"),
+            Self::Image(e) => e.fmt(f),
+        }
+    }
+}
+
+/// A streaming S-record reader.
+///
+/// Yields each record in a [`BufRead`](std::io::BufRead), skipping blank lines. Errors are
+/// returned as [`InvalidData`](std::io::ErrorKind::InvalidData) wrapping an [`Error`] with the
+/// line number.
+///
+/// # Examples
+///
+/// ```
+/// use const_hex::srec::{AddressSize, Reader, Record};
+///
+/// let input = "S0030000FC\r\nS9030000FC\r\n";
+/// let records = Reader::new(input.as_bytes()).collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(records[0], Record::Header(vec![]));
+/// assert_eq!(records[1], Record::Start { size: AddressSize::Bits16, address: 0 });
+/// ```
+#[cfg(feature = "std")]
+pub type Reader<R> = image::Reader<R, Record>;
+
+/// Reads an S-record file into a memory image.
+///
+/// See [`decode`] for more details.
+///
+/// # Errors
+///
+/// Returns an error if reading fails, or an [`InvalidData`](std::io::ErrorKind::InvalidData)
+/// error wrapping an [`Error`] if the file is invalid.
+#[cfg(feature = "std")]
+#[inline]
+pub fn read<R: std::io::Read>(reader: R) -> std::io::Result<Image> {
+    image::read::<Record>(reader)
+}
+
+/// Writes a memory image as an S-record file.
+///
+/// See [`encode`] for more details.
+///
+/// # Errors
+///
+/// Returns an error if writing fails.
+///
+/// # Panics
+///
+/// Panics if `record_len` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use const_hex::image::Image;
+/// use const_hex::srec;
+///
+/// let image = Image::from_bytes(0x0100_0000, &[0xaa; 40]).unwrap();
+/// let mut out = Vec::new();
+/// srec::write(&mut out, &image, 16).unwrap();
+/// assert!(out.starts_with(b"S0030000FC\nS315010000"));
+/// assert_eq!(srec::read(&out[..]).unwrap(), image);
+/// ```
+#[cfg(feature = "std")]
+#[inline]
+pub fn write<W: std::io::Write>(writer: W, image: &Image, record_len: u8) -> std::io::Result<()> {
+    image::write::<Record>(writer, image, record_len)
+}
+
+impl image::Record for Record {}
+
+impl image::sealed::Sealed for Record {
+    type ErrorKind = ErrorKind;
+
+    #[inline]
+    fn parse(line: &[u8]) -> Result<Self, ErrorKind> {
+        Self::parse(line)
+    }
+}
+
+impl image::Format for Record {
+    type Loader = Loader;
+
+    #[inline]
+    fn write_lines<E>(
+        image: &Image,
+        record_len: u8,
+        mut emit: impl FnMut(&str) -> Result<(), E>,
+    ) -> Result<(), E> {
+        let mut line = [0u8; MAX_LINE_LEN];
+        write_records(image, record_len, |record_type, size, address, payload| {
+            emit(format_raw(record_type, size, address, payload, &mut line))
+        })
+    }
+}
+
+/// Builds an image from records.
+#[derive(Default)]
+pub(crate) struct Loader {
+    image: Image,
+    data_records: u32,
+    terminated: bool,
+}
+
+impl image::Loader<Record> for Loader {
+    #[inline]
+    fn push(&mut self, record: Record) -> Result<(), ErrorKind> {
+        if self.terminated {
+            return Err(ErrorKind::RecordAfterTermination);
+        }
+        match record {
+            Record::Header(_) => {}
+            Record::Data { address, data, .. } => {
+                self.image
+                    .insert(address, &data)
+                    .map_err(ErrorKind::Image)?;
+                self.data_records = self.data_records.wrapping_add(1);
+            }
+            Record::Count(count) => {
+                if count != self.data_records {
+                    return Err(ErrorKind::CountMismatch {
+                        expected: self.data_records,
+                        actual: count,
+                    });
+                }
+            }
+            Record::Start { address, .. } => {
+                self.image
+                    .set_start_address(if address == 0 { None } else { Some(address) });
+                self.terminated = true;
+            }
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn finish(self) -> Result<Image, ErrorKind> {
+        if self.terminated {
+            Ok(self.image)
+        } else {
+            Err(ErrorKind::MissingTermination)
+        }
+    }
+}
+
+/// Splits `image` into raw records and passes them to `emit`.
+fn write_records<E>(
+    image: &Image,
+    record_len: u8,
+    mut emit: impl FnMut(u8, AddressSize, u32, &[u8]) -> Result<(), E>,
+) -> Result<(), E> {
+    assert!(record_len != 0, "record length must not be zero");
+    let last = image.segments().last().map_or(0, |s| (s.end() - 1) as u32);
+    let start = image.start_address().unwrap_or(0);
+    let size = AddressSize::for_address(last.max(start));
+    let record_len = (record_len as usize).min(255 - size.bytes() - 1);
+
+    emit(0, AddressSize::Bits16, 0, &[])?;
+    let mut count = 0u32;
+    for segment in image.segments() {
+        let mut address = segment.address;
+        for chunk in segment.data.chunks(record_len) {
+            emit(size.data_type(), size, address, chunk)?;
+            address = address.wrapping_add(chunk.len() as u32);
+            count = count.wrapping_add(1);
+        }
+    }
+    if count <= 0xff_ffff {
+        let count_size = AddressSize::for_address(count);
+        emit(if count <= 0xffff { 5 } else { 6 }, count_size, count, &[])?;
+    }
+    emit(size.start_type(), size, start, &[])
+}
+
+/// Parses a record line into `raw`, returning the record type, address and payload.
+fn parse_raw<'a>(
+    line: &[u8],
+    raw: &'a mut [u8; MAX_RAW_LEN],
+) -> Result<(u8, u32, &'a [u8]), ErrorKind> {
+    let line = trim_end(line);
+    let (record_type, hex) = match line {
+        [b'S', t @ b'0'..=b'9', hex @ ..] if *t != b'4' => (t - b'0', hex),
+        [b'S', t, ..] => return Err(ErrorKind::UnsupportedRecordType(*t as char)),
+        [b'S'] => return Err(ErrorKind::RecordTooShort),
+        _ => return Err(ErrorKind::MissingStartCode),
+    };
+    let invalid_hex = |e| ErrorKind::InvalidHex(crate::offset_error(e, 2));
+    let address_len = match record_type {
+        0 | 1 | 5 | 9 => 2,
+        2 | 6 | 8 => 3,
+        _ => 4,
+    };
+    if hex.len() < 2 {
+        return Err(ErrorKind::RecordTooShort);
+    }
+
+    let mut count = [0u8];
+    crate::decode_to_slice_unprefixed(&hex[..2], &mut count).map_err(invalid_hex)?;
+    let len = 1 + count[0] as usize;
+    if hex.len() != len * 2 {
+        return Err(ErrorKind::LengthMismatch {
+            expected: 2 + len * 2,
+            actual: line.len(),
+        });
+    }
+    if count[0] as usize <= address_len {
+        return Err(ErrorKind::InvalidRecordLength {
+            record_type,
+            len: count[0],
+        });
+    }
+
+    let raw = &mut raw[..len];
+    crate::decode_to_slice_unprefixed(hex, raw).map_err(invalid_hex)?;
+    let (checksum, record) = raw.split_last().unwrap();
+    let expected = checksum_of(record);
+    if *checksum != expected {
+        return Err(ErrorKind::ChecksumMismatch {
+            expected,
+            actual: *checksum,
+        });
+    }
+    let mut address = [0u8; 4];
+    address[4 - address_len..].copy_from_slice(&record[1..1 + address_len]);
+    Ok((
+        record_type,
+        u32::from_be_bytes(address),
+        &record[1 + address_len..],
+    ))
+}
+
+/// Formats a record into `line`, including the trailing newline.
+fn format_raw<'a>(
+    record_type: u8,
+    size: AddressSize,
+    address: u32,
+    payload: &[u8],
+    line: &'a mut [u8; MAX_LINE_LEN],
+) -> &'a str {
+    assert!(
+        size.fits(address),
+        "address {address:#x} does not fit in {size:?}"
+    );
+    let count = size.bytes() + payload.len() + 1;
+    assert!(count <= 255, "record data is too long");
+    let mut raw = [0u8; MAX_RAW_LEN];
+    let len = 1 + count;
+    raw[0] = count as u8;
+    raw[1..1 + size.bytes()].copy_from_slice(&address.to_be_bytes()[4 - size.bytes()..]);
+    raw[1 + size.bytes()..len - 1].copy_from_slice(payload);
+    raw[len - 1] = checksum_of(&raw[..len - 1]);
+
+    let line = &mut line[..2 + len * 2 + 1];
+    line[0] = b'S';
+    line[1] = b'0' + record_type;
+    line[line.len() - 1] = b'\n';
+    let end = line.len() - 1;
+    crate::encode_to_slice_upper(&raw[..len], &mut line[2..end]).unwrap();
+    // SAFETY: Only ASCII was written.
+    unsafe { core::str::from_utf8_unchecked(line) }
+}
+
+/// Returns the ones' complement of the sum of `bytes`.
+fn checksum_of(bytes: &[u8]) -> u8 {
+    !bytes.iter().fold(0u8, |sum, &b| sum.wrapping_add(b))
+}
@@ -34,7 +34,7 @@ use alloc::sync::Arc;
 /// ```
 #[cfg_attr(feature = "alloc", doc = "\n[`encode`]: crate::encode")]
 #[cfg_attr(not(feature = "alloc"), doc = "\n[`encode`]: crate::encode_to_slice")]
-#[deprecated(note = "use `ToHexExt` instead")]
+#[deprecated(note = "use `ToHexExt` or `encode_iter` instead")]
 pub trait ToHex {
     /// Encode the hex strict representing `self` into the result.
     /// Lower case letters are used (e.g. `f9b4ca`).
@@ -79,38 +79,13 @@ pub trait ToHexExt {
     fn encode_hex_upper_with_prefix(&self) -> String;
 }
 
-struct BytesToHexChars<'a, const UPPER: bool> {
-    inner: core::slice::Iter<'a, u8>,
-    next: Option<char>,
-}
-
-impl<'a, const UPPER: bool> BytesToHexChars<'a, UPPER> {
-    fn new(inner: &'a [u8]) -> Self {
-        BytesToHexChars {
-            inner: inner.iter(),
-            next: None,
-        }
-    }
-}
-
-impl<const UPPER: bool> Iterator for BytesToHexChars<'_, UPPER> {
-    type Item = char;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        match self.next.take() {
-            Some(current) => Some(current),
-            None => self.inner.next().map(|byte| {
-                let (high, low) = crate::byte2hex::<UPPER>(*byte);
-                self.next = Some(low as char);
-                high as char
-            }),
-        }
-    }
-}
-
 #[inline]
 fn encode_to_iter<T: iter::FromIterator<char>, const UPPER: bool>(source: &[u8]) -> T {
-    BytesToHexChars::<UPPER>::new(source).collect()
+    if UPPER {
+        crate::encode_iter_upper(source).map(char::from).collect()
+    } else {
+        crate::encode_iter(source).map(char::from).collect()
+    }
 }
 
 #[allow(deprecated)]
@@ -151,7 +126,12 @@ impl<T: AsRef<[u8]>> ToHexExt for T {
 
 /// Types that can be decoded from a hex string.
 ///
-/// This trait is implemented for `Vec<u8>` and small `u8`-arrays.
+/// This trait is implemented for `Vec<u8>` and small `u8`-arrays, as well as,
+/// behind features of the same name, for `heapless::Vec<u8, N>`,
+/// `arrayvec::ArrayVec<u8, N>`, `smallvec::SmallVec<[u8; N]>`, and
+/// `bytes::Bytes` and `bytes::BytesMut`. Fixed-capacity containers fail with
+/// [`InvalidStringLength`](crate::FromHexError::InvalidStringLength) if the
+/// decoded bytes do not fit.
 ///
 /// # Example
 ///
@@ -278,3 +258,88 @@ impl<const N: usize> FromHex for [i8; N] {
             .map(|buf| unsafe { *(&buf as *const [u8; N] as *const [i8; N]) })
     }
 }
+
+/// Returns the length of the decoded `hex`, after checking that it has an even length and fits in
+/// `capacity` bytes.
+#[cfg(any(
+    feature = "arrayvec",
+    feature = "bytes",
+    feature = "heapless",
+    feature = "smallvec"
+))]
+const fn decoded_len(hex: &[u8], capacity: usize) -> Result<usize, crate::FromHexError> {
+    if hex.len() % 2 != 0 {
+        return Err(crate::FromHexError::OddLength);
+    }
+    let len = crate::strip_prefix(hex).len() / 2;
+    if len > capacity {
+        return Err(crate::FromHexError::InvalidStringLength);
+    }
+    Ok(len)
+}
+
+#[cfg(feature = "heapless")]
+impl<const N: usize> FromHex for heapless::Vec<u8, N> {
+    type Error = crate::FromHexError;
+
+    #[inline]
+    fn from_hex<T: AsRef<[u8]>>(hex: T) -> Result<Self, Self::Error> {
+        let hex = hex.as_ref();
+        let mut vec = Self::new();
+        // Cannot fail, since the length is checked.
+        let _ = vec.resize(decoded_len(hex, N)?, 0);
+        crate::decode_to_slice(hex, &mut vec)?;
+        Ok(vec)
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+impl<const N: usize> FromHex for arrayvec::ArrayVec<u8, N> {
+    type Error = crate::FromHexError;
+
+    #[inline]
+    fn from_hex<T: AsRef<[u8]>>(hex: T) -> Result<Self, Self::Error> {
+        let hex = hex.as_ref();
+        let mut vec = Self::new();
+        vec.extend(iter::repeat(0).take(decoded_len(hex, N)?));
+        crate::decode_to_slice(hex, &mut vec)?;
+        Ok(vec)
+    }
+}
+
+#[cfg(feature = "smallvec")]
+impl<const N: usize> FromHex for smallvec::SmallVec<[u8; N]> {
+    type Error = crate::FromHexError;
+
+    #[inline]
+    fn from_hex<T: AsRef<[u8]>>(hex: T) -> Result<Self, Self::Error> {
+        let hex = hex.as_ref();
+        let mut vec = Self::from_elem(0, decoded_len(hex, usize::MAX)?);
+        crate::decode_to_slice(hex, &mut vec)?;
+        Ok(vec)
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl FromHex for bytes::BytesMut {
+    type Error = crate::FromHexError;
+
+    #[inline]
+    fn from_hex<T: AsRef<[u8]>>(hex: T) -> Result<Self, Self::Error> {
+        let hex = hex.as_ref();
+        let mut buf = Self::new();
+        buf.resize(decoded_len(hex, usize::MAX)?, 0);
+        crate::decode_to_slice(hex, &mut buf)?;
+        Ok(buf)
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl FromHex for bytes::Bytes {
+    type Error = crate::FromHexError;
+
+    #[inline]
+    fn from_hex<T: AsRef<[u8]>>(hex: T) -> Result<Self, Self::Error> {
+        crate::decode(hex.as_ref()).map(Self::from)
+    }
+}
@@ -0,0 +1,202 @@
+use crate::{arch, unlikely, FromHexError};
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// Encodes `input` as a lowercase hex string of UTF-16 code units into `output`.
+///
+/// # Errors
+///
+/// If `output` is not exactly `input.len() * 2` code units long.
+///
+/// # Examples
+///
+/// ```
+/// let mut hex = [0u16; 8];
+/// const_hex::encode_to_utf16(b"kiwi", &mut hex)?;
+/// assert_eq!(String::from_utf16_lossy(&hex), "6b697769");
+/// # Ok::<_, const_hex::FromHexError>(())
+/// ```
+#[inline]
+pub fn encode_to_utf16<T: AsRef<[u8]>>(input: T, output: &mut [u16]) -> Result<(), FromHexError> {
+    encode_to_utf16_inner::<false>(input.as_ref(), output)
+}
+
+/// Encodes `input` as an uppercase hex string of UTF-16 code units into `output`.
+///
+/// # Errors
+///
+/// If `output` is not exactly `input.len() * 2` code units long.
+///
+/// # Examples
+///
+/// ```
+/// let mut hex = [0u16; 8];
+/// const_hex::encode_to_utf16_upper(b"kiwi", &mut hex)?;
+/// assert_eq!(String::from_utf16_lossy(&hex), "6B697769");
+/// # Ok::<_, const_hex::FromHexError>(())
+/// ```
+#[inline]
+pub fn encode_to_utf16_upper<T: AsRef<[u8]>>(
+    input: T,
+    output: &mut [u16],
+) -> Result<(), FromHexError> {
+    encode_to_utf16_inner::<true>(input.as_ref(), output)
+}
+
+fn encode_to_utf16_inner<const UPPER: bool>(
+    input: &[u8],
+    output: &mut [u16],
+) -> Result<(), FromHexError> {
+    if unlikely(output.len() != 2 * input.len()) {
+        return Err(FromHexError::InvalidStringLength);
+    }
+    // SAFETY: Lengths are checked above.
+    unsafe { arch::encode_utf16::<UPPER>(input, output) };
+    Ok(())
+}
+
+/// Checks that a string of UTF-16 code units is valid hex and can be decoded successfully.
+///
+/// Strips the `0x` prefix if present. Non-ASCII code units are invalid characters.
+///
+/// # Errors
+///
+/// If the input has an odd length or contains invalid hex characters.
+///
+/// # Examples
+///
+/// ```
+/// let hex: Vec<u16> = "0x6b697769".encode_utf16().collect();
+/// assert!(const_hex::check_utf16(&hex).is_ok());
+///
+/// let hex: Vec<u16> = "6b69776٩".encode_utf16().collect();
+/// assert_eq!(
+///     const_hex::check_utf16(&hex),
+///     Err(const_hex::FromHexError::InvalidHexCharacter { c: '٩', index: 7 })
+/// );
+/// ```
+#[inline]
+pub fn check_utf16<T: AsRef<[u16]>>(input: T) -> Result<(), FromHexError> {
+    fn check_utf16_inner(input: &[u16]) -> Result<(), FromHexError> {
+        if unlikely(input.len() % 2 != 0) {
+            return Err(FromHexError::OddLength);
+        }
+        let stripped = strip_prefix(input);
+        if arch::check_utf16(stripped) {
+            Ok(())
+        } else {
+            Err(invalid_hex_error(input, input.len() - stripped.len()))
+        }
+    }
+
+    check_utf16_inner(input.as_ref())
+}
+
+/// Decodes a hex string of UTF-16 code units into raw bytes.
+///
+/// Strips the `0x` prefix if present. Both upper and lower case characters are valid. Non-ASCII
+/// code units are invalid characters.
+///
+/// # Errors
+///
+/// If the input has an odd length or contains invalid hex characters. As with
+/// [`decode`](crate::decode), the index of an invalid character does not count the prefix.
+///
+/// # Examples
+///
+/// ```
+/// let hex: Vec<u16> = "0x6B697769".encode_utf16().collect();
+/// assert_eq!(const_hex::decode_utf16(&hex), Ok(b"kiwi".to_vec()));
+///
+/// let hex: Vec<u16> = "6b697".encode_utf16().collect();
+/// assert_eq!(const_hex::decode_utf16(&hex), Err(const_hex::FromHexError::OddLength));
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn decode_utf16<T: AsRef<[u16]>>(input: T) -> Result<Vec<u8>, FromHexError> {
+    fn decode_utf16_inner(input: &[u16]) -> Result<Vec<u8>, FromHexError> {
+        if unlikely(input.len() % 2 != 0) {
+            return Err(FromHexError::OddLength);
+        }
+        let mut output = vec![0; strip_prefix(input).len() / 2];
+        decode_utf16_to_slice_inner(input, &mut output)?;
+        Ok(output)
+    }
+
+    decode_utf16_inner(input.as_ref())
+}
+
+/// Decodes a hex string of UTF-16 code units into a mutable bytes slice.
+///
+/// Strips the `0x` prefix if present. Both upper and lower case characters are valid. Non-ASCII
+/// code units are invalid characters.
+///
+/// # Errors
+///
+/// If the input has an odd length or contains invalid hex characters, or if the output slice is
+/// not exactly half the length of the input without its prefix. Invalid characters are reported
+/// as by [`decode_utf16`].
+///
+/// # Examples
+///
+/// ```
+/// let hex: Vec<u16> = "6b697769".encode_utf16().collect();
+/// let mut bytes = [0u8; 4];
+/// const_hex::decode_utf16_to_slice(&hex, &mut bytes)?;
+/// assert_eq!(&bytes, b"kiwi");
+/// # Ok::<_, const_hex::FromHexError>(())
+/// ```
+#[inline]
+pub fn decode_utf16_to_slice<T: AsRef<[u16]>>(
+    input: T,
+    output: &mut [u8],
+) -> Result<(), FromHexError> {
+    decode_utf16_to_slice_inner(input.as_ref(), output)
+}
+
+fn decode_utf16_to_slice_inner(input: &[u16], output: &mut [u8]) -> Result<(), FromHexError> {
+    if unlikely(input.len() % 2 != 0) {
+        return Err(FromHexError::OddLength);
+    }
+    let stripped = strip_prefix(input);
+    if unlikely(output.len() != stripped.len() / 2) {
+        return Err(FromHexError::InvalidStringLength);
+    }
+    // SAFETY: Lengths are checked above.
+    if unsafe { arch::decode_utf16_checked(stripped, output) } {
+        Ok(())
+    } else {
+        Err(invalid_hex_error(stripped, 0))
+    }
+}
+
+#[inline]
+const fn strip_prefix(input: &[u16]) -> &[u16] {
+    match input {
+        [0x30, 0x58 | 0x78, rest @ ..] => rest,
+        _ => input,
+    }
+}
+
+/// Creates an invalid hex error for the first invalid code unit in `input` after a prefix of
+/// length `start`.
+///
+/// The character is decoded from the code units at that index, or is
+/// [`char::REPLACEMENT_CHARACTER`] for an unpaired surrogate.
+#[cold]
+fn invalid_hex_error(input: &[u16], start: usize) -> FromHexError {
+    let index = input[start..]
+        .iter()
+        .position(|&c| !arch::generic::check_utf16(&[c]))
+        .map(|i| start + i);
+    let index = match index {
+        Some(index) => index,
+        None => unreachable!("input was valid but `check` failed"),
+    };
+    let c = char::decode_utf16(input[index..].iter().copied())
+        .next()
+        .and_then(Result::ok)
+        .unwrap_or(char::REPLACEMENT_CHARACTER);
+    FromHexError::InvalidHexCharacter { c, index }
+}
@@ -0,0 +1,105 @@
+/* Tests for the C interface in `include/const_hex.h`. Run with `make -C tests/ffi`. */
+
+#include "const_hex.h"
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+static int failures = 0;
+
+#define CHECK(cond)                                                       \
+    do {                                                                  \
+        if (!(cond)) {                                                    \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, #cond); \
+            failures++;                                                   \
+        }                                                                 \
+    } while (0)
+
+static void test_encode(void) {
+    const uint8_t input[] = {0x01, 0xab, 0xff};
+    char output[6];
+
+    CHECK(const_hex_encode(input, 3, output, 6, false) == CONST_HEX_OK);
+    CHECK(memcmp(output, "01abff", 6) == 0);
+
+    CHECK(const_hex_encode(input, 3, output, 6, true) == CONST_HEX_OK);
+    CHECK(memcmp(output, "01ABFF", 6) == 0);
+
+    memset(output, 'x', sizeof(output));
+    CHECK(const_hex_encode(input, 3, output, 5, false) == CONST_HEX_INVALID_LENGTH);
+    CHECK(memcmp(output, "xxxxx", 5) == 0);
+
+    CHECK(const_hex_encode(NULL, 0, NULL, 0, false) == CONST_HEX_OK);
+}
+
+static void test_encode_large(void) {
+    /* Long enough to go through the SIMD kernels. */
+    uint8_t input[1000];
+    char output[2000];
+    uint8_t decoded[1000];
+    for (size_t i = 0; i < sizeof(input); i++) {
+        input[i] = (uint8_t)(i * 7);
+    }
+
+    CHECK(const_hex_encode(input, sizeof(input), output, sizeof(output), false) == CONST_HEX_OK);
+    CHECK(memcmp(output, "00070e151c23", 12) == 0);
+    CHECK(const_hex_decode(output, sizeof(output), decoded, sizeof(decoded), NULL) ==
+          CONST_HEX_OK);
+    CHECK(memcmp(decoded, input, sizeof(input)) == 0);
+}
+
+static void test_decode(void) {
+    uint8_t output[4];
+    size_t index = 12345;
+
+    CHECK(const_hex_decode("6b697769", 8, output, 4, &index) == CONST_HEX_OK);
+    CHECK(memcmp(output, "kiwi", 4) == 0);
+    CHECK(index == 12345);
+
+    CHECK(const_hex_decode("0x6B697769", 10, output, 4, NULL) == CONST_HEX_OK);
+    CHECK(memcmp(output, "kiwi", 4) == 0);
+
+    CHECK(const_hex_decode("0x6b69gg69", 10, output, 4, &index) == CONST_HEX_INVALID_CHARACTER);
+    CHECK(index == 6);
+    CHECK(const_hex_decode("0x6b69gg69", 10, output, 4, NULL) == CONST_HEX_INVALID_CHARACTER);
+
+    CHECK(const_hex_decode("6b6977", 6, output, 4, NULL) == CONST_HEX_INVALID_LENGTH);
+    CHECK(const_hex_decode("6b69776", 7, output, 4, NULL) == CONST_HEX_ODD_LENGTH);
+
+    CHECK(const_hex_decode(NULL, 0, NULL, 0, NULL) == CONST_HEX_OK);
+}
+
+static void test_check(void) {
+    size_t index = 0;
+
+    CHECK(const_hex_check("0123456789abcdefABCDEF", 22, &index) == CONST_HEX_OK);
+    CHECK(const_hex_check("0x00", 4, NULL) == CONST_HEX_OK);
+    CHECK(const_hex_check("", 0, NULL) == CONST_HEX_OK);
+    CHECK(const_hex_check("00 0", 4, &index) == CONST_HEX_INVALID_CHARACTER);
+    CHECK(index == 2);
+    CHECK(const_hex_check("000", 3, &index) == CONST_HEX_ODD_LENGTH);
+}
+
+static void test_decode_error(void) {
+    CHECK(strcmp(const_hex_decode_error(CONST_HEX_OK), "success") == 0);
+    CHECK(strcmp(const_hex_decode_error(CONST_HEX_INVALID_CHARACTER), "invalid character") == 0);
+    CHECK(strcmp(const_hex_decode_error(CONST_HEX_ODD_LENGTH), "odd number of digits") == 0);
+    CHECK(strcmp(const_hex_decode_error(CONST_HEX_INVALID_LENGTH), "invalid string length") == 0);
+    CHECK(strcmp(const_hex_decode_error(-1), "unknown status") == 0);
+}
+
+int main(void) {
+    test_encode();
+    test_encode_large();
+    test_decode();
+    test_check();
+    test_decode_error();
+
+    if (failures != 0) {
+        fprintf(stderr, "%d check(s) failed\n", failures);
+        return EXIT_FAILURE;
+    }
+    printf("all tests passed\n");
+    return EXIT_SUCCESS;
+}
@@ -62,6 +62,128 @@ fn buffer_const_upper() {
     assert_upper(BUFFER.as_str());
 }
 
+#[test]
+fn buffer_value() {
+    use const_hex::FromHexError;
+    use std::collections::{BTreeSet, HashSet};
+
+    let a = Buffer::<2, true>::new().const_format(&[0xab, 0xcd]);
+    let b = a;
+    assert_eq!(a, b);
+    assert_eq!(a, "0xabcd");
+    assert_eq!("0xabcd", a);
+    assert_eq!(*"0xabcd", a);
+    #[cfg(feature = "alloc")]
+    assert_eq!(a, String::from("0xabcd"));
+    assert_eq!(format!("{a}"), "0xabcd");
+    assert_eq!(format!("{a:>8}|{a:<8}|"), "  0xabcd|0xabcd  |");
+    assert_eq!(a.len(), 6);
+    assert!(a.starts_with("0x"));
+    assert_eq!(a.decode(), Ok([0xab, 0xcd]));
+
+    let c = Buffer::<2, true>::new().const_format_upper(&[0xab, 0xcd]);
+    assert_ne!(a, c);
+    assert_eq!(a.cmp(&c), a.as_str().cmp(c.as_str()));
+    assert_eq!(c.decode(), a.decode());
+    let set = [a, b, c].into_iter().collect::<HashSet<_>>();
+    assert_eq!(set.len(), 2);
+    assert!(set.contains("0xABCD"));
+    let set = [a, c].into_iter().collect::<BTreeSet<_>>();
+    assert_eq!(set.first(), Some(&c));
+    assert!(set.contains("0xabcd"));
+
+    for input in ["abcd", "0xabcd", "0XabCD", "ABCD"] {
+        let with: Buffer<2, true> = input.parse().unwrap();
+        let without = Buffer::<2>::try_from(input).unwrap();
+        let digits = input.trim_start_matches("0x").trim_start_matches("0X");
+        assert_eq!(with.as_str(), format!("0x{digits}"));
+        assert_eq!(without, digits);
+        assert_eq!(without.decode(), Ok([0xab, 0xcd]));
+    }
+    assert_eq!("abc".parse::<Buffer<2>>(), Err(FromHexError::OddLength));
+    assert_eq!(
+        "abcdef".parse::<Buffer<2>>(),
+        Err(FromHexError::InvalidStringLength)
+    );
+    assert_eq!(
+        "0xabzd".parse::<Buffer<2>>(),
+        Err(FromHexError::InvalidHexCharacter { c: 'z', index: 4 })
+    );
+    assert_eq!(
+        Buffer::<2>::new().decode(),
+        Err(FromHexError::InvalidHexCharacter { c: '\0', index: 0 })
+    );
+}
+
+#[test]
+fn hex_buf() {
+    use const_hex::{FromHexError, HexBuf};
+
+    let mut buf = HexBuf::<256>::new();
+    assert_eq!(buf, "");
+    assert_lower(buf.format(ALL).unwrap());
+    assert_upper(buf.format_upper(ALL).unwrap());
+    assert_eq!(buf.byte_len(), 256);
+    let all = Buffer::<256>::new().const_format(&ALL);
+    for len in [0, 1, 15, 16, 17, 32, 33, 255] {
+        assert_eq!(buf.format(&ALL[..len]).unwrap(), &all[..len * 2]);
+        assert_eq!(buf.byte_len(), len);
+        assert_eq!(buf.len(), len * 2);
+    }
+    buf.clear();
+    assert!(buf.is_empty());
+
+    let mut small = HexBuf::<2, true>::new();
+    assert_eq!(small, "0x");
+    assert_eq!(HexBuf::<2, true>::CAPACITY, 6);
+    assert_eq!(small.format([0xab]).unwrap(), "0xab");
+    assert_eq!(
+        small.format([1, 2, 3]),
+        Err(FromHexError::InvalidStringLength)
+    );
+    assert_eq!(small, "0xab");
+    assert_eq!(format!("{small:?}"), r#"HexBuf("0xab")"#);
+    let mut bytes = [0; 1];
+    small.decode_to_slice(&mut bytes).unwrap();
+    assert_eq!(bytes, [0xab]);
+    assert_eq!(
+        small.decode_to_slice(&mut [0; 2]),
+        Err(FromHexError::InvalidStringLength)
+    );
+
+    const CONST: HexBuf<4, true> = match HexBuf::new().const_format_upper(b"\xde\xad") {
+        Ok(buf) => buf,
+        Err(_) => panic!(),
+    };
+    assert_eq!(CONST, "0xDEAD");
+    assert_eq!(
+        HexBuf::<1>::new().const_format(&[1, 2]),
+        Err(FromHexError::InvalidStringLength)
+    );
+
+    // Only the formatted part takes part in comparisons.
+    let mut a = HexBuf::<4>::new();
+    a.format([0xab, 0xcd, 0xef]).unwrap();
+    a.format([0xab]).unwrap();
+    let b: HexBuf<4> = "0xab".parse().unwrap();
+    assert_eq!(a, b);
+    assert_eq!(a.cmp(&b), core::cmp::Ordering::Equal);
+    assert_eq!(
+        "0xabcdef0102".parse::<HexBuf<4>>(),
+        Err(FromHexError::InvalidStringLength)
+    );
+    assert_eq!(
+        HexBuf::<4>::try_from("abzd"),
+        Err(FromHexError::InvalidHexCharacter { c: 'z', index: 2 })
+    );
+    assert_eq!("abc".parse::<HexBuf<4>>(), Err(FromHexError::OddLength));
+    #[cfg(feature = "alloc")]
+    assert_eq!(
+        "0xABCD".parse::<HexBuf<4, true>>().unwrap().decode(),
+        [0xab, 0xcd]
+    );
+}
+
 #[test]
 #[cfg(feature = "alloc")]
 fn encode_lower() {
@@ -159,6 +281,1114 @@ fn check() {
     }
 }
 
+#[test]
+fn backends() {
+    use const_hex::Backend;
+
+    let detected = const_hex::backend();
+    assert!(detected.is_available());
+    assert!(Backend::Generic.is_available());
+
+    for backend in [
+        Backend::Generic,
+        Backend::Sse2,
+        Backend::Ssse3,
+        Backend::Avx2,
+        Backend::Neon,
+        Backend::Simd128,
+        Backend::PortableSimd,
+    ] {
+        let previous = const_hex::backend();
+        if !const_hex::force_backend(backend) {
+            // Only backends that are not available are skipped.
+            assert!(!backend.is_available(), "{backend}");
+            assert_eq!(const_hex::backend(), previous);
+            continue;
+        }
+        assert_eq!(const_hex::backend(), backend);
+
+        let mut buf = [0u8; 512];
+        const_hex::encode_to_slice(ALL, &mut buf).unwrap();
+        assert_eq!(buf, ALL_LOWER.as_bytes(), "{backend}");
+        assert!(const_hex::check_raw(ALL_UPPER), "{backend}");
+        assert_eq!(const_hex::decode_to_array(ALL_UPPER), Ok(ALL), "{backend}");
+        utf16_roundtrip(backend);
+
+        fixed_size::<20>(backend);
+        fixed_size::<32>(backend);
+        fixed_size::<64>(backend);
+    }
+
+    const_hex::reset_backend();
+    assert_eq!(const_hex::backend(), detected);
+}
+
+/// Checks the UTF-16 functions against the byte ones, with non-ASCII code units at every position.
+fn utf16_roundtrip(backend: const_hex::Backend) {
+    let mut utf16 = [0u16; 512];
+    const_hex::encode_to_utf16(ALL, &mut utf16).unwrap();
+    assert!(
+        utf16.iter().copied().eq(ALL_LOWER.bytes().map(u16::from)),
+        "{backend}"
+    );
+    const_hex::encode_to_utf16_upper(ALL, &mut utf16).unwrap();
+    assert!(
+        utf16.iter().copied().eq(ALL_UPPER.bytes().map(u16::from)),
+        "{backend}"
+    );
+
+    let mut bytes = [0u8; 256];
+    const_hex::decode_utf16_to_slice(utf16, &mut bytes).unwrap();
+    assert_eq!(bytes, ALL, "{backend}");
+    assert_eq!(const_hex::check_utf16(utf16), Ok(()), "{backend}");
+
+    // The low bytes of these are valid hex digits, so they must not be truncated.
+    for (unit, c) in [(0x0161, 'š'), (0xff10, '０'), (0x8030, '耰')] {
+        for index in 0..utf16.len() {
+            let mut invalid = utf16;
+            invalid[index] = unit;
+            let error = Err(const_hex::FromHexError::InvalidHexCharacter { c, index });
+            assert_eq!(const_hex::check_utf16(invalid), error, "{backend}");
+            let result = const_hex::decode_utf16_to_slice(invalid, &mut bytes);
+            assert_eq!(result, error, "{backend}");
+        }
+    }
+}
+
+/// Checks the fixed-size fast paths against the slice functions.
+fn fixed_size<const N: usize>(backend: const_hex::Backend) {
+    let mut bytes = [0u8; N];
+    bytes.copy_from_slice(&ALL[256 - N..]);
+
+    let mut expected = vec![0; N * 2];
+    const_hex::encode_to_slice(bytes, &mut expected).unwrap();
+    let mut buffer = Buffer::<N, true>::new();
+    assert_eq!(
+        &buffer.format(&bytes).as_bytes()[2..],
+        expected,
+        "{backend}"
+    );
+    const_hex::encode_to_slice_upper(bytes, &mut expected).unwrap();
+    assert_eq!(
+        &buffer.format_upper(&bytes).as_bytes()[2..],
+        expected,
+        "{backend}"
+    );
+
+    assert_eq!(
+        const_hex::decode_to_array(buffer.as_str()),
+        Ok(bytes),
+        "{backend}"
+    );
+    let mut hex = buffer.as_str().to_owned().into_bytes();
+    for i in 2..hex.len() {
+        let c = hex[i];
+        hex[i] = b'g';
+        let mut out = [0u8; N];
+        let expected = const_hex::decode_to_slice(&hex, &mut out).unwrap_err();
+        assert_eq!(
+            const_hex::decode_to_array::<_, N>(&hex),
+            Err(expected),
+            "{backend}"
+        );
+        hex[i] = c;
+    }
+}
+
+#[test]
+fn decode_error_index() {
+    let mut out = [0u8; 2];
+    // `check` counts the prefix, the `decode` functions do not.
+    assert_eq!(
+        const_hex::check("0x00zz"),
+        Err(const_hex::FromHexError::InvalidHexCharacter { c: 'z', index: 4 })
+    );
+    let expected = const_hex::FromHexError::InvalidHexCharacter { c: 'z', index: 2 };
+    assert_eq!(
+        const_hex::decode_to_slice("0x00zz", &mut out),
+        Err(expected)
+    );
+    assert_eq!(const_hex::decode_to_array::<_, 2>("0x00zz"), Err(expected));
+    #[cfg(feature = "alloc")]
+    assert_eq!(const_hex::decode("0x00zz"), Err(expected));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn decode_error_details() {
+    let input = "0x0011\n2233\n44zz";
+    let mut out = [0u8; 7];
+    let err = const_hex::decode_to_slice_detailed(input, &mut out).unwrap_err();
+    assert_eq!(
+        err.error(),
+        const_hex::FromHexError::InvalidHexCharacter { c: '\n', index: 6 }
+    );
+    assert_eq!((err.line(), err.column()), (Some(1), Some(7)));
+
+    let input = input.replace('\n', "");
+    let mut out = [0u8; 6];
+    let err = const_hex::decode_to_slice_detailed(&input, &mut out).unwrap_err();
+    assert_eq!((err.line(), err.column()), (Some(1), Some(13)));
+    assert_eq!(
+        err.snippet(&input).to_string(),
+        "1 | 0x0011223344zz\n  |             ^\n"
+    );
+
+    let err = const_hex::decode_to_slice_detailed("0x0011", &mut out).unwrap_err();
+    assert_eq!((err.expected_len(), err.actual_len()), (Some(12), 4));
+    assert_eq!(err.line(), None);
+
+    let io_err = std::io::Error::from(err);
+    assert_eq!(io_err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn diff() {
+    let left: Vec<u8> = (0..40).collect();
+    let mut right = left[..20].to_vec();
+    right[3] = 0xab;
+    assert_eq!(
+        const_hex::diff(&left, &right).to_string(),
+        "\
+21 differing bytes, left: 40 bytes, right: 20 bytes
+00000000  00 01 02 03 04 05 06 07 08 09 0a 0b 0c 0d 0e 0f | 00 01 02 ab 04 05 06 07 08 09 0a 0b 0c 0d 0e 0f
+                   ^^                                                ^^
+00000010  10 11 12 13 14 15 16 17 18 19 1a 1b 1c 1d 1e 1f | 10 11 12 13
+                      ^^ ^^ ^^ ^^ ^^ ^^ ^^ ^^ ^^ ^^ ^^ ^^               ^^ ^^ ^^ ^^ ^^ ^^ ^^ ^^ ^^ ^^ ^^ ^^
+00000020  20 21 22 23 24 25 26 27                         |
+          ^^ ^^ ^^ ^^ ^^ ^^ ^^ ^^                           ^^ ^^ ^^ ^^ ^^ ^^ ^^ ^^
+"
+    );
+    assert_eq!(
+        format!("{:X}", const_hex::diff([0xab; 64], [0xab; 64])),
+        "0 differing bytes, left: 64 bytes, right: 64 bytes\n*        (4 equal rows)\n"
+    );
+    assert_eq!(
+        format!("{:X}", const_hex::diff([0xab, 0xcd], [0xab, 0xce])),
+        "\
+1 differing byte, left: 2 bytes, right: 2 bytes
+00000000  AB CD                                           | AB CE
+             ^^                                                ^^
+"
+    );
+}
+
+#[test]
+#[should_panic(expected = "assertion `left == right` failed: bytes differ\n1 differing byte")]
+fn assert_hex_eq() {
+    const_hex::assert_hex_eq!(b"\x00\x01", [0u8, 1]);
+    const_hex::assert_hex_eq!([0u8, 1], [0u8, 2], "bytes {}", "differ");
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn image() {
+    use const_hex::image::{Image, InsertError};
+
+    let mut image = Image::new();
+    image.insert(0x20, &[3]).unwrap();
+    image.insert(0x10, &[1]).unwrap();
+    image.insert(0x11, &[2]).unwrap();
+    image.insert(0x1f, &[0]).unwrap();
+    assert_eq!(image.len(), 4);
+    assert_eq!(image.segments().len(), 2);
+    assert_eq!(image.segments()[0].data, [1, 2]);
+    assert_eq!(image.segments()[1].address, 0x1f);
+    assert_eq!(image.get(0x11), Some(2));
+    assert_eq!(image.get(0x12), None);
+
+    assert_eq!(
+        image.insert(0x0f, &[0, 0]),
+        Err(InsertError::Overlap { address: 0x10 })
+    );
+    assert_eq!(
+        image.insert(0x20, &[0]),
+        Err(InsertError::Overlap { address: 0x20 })
+    );
+    assert_eq!(
+        image.insert(u32::MAX, &[0, 0]),
+        Err(InsertError::AddressOverflow)
+    );
+    image.insert(u32::MAX, &[0xff]).unwrap();
+    assert_eq!(image.segments().last().unwrap().end(), 1 << 32);
+
+    let image = Image::from_bytes(4, &[1]).unwrap();
+    assert_eq!(image.to_contiguous(0), Some((4, vec![1])));
+    assert_eq!(Image::new().to_contiguous(0), None);
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn ihex_roundtrip() {
+    use const_hex::ihex;
+    use const_hex::image::Image;
+
+    let mut image = Image::new();
+    // Crosses a 64 KiB boundary.
+    image.insert(0xfff8, &(0..32).collect::<Vec<u8>>()).unwrap();
+    // After a gap.
+    image.insert(0x0002_0000, &[0xaa; 300]).unwrap();
+    image.set_start_address(Some(0x0002_0000));
+
+    for record_len in [1, 16, 32, 255] {
+        let hex = ihex::encode(&image, record_len);
+        assert_eq!(ihex::decode(&hex), Ok(image.clone()), "{record_len}");
+        for line in hex.lines() {
+            let record = ihex::Record::parse(line).unwrap();
+            assert_eq!(record.to_string(), line);
+            if let ihex::Record::Data { data, .. } = record {
+                assert!(data.len() <= record_len as usize);
+            }
+        }
+    }
+
+    let hex = ihex::encode(&image, 16);
+    assert_eq!(
+        hex.lines().take(4).collect::<Vec<_>>(),
+        [
+            ":08FFF8000001020304050607E5",
+            ":020000040001F9",
+            ":1000000008090A0B0C0D0E0F1011121314151617F8",
+            ":0800100018191A1B1C1D1E1F0C",
+        ]
+    );
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn ihex_segment_address() {
+    use const_hex::ihex;
+
+    let hex = "\
+:020000021000EC
+:0200100001FFEE
+:0400000310002000C9
+:00000001FF
+";
+    let image = ihex::decode(hex).unwrap();
+    assert_eq!(image.segments()[0].address, 0x10010);
+    assert_eq!(image.segments()[0].data, [0x01, 0xff]);
+    assert_eq!(image.start_address(), Some(0x10000 + 0x2000));
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn ihex_errors() {
+    use const_hex::ihex::{self, ErrorKind, Record};
+    use const_hex::image::InsertError;
+    use const_hex::FromHexError;
+
+    let err = |s: &str| {
+        let e = ihex::decode(s).unwrap_err();
+        (e.line(), e.kind().clone())
+    };
+
+    assert_eq!(
+        Record::parse("0300300002337A1E"),
+        Err(ErrorKind::MissingStartCode)
+    );
+    assert_eq!(Record::parse(":00000001"), Err(ErrorKind::RecordTooShort));
+    assert_eq!(
+        Record::parse(":0300300002337A1E00"),
+        Err(ErrorKind::LengthMismatch {
+            expected: 17,
+            actual: 19
+        })
+    );
+    assert_eq!(
+        Record::parse(":0300300002X37A1E"),
+        Err(ErrorKind::InvalidHex(FromHexError::InvalidHexCharacter {
+            c: 'X',
+            index: 11
+        }))
+    );
+    assert_eq!(
+        Record::parse(":00000006FA"),
+        Err(ErrorKind::UnsupportedRecordType(6))
+    );
+    assert_eq!(
+        Record::parse(":0100000100FE"),
+        Err(ErrorKind::InvalidRecordLength {
+            record_type: 1,
+            len: 1
+        })
+    );
+
+    assert_eq!(err(":0300300002337A1E\n"), (1, ErrorKind::MissingEndOfFile));
+    assert_eq!(
+        err("\n:00000001FF\n:0300300002337A1E\n"),
+        (3, ErrorKind::RecordAfterEndOfFile)
+    );
+    assert_eq!(
+        err(":0300300002337A1E\n:0100310000CE\n:00000001FF\n"),
+        (2, ErrorKind::Image(InsertError::Overlap { address: 0x31 }))
+    );
+    assert_eq!(
+        err(":00000001FF\n:00000001FE").1,
+        ErrorKind::ChecksumMismatch {
+            expected: 0xff,
+            actual: 0xfe
+        }
+    );
+    assert_eq!(
+        ihex::decode(":00000001FE").unwrap_err().to_string(),
+        "line 1: checksum mismatch: expected FF, got FE"
+    );
+    // Records that cannot be formatted are an error, not a panic.
+    use core::fmt::Write;
+    let record = Record::Data {
+        offset: 0,
+        data: vec![0; 256],
+    };
+    assert!(write!(String::new(), "{record}").is_err());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn ihex_io() {
+    use const_hex::ihex;
+    use const_hex::image::Image;
+
+    let image = Image::from_bytes(0x1234_5678, &[0x55; 100]).unwrap();
+    let mut out = Vec::new();
+    ihex::write(&mut out, &image, 24).unwrap();
+    assert_eq!(out, ihex::encode(&image, 24).into_bytes());
+    assert_eq!(ihex::read(&out[..]).unwrap(), image);
+
+    let e = ihex::read(&b":00000001FF\n\n:00000001FF\n"[..]).unwrap_err();
+    assert_eq!(e.kind(), std::io::ErrorKind::InvalidData);
+    assert_eq!(e.to_string(), "line 3: record after end of file record");
+
+    let mut reader = ihex::Reader::new(&b"\r\n:00000001FF\r\n"[..]);
+    assert_eq!(reader.next().unwrap().unwrap(), ihex::Record::EndOfFile);
+    assert_eq!(reader.line(), 2);
+    assert!(reader.next().is_none());
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn srec_roundtrip() {
+    use const_hex::image::Image;
+    use const_hex::srec::{self, AddressSize, Record};
+
+    for (address, size) in [
+        (0x10, AddressSize::Bits16),
+        (0x01_0000, AddressSize::Bits24),
+        (0x0100_0000, AddressSize::Bits32),
+    ] {
+        let mut image = Image::new();
+        image
+            .insert(address, &(0..=255).collect::<Vec<u8>>())
+            .unwrap();
+        image.insert(address + 0x1000, &[0x11; 3]).unwrap();
+        image.set_start_address(Some(address));
+
+        for record_len in [1, 16, 255] {
+            let s = srec::encode(&image, record_len);
+            assert_eq!(
+                srec::decode(&s),
+                Ok(image.clone()),
+                "{address:#x} {record_len}"
+            );
+            for line in s.lines() {
+                let record = Record::parse(line).unwrap();
+                assert_eq!(record.to_string(), line);
+                if let Record::Data {
+                    size: record_size,
+                    data,
+                    ..
+                } = record
+                {
+                    assert_eq!(record_size, size);
+                    assert!(data.len() <= record_len as usize);
+                    assert!(data.len() + size.bytes() < 255);
+                }
+            }
+        }
+    }
+
+    assert_eq!(
+        Record::parse("S00600004844521B"),
+        Ok(Record::Header(b"HDR".to_vec()))
+    );
+    assert_eq!(Record::parse("S5030002FA"), Ok(Record::Count(2)));
+    assert_eq!(
+        Record::parse("S70500000000FA"),
+        Ok(Record::Start {
+            size: AddressSize::Bits32,
+            address: 0
+        })
+    );
+    assert_eq!(srec::decode("S9030000FC").unwrap().start_address(), None);
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn srec_errors() {
+    use const_hex::image::InsertError;
+    use const_hex::srec::{self, ErrorKind, Record};
+    use const_hex::FromHexError;
+
+    let err = |s: &str| {
+        let e = srec::decode(s).unwrap_err();
+        (e.line(), e.kind().clone())
+    };
+
+    assert_eq!(Record::parse(":030000FC"), Err(ErrorKind::MissingStartCode));
+    assert_eq!(
+        Record::parse("S4030000FC"),
+        Err(ErrorKind::UnsupportedRecordType('4'))
+    );
+    assert_eq!(
+        Record::parse("SX030000FC"),
+        Err(ErrorKind::UnsupportedRecordType('X'))
+    );
+    assert_eq!(Record::parse("S9"), Err(ErrorKind::RecordTooShort));
+    assert_eq!(
+        Record::parse("S9030000FC00"),
+        Err(ErrorKind::LengthMismatch {
+            expected: 10,
+            actual: 12
+        })
+    );
+    assert_eq!(
+        Record::parse("S90300g0FC"),
+        Err(ErrorKind::InvalidHex(FromHexError::InvalidHexCharacter {
+            c: 'g',
+            index: 6
+        }))
+    );
+    assert_eq!(
+        Record::parse("S9030000FD"),
+        Err(ErrorKind::ChecksumMismatch {
+            expected: 0xfc,
+            actual: 0xfd
+        })
+    );
+    assert_eq!(
+        Record::parse("S3030000FC"),
+        Err(ErrorKind::InvalidRecordLength {
+            record_type: 3,
+            len: 3
+        })
+    );
+    assert_eq!(
+        Record::parse("S904000000FB"),
+        Err(ErrorKind::InvalidRecordLength {
+            record_type: 9,
+            len: 4
+        })
+    );
+
+    assert_eq!(err("S0030000FC\n"), (1, ErrorKind::MissingTermination));
+    assert_eq!(
+        err("S9030000FC\n\nS0030000FC\n"),
+        (3, ErrorKind::RecordAfterTermination)
+    );
+    assert_eq!(
+        err("S107003001020304BE\nS107003001020304BE\nS9030000FC\n"),
+        (2, ErrorKind::Image(InsertError::Overlap { address: 0x30 }))
+    );
+    assert_eq!(
+        srec::decode("S5030001FB").unwrap_err().to_string(),
+        "line 1: record count mismatch: expected 0, got 1"
+    );
+    // Records that cannot be formatted are an error, not a panic.
+    use core::fmt::Write;
+    use srec::AddressSize;
+    for record in [
+        Record::Count(0x100_0000),
+        Record::Data {
+            size: AddressSize::Bits16,
+            address: 0x1_0000,
+            data: vec![],
+        },
+        Record::Data {
+            size: AddressSize::Bits32,
+            address: 0,
+            data: vec![0; 251],
+        },
+        Record::Header(vec![0; 253]),
+        Record::Start {
+            size: AddressSize::Bits24,
+            address: 0x100_0000,
+        },
+    ] {
+        assert!(write!(String::new(), "{record}").is_err(), "{record:?}");
+    }
+    let record = Record::Data {
+        size: AddressSize::Bits32,
+        address: 0,
+        data: vec![0; 250],
+    };
+    assert_eq!(record.to_string().len(), 2 + 255 * 2 + 2);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn srec_io() {
+    use const_hex::image::Image;
+    use const_hex::srec;
+
+    let image = Image::from_bytes(0x0012_3456, &[0x55; 100]).unwrap();
+    let mut out = Vec::new();
+    srec::write(&mut out, &image, 24).unwrap();
+    assert_eq!(out, srec::encode(&image, 24).into_bytes());
+    assert_eq!(srec::read(&out[..]).unwrap(), image);
+
+    let e = srec::read(&b"S0030000FC\r\n"[..]).unwrap_err();
+    assert_eq!(e.kind(), std::io::ErrorKind::InvalidData);
+    assert_eq!(e.to_string(), "line 1: missing termination record");
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn escape() {
+    use const_hex::escape;
+
+    let all = (0..=255).collect::<Vec<u8>>();
+    for data in [&[][..], &[0], &[0xde, 0xad, 0xbe, 0xef], &all] {
+        let escaped = escape::encode(data);
+        assert_eq!(escaped.len(), escape::escaped_len(data.len()));
+        assert_eq!(escape::decode(&escaped).unwrap(), data);
+        assert_eq!(escape::decode(escape::encode_upper(data)).unwrap(), data);
+
+        for per_line in [0, 1, 3, 16] {
+            let list = escape::encode_list(data, per_line);
+            assert_eq!(list.len(), escape::list_len(data.len()));
+            assert_eq!(escape::decode_list(&list).unwrap(), data);
+            let upper = escape::encode_list_upper(data, per_line);
+            assert_eq!(escape::decode_list(&upper).unwrap(), data);
+        }
+
+        let literal = escape::encode_byte_string(data);
+        assert_eq!(literal.len(), escape::byte_string_len(data.len()));
+        assert_eq!(escape::decode_byte_string(&literal).unwrap(), data);
+    }
+
+    assert_eq!(
+        escape::encode_list_upper([0xab; 5], 2),
+        "0xAB, 0xAB,\n0xAB, 0xAB,\n0xAB,"
+    );
+    assert_eq!(
+        escape::decode_list(" 0x1,0XfF , 0x00 ,\n"),
+        Ok(vec![1, 0xff, 0])
+    );
+    assert_eq!(
+        escape::decode_byte_string("b\"ab\\\\\\'\\t\\\n    c\""),
+        Ok(b"ab\\'\tc".to_vec())
+    );
+}
+
+#[test]
+fn escape_to_slice() {
+    use const_hex::{escape, FromHexError};
+
+    let data = [0xde, 0xad, 0xbe, 0xef];
+    let mut buf = [0; escape::escaped_len(4)];
+    assert_eq!(
+        escape::encode_to_str(data, &mut buf).unwrap(),
+        "\\xde\\xad\\xbe\\xef"
+    );
+    let mut out = [0; 4];
+    escape::decode_to_slice(buf, &mut out).unwrap();
+    assert_eq!(out, data);
+    assert_eq!(
+        escape::decode_to_slice(buf, &mut [0; 3]),
+        Err(FromHexError::InvalidStringLength)
+    );
+    assert_eq!(
+        escape::encode_to_str_upper(data, &mut buf).unwrap(),
+        "\\xDE\\xAD\\xBE\\xEF"
+    );
+    escape::decode_to_slice(buf, &mut out).unwrap();
+    assert_eq!(out, data);
+
+    let mut buf = [0; escape::list_len(4)];
+    let list = escape::encode_list_to_str(data, 3, &mut buf).unwrap();
+    assert_eq!(list, "0xde, 0xad, 0xbe,\n0xef,");
+    let mut out = [0; 8];
+    assert_eq!(escape::decode_list_to_slice(buf, &mut out), Ok(4));
+    assert_eq!(out[..4], data);
+    assert_eq!(
+        escape::decode_list_to_slice(buf, &mut [0; 3]),
+        Err(FromHexError::InvalidStringLength)
+    );
+    let list = escape::encode_list_to_str_upper(data, 0, &mut buf).unwrap();
+    assert_eq!(list, "0xDE, 0xAD, 0xBE, 0xEF,");
+    assert_eq!(
+        escape::encode_list_to_str_upper(data, 0, &mut [0; 4]),
+        Err(FromHexError::InvalidStringLength)
+    );
+
+    let mut buf = [0; escape::byte_string_len(4)];
+    escape::encode_byte_string_to_str(data, &mut buf).unwrap();
+    assert_eq!(escape::decode_byte_string_to_slice(buf, &mut out), Ok(4));
+    assert_eq!(out[..4], data);
+    assert_eq!(
+        escape::encode_byte_string_to_str(data, &mut [0; 4]),
+        Err(FromHexError::InvalidStringLength)
+    );
+}
+
+#[test]
+fn escape_errors() {
+    use const_hex::{escape, FromHexError};
+
+    let invalid = |c, index| FromHexError::InvalidHexCharacter { c, index };
+    let mut out = [0; 4];
+
+    assert_eq!(
+        escape::decode_to_slice("\\xdg", &mut out[..1]).unwrap_err(),
+        invalid('g', 3)
+    );
+    assert_eq!(
+        escape::decode_to_slice("\\Xde", &mut out[..1]).unwrap_err(),
+        invalid('X', 1)
+    );
+    assert_eq!(
+        escape::decode_to_slice("xde\\", &mut out[..1]).unwrap_err(),
+        invalid('x', 0)
+    );
+    assert_eq!(
+        escape::decode_to_slice("\\xde\\x", &mut out[..2]).unwrap_err(),
+        FromHexError::InvalidStringLength
+    );
+
+    assert_eq!(
+        escape::decode_list_to_slice("0x1,,0x2", &mut out).unwrap_err(),
+        invalid(',', 4)
+    );
+    assert_eq!(
+        escape::decode_list_to_slice("0x123", &mut out).unwrap_err(),
+        invalid('3', 4)
+    );
+    assert_eq!(
+        escape::decode_list_to_slice("0x1 0x2", &mut out).unwrap_err(),
+        invalid('0', 4)
+    );
+    assert_eq!(
+        escape::decode_list_to_slice("1", &mut out).unwrap_err(),
+        invalid('1', 0)
+    );
+    assert_eq!(
+        escape::decode_list_to_slice("0b1", &mut out).unwrap_err(),
+        invalid('b', 1)
+    );
+    assert_eq!(
+        escape::decode_list_to_slice("0x1, 0x", &mut out).unwrap_err(),
+        FromHexError::InvalidStringLength
+    );
+
+    assert_eq!(
+        escape::decode_byte_string_to_slice("\"\"", &mut out).unwrap_err(),
+        invalid('"', 0)
+    );
+    assert_eq!(
+        escape::decode_byte_string_to_slice("b'a'", &mut out).unwrap_err(),
+        invalid('\'', 1)
+    );
+    assert_eq!(
+        escape::decode_byte_string_to_slice("b\"\\q\"", &mut out).unwrap_err(),
+        invalid('q', 3)
+    );
+    assert_eq!(
+        escape::decode_byte_string_to_slice("b\"\"x", &mut out).unwrap_err(),
+        invalid('x', 3)
+    );
+    assert_eq!(
+        escape::decode_byte_string_to_slice("b\"\u{e9}\"", &mut out).unwrap_err(),
+        invalid('\u{c3}', 2)
+    );
+    assert_eq!(
+        escape::decode_byte_string_to_slice("b\"a\rb\"", &mut out).unwrap_err(),
+        invalid('\r', 3)
+    );
+    assert_eq!(
+        escape::decode_byte_string_to_slice("b\"a\\\r b\"", &mut out).unwrap_err(),
+        invalid('\r', 4)
+    );
+    assert_eq!(
+        escape::decode_byte_string_to_slice("b\"\x01\"", &mut out).unwrap_err(),
+        invalid('\x01', 2)
+    );
+    assert_eq!(
+        escape::decode_byte_string_to_slice("b\"a\r\nb\\\r\n\tc\"", &mut out),
+        Ok(4)
+    );
+    assert_eq!(out, *b"a\nbc");
+    assert_eq!(
+        escape::decode_byte_string_to_slice("b\"abc", &mut out).unwrap_err(),
+        FromHexError::InvalidStringLength
+    );
+    assert_eq!(
+        escape::decode_byte_string_to_slice("b\"abcde\"", &mut out).unwrap_err(),
+        FromHexError::InvalidStringLength
+    );
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn reversed() {
+    use const_hex::FromHexError;
+
+    for len in 0..200 {
+        let data = (0..len).map(|i| (i * 7) as u8).collect::<Vec<u8>>();
+        let mut reversed = data.clone();
+        reversed.reverse();
+
+        let expected = const_hex::encode(&reversed);
+        assert_eq!(const_hex::encode_reversed(&data), expected);
+        assert_eq!(
+            const_hex::encode_upper_reversed(&data),
+            const_hex::encode_upper(&reversed)
+        );
+        assert_eq!(const_hex::display_reversed(&data).to_string(), expected);
+        assert_eq!(
+            format!("{:#X}", const_hex::display_reversed(&data)),
+            const_hex::encode_upper_prefixed(&reversed)
+        );
+        assert_eq!(const_hex::decode_reversed(&expected).unwrap(), data);
+
+        let mut out = vec![0; len * 2];
+        const_hex::encode_reversed_to_slice(&data, &mut out).unwrap();
+        assert_eq!(out, expected.as_bytes());
+        const_hex::encode_reversed_to_slice_upper(&data, &mut out).unwrap();
+        assert_eq!(out, expected.to_ascii_uppercase().as_bytes());
+
+        let mut out = vec![0; len];
+        const_hex::decode_reversed_to_slice(&expected, &mut out).unwrap();
+        assert_eq!(out, data);
+    }
+
+    let txid = "0x4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b";
+    let bytes: [u8; 32] = const_hex::decode_reversed_to_array(txid).unwrap();
+    assert_eq!(bytes[0], 0x3b);
+    let mut buffer = const_hex::Buffer::<32, true>::new();
+    assert_eq!(buffer.format_reversed(&bytes), txid);
+    assert_eq!(
+        *buffer.format_reversed_upper(&bytes),
+        txid.to_ascii_uppercase().replace("0X", "0x")
+    );
+
+    assert_eq!(
+        const_hex::encode_reversed_to_slice([1, 2], &mut [0; 3]),
+        Err(FromHexError::InvalidStringLength)
+    );
+    assert_eq!(
+        const_hex::decode_reversed("0x0g"),
+        Err(FromHexError::InvalidHexCharacter { c: 'g', index: 1 })
+    );
+}
+
+#[test]
+fn utf16() {
+    let hex: Vec<u16> = "0x6b697769".encode_utf16().collect();
+    let mut bytes = [0u8; 4];
+    assert_eq!(const_hex::decode_utf16_to_slice(&hex, &mut bytes), Ok(()));
+    assert_eq!(&bytes, b"kiwi");
+    assert_eq!(const_hex::check_utf16(&hex), Ok(()));
+    assert_eq!(const_hex::check_utf16(&hex[2..]), Ok(()));
+    assert_eq!(const_hex::check_utf16([]), Ok(()));
+
+    assert_eq!(
+        const_hex::decode_utf16_to_slice(&hex[..9], &mut bytes),
+        Err(const_hex::FromHexError::OddLength)
+    );
+    assert_eq!(
+        const_hex::decode_utf16_to_slice(&hex[..8], &mut bytes),
+        Err(const_hex::FromHexError::InvalidStringLength)
+    );
+    assert_eq!(
+        const_hex::encode_to_utf16(b"kiwi", &mut [0; 7]),
+        Err(const_hex::FromHexError::InvalidStringLength)
+    );
+
+    // Surrogate pairs are reported as one character, unpaired surrogates as U+FFFD.
+    let hex: Vec<u16> = "00🥝00".encode_utf16().collect();
+    assert_eq!(
+        const_hex::check_utf16(&hex),
+        Err(const_hex::FromHexError::InvalidHexCharacter {
+            c: '🥝', index: 2
+        })
+    );
+    assert_eq!(
+        const_hex::check_utf16([0x30, 0x30, 0x30, 0xdc00]),
+        Err(const_hex::FromHexError::InvalidHexCharacter {
+            c: char::REPLACEMENT_CHARACTER,
+            index: 3
+        })
+    );
+
+    #[cfg(feature = "alloc")]
+    {
+        let hex: Vec<u16> = "0X6B697769".encode_utf16().collect();
+        assert_eq!(const_hex::decode_utf16(hex), Ok(b"kiwi".to_vec()));
+        assert_eq!(const_hex::decode_utf16([]), Ok(vec![]));
+        // Like `decode`, only `check_utf16` counts the prefix.
+        let invalid = [0x30, 0x78, 0x30, 0xe9];
+        assert_eq!(
+            const_hex::decode_utf16(invalid),
+            Err(const_hex::FromHexError::InvalidHexCharacter { c: 'é', index: 1 })
+        );
+        assert_eq!(
+            const_hex::check_utf16(invalid),
+            Err(const_hex::FromHexError::InvalidHexCharacter { c: 'é', index: 3 })
+        );
+        let hex: Vec<u16> = "0xzz".encode_utf16().collect();
+        assert_eq!(const_hex::decode_utf16(hex), const_hex::decode("0xzz"));
+    }
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn nibbles() {
+    use const_hex::nibbles::{self, Error};
+
+    let all = (0..=255).collect::<Vec<u8>>();
+    let unpacked = nibbles::unpack(&all);
+    for (i, pair) in unpacked.chunks(2).enumerate() {
+        assert_eq!(pair, [i as u8 >> 4, i as u8 & 0xf]);
+    }
+    assert_eq!(nibbles::pack(&unpacked).unwrap(), all);
+
+    let mut invalid = unpacked.clone();
+    invalid[100] = 0x10;
+    assert_eq!(
+        nibbles::pack(&invalid),
+        Err(Error::InvalidNibble {
+            value: 0x10,
+            index: 100
+        })
+    );
+
+    for len in 0..40 {
+        let path = &unpacked[unpacked.len() - len..];
+        for leaf in [false, true] {
+            let encoded = nibbles::encode_hex_prefix(path, leaf).unwrap();
+            assert_eq!(encoded.len(), nibbles::hex_prefix_len(len));
+            assert_eq!(encoded[0] >> 4, (leaf as u8) << 1 | (len % 2) as u8);
+            assert_eq!(
+                nibbles::decode_hex_prefix(&encoded).unwrap(),
+                (path.to_vec(), leaf)
+            );
+        }
+    }
+
+    assert_eq!(
+        nibbles::encode_hex_prefix([1, 2, 0x10], false),
+        Err(Error::InvalidNibble {
+            value: 0x10,
+            index: 2
+        })
+    );
+    assert_eq!(
+        nibbles::encode_hex_prefix([0x10, 2, 3], false),
+        Err(Error::InvalidNibble {
+            value: 0x10,
+            index: 0
+        })
+    );
+    assert_eq!(nibbles::decode_hex_prefix([]), Err(Error::Empty));
+    assert_eq!(
+        nibbles::decode_hex_prefix([0x21, 0x00]),
+        Err(Error::InvalidPadding)
+    );
+    assert_eq!(
+        nibbles::decode_hex_prefix([0xf0]),
+        Err(Error::InvalidFlag(0xf))
+    );
+    assert_eq!(
+        Error::InvalidNibble {
+            value: 0x10,
+            index: 2
+        }
+        .to_string(),
+        "invalid nibble 0x10 at position 2"
+    );
+
+    assert_eq!(nibbles::display(&unpacked[..5]).to_string(), "00010");
+    assert_eq!(format!("{:#X}", nibbles::display([0xa, 0xfb])), "0xAB");
+    assert_eq!(
+        nibbles::display(&unpacked).to_string(),
+        const_hex::encode(&all)
+    );
+}
+
+#[test]
+fn nibbles_to_slice() {
+    use const_hex::nibbles::{self, Error};
+
+    let mut out = [0; 4];
+    nibbles::unpack_to_slice([0x12, 0x34], &mut out).unwrap();
+    assert_eq!(out, [1, 2, 3, 4]);
+    assert_eq!(
+        nibbles::unpack_to_slice([0x12], &mut out),
+        Err(Error::InvalidLength)
+    );
+
+    let mut bytes = [0; 2];
+    nibbles::pack_to_slice(out, &mut bytes).unwrap();
+    assert_eq!(bytes, [0x12, 0x34]);
+    assert_eq!(
+        nibbles::pack_to_slice(&out[1..], &mut bytes),
+        Err(Error::OddLength)
+    );
+    assert_eq!(
+        nibbles::pack_to_slice(&out[2..], &mut bytes),
+        Err(Error::InvalidLength)
+    );
+
+    let mut encoded = [0; nibbles::hex_prefix_len(3)];
+    nibbles::encode_hex_prefix_to_slice(&out[1..], true, &mut encoded).unwrap();
+    assert_eq!(encoded, [0x32, 0x34]);
+    let mut decoded = [0; 8];
+    assert_eq!(
+        nibbles::decode_hex_prefix_to_slice(encoded, &mut decoded),
+        Ok((3, true))
+    );
+    assert_eq!(decoded[..3], [2, 3, 4]);
+    assert_eq!(
+        nibbles::decode_hex_prefix_to_slice(encoded, &mut decoded[..2]),
+        Err(Error::InvalidLength)
+    );
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn batch() {
+    use const_hex::batch;
+
+    for len in [0, 1, 2, 3, 7, 8, 9, 15, 16, 17, 31, 32, 33, 100, 1000] {
+        let items = (0..len)
+            .map(|i| core::array::from_fn(|j| (i * 7 + j * 13) as u8))
+            .collect::<Vec<[u8; 20]>>();
+
+        let encoded = batch::encode(&items);
+        assert_eq!(encoded.len(), len);
+        assert_eq!(encoded.as_str(), const_hex::encode(items.concat()));
+        for (i, hex) in encoded.iter().enumerate() {
+            assert_eq!(hex, const_hex::encode(items[i]));
+        }
+        let upper = batch::encode_upper(&items);
+        assert_eq!(upper.iter().rev().count(), len);
+        assert_eq!(upper.as_str(), const_hex::encode_upper(items.concat()));
+
+        let mut output = vec![0u8; len * 40];
+        batch::encode_to_slice(&items, &mut output).unwrap();
+        assert_eq!(output, encoded.as_str().as_bytes());
+
+        let buffers: Vec<Buffer<20, true>> = batch::encode_buffers(&items);
+        let buffers_upper: Vec<Buffer<20>> = batch::encode_buffers_upper(&items);
+        for (i, item) in items.iter().enumerate() {
+            assert_eq!(buffers[i].as_str(), const_hex::encode_prefixed(item));
+            assert_eq!(buffers_upper[i].as_str(), const_hex::encode_upper(item));
+        }
+
+        let decoded: Vec<[u8; 20]> = batch::decode(&encoded.iter().collect::<Vec<_>>()).unwrap();
+        assert_eq!(decoded, items);
+        let decoded: Vec<[u8; 20]> = batch::decode(
+            &buffers
+                .iter()
+                .map(|buffer| buffer.as_str())
+                .collect::<Vec<_>>(),
+        )
+        .unwrap();
+        assert_eq!(decoded, items);
+
+        let slices = items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| &item[..i % 21])
+            .collect::<Vec<_>>();
+        let iter = batch::encode_iter(&slices);
+        assert_eq!(iter.len(), len);
+        for (hex, slice) in iter.iter().zip(&slices) {
+            assert_eq!(hex, const_hex::encode(slice));
+        }
+        let iter = batch::encode_iter_upper(&slices);
+        assert_eq!(iter.as_str(), const_hex::encode_upper(slices.concat()));
+    }
+
+    // Items larger than the staging area.
+    let items = vec![[0xab; 300]; 3];
+    let encoded = batch::encode_buffers::<300, false>(&items);
+    assert!(encoded
+        .iter()
+        .all(|buffer| buffer.as_str() == "ab".repeat(300)));
+    let decoded: Vec<[u8; 300]> =
+        batch::decode(&encoded.iter().map(|b| b.as_str()).collect::<Vec<_>>()).unwrap();
+    assert_eq!(decoded, items);
+
+    assert_eq!(
+        batch::encode::<0>(&[[], []]).iter().collect::<Vec<_>>(),
+        ["", ""]
+    );
+    assert_eq!(batch::decode::<0, _>(&["", "0x"]), Ok(vec![[], []]));
+    assert!(batch::encode_iter(Vec::<Vec<u8>>::new()).is_empty());
+}
+
+#[test]
+fn batch_errors() {
+    use const_hex::{batch, FromHexError};
+
+    let mut inputs = [*b"0xdeadbeef"; 100];
+    let mut output = [[0u8; 4]; 100];
+    batch::decode_to_slice(&inputs, &mut output).unwrap();
+    assert!(output.iter().all(|item| *item == [0xde, 0xad, 0xbe, 0xef]));
+
+    for item in [0, 1, 63, 64, 65, 99] {
+        let mut inputs = inputs;
+        inputs[item][7] = b'g';
+        let err = batch::decode_to_slice(&inputs, &mut output).unwrap_err();
+        assert_eq!(err.item(), item);
+        assert_eq!(
+            *err.error(),
+            FromHexError::InvalidHexCharacter { c: 'g', index: 5 }
+        );
+    }
+
+    inputs[42][1] = b'0';
+    let err = batch::decode_to_slice(&inputs, &mut output).unwrap_err();
+    assert_eq!(
+        (err.item(), *err.error()),
+        (42, FromHexError::InvalidStringLength)
+    );
+
+    let err = batch::decode_to_slice(&["abc"], &mut [[0u8; 2]]).unwrap_err();
+    assert_eq!((err.item(), *err.error()), (0, FromHexError::OddLength));
+    let err = batch::decode_to_slice(&["abcd"], &mut [[0u8; 2]; 2]).unwrap_err();
+    assert_eq!(
+        (err.item(), *err.error()),
+        (0, FromHexError::InvalidStringLength)
+    );
+    let err = batch::decode_to_slice(&["abcd", "abcdef"], &mut [[0u8; 2]; 2]).unwrap_err();
+    assert_eq!(err.to_string(), "item 1: invalid string length");
+
+    let mut output = [0u8; 7];
+    assert_eq!(
+        batch::encode_to_slice(&[[1, 2], [3, 4]], &mut output),
+        Err(FromHexError::InvalidStringLength)
+    );
+    let mut output = [0u8; 8];
+    batch::encode_to_slice_upper(&[[0xab, 0xcd], [0xef, 0x01]], &mut output).unwrap();
+    assert_eq!(&output, b"ABCDEF01");
+}
+
+#[test]
+#[cfg(feature = "arbitrary")]
+fn buffer_arbitrary() {
+    use arbitrary::{Arbitrary, Unstructured};
+
+    let data = [0xde, 0xad, 0xbe, 0xef, 0x01, 0xde, 0xad, 0xbe, 0xef, 0x00];
+    let mut u = Unstructured::new(&data);
+    let upper = const_hex::Buffer::<4, true>::arbitrary(&mut u).unwrap();
+    assert_eq!(upper.as_str(), "0xDEADBEEF");
+    let lower = const_hex::Buffer::<4>::arbitrary(&mut u).unwrap();
+    assert_eq!(lower.as_str(), "deadbeef");
+}
+
 #[test]
 #[cfg(all(feature = "serde", feature = "alloc"))]
 fn serde() {
@@ -399,3 +1629,997 @@ fn assert_upper(s: &str) {
     assert_eq!(ALL_UPPER, expected);
     assert_eq!(s, expected);
 }
+
+#[test]
+#[cfg(feature = "rayon")]
+fn par() {
+    use const_hex::FromHexError;
+
+    const CHUNK: usize = 1 << 16;
+    for len in [0, 1, 31, CHUNK - 1, CHUNK, CHUNK + 1, 5 * CHUNK + 17] {
+        let data = (0..len)
+            .map(|i| (i * 31 + i / 256) as u8)
+            .collect::<Vec<_>>();
+        let lower = const_hex::encode(&data);
+        let upper = const_hex::encode_upper(&data);
+        assert_eq!(const_hex::par_encode(&data), lower);
+        assert_eq!(const_hex::par_encode_upper(&data), upper);
+
+        let mut output = vec![0u8; len * 2];
+        const_hex::par_encode_to_slice(&data, &mut output).unwrap();
+        assert_eq!(output, lower.as_bytes());
+        const_hex::par_encode_to_slice_upper(&data, &mut output).unwrap();
+        assert_eq!(output, upper.as_bytes());
+
+        assert_eq!(const_hex::par_decode(&lower), Ok(data.clone()));
+        assert_eq!(
+            const_hex::par_decode(format!("0x{upper}")),
+            Ok(data.clone())
+        );
+        let mut output = vec![0u8; len];
+        const_hex::par_decode_to_slice(&upper, &mut output).unwrap();
+        assert_eq!(output, data);
+        assert_eq!(const_hex::par_check(&lower), Ok(()));
+
+        // The first invalid character is reported, even if later chunks are invalid too.
+        for index in [0, 1, CHUNK * 2 - 1, CHUNK * 2, CHUNK * 4 + 3] {
+            if index >= len * 2 {
+                continue;
+            }
+            for prefix in ["", "0x"] {
+                let mut invalid = format!("{prefix}{lower}").into_bytes();
+                invalid[prefix.len() + index] = b'g';
+                if index + 1 < len * 2 {
+                    *invalid.last_mut().unwrap() = b'z';
+                }
+                let expected = FromHexError::InvalidHexCharacter { c: 'g', index };
+                assert_eq!(const_hex::decode(&invalid), Err(expected));
+                assert_eq!(const_hex::par_decode(&invalid), Err(expected));
+                let expected_check = FromHexError::InvalidHexCharacter {
+                    c: 'g',
+                    index: prefix.len() + index,
+                };
+                assert_eq!(const_hex::check(&invalid), Err(expected_check));
+                assert_eq!(const_hex::par_check(&invalid), Err(expected_check));
+                let mut output = vec![0u8; len];
+                assert_eq!(
+                    const_hex::par_decode_to_slice(&invalid, &mut output),
+                    Err(expected)
+                );
+            }
+        }
+    }
+
+    assert_eq!(const_hex::par_decode("abc"), Err(FromHexError::OddLength));
+    assert_eq!(const_hex::par_check("abc"), Err(FromHexError::OddLength));
+    assert_eq!(
+        const_hex::par_decode_to_slice("abcd", &mut [0u8; 1]),
+        Err(FromHexError::InvalidStringLength)
+    );
+    assert_eq!(
+        const_hex::par_encode_to_slice("ab", &mut [0u8; 3]),
+        Err(FromHexError::InvalidStringLength)
+    );
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn engine() {
+    use const_hex::engine::{self, Case, Config, Engine, PrefixMode};
+    use const_hex::FromHexError;
+
+    for len in [0, 1, 2, 15, 16, 17, 63, 64, 65, 200] {
+        let data = (0..len).map(|i| (i * 37) as u8).collect::<Vec<u8>>();
+        let lower = const_hex::encode(&data);
+        let upper = const_hex::encode_upper(&data);
+
+        assert_eq!(engine::STANDARD.encode(&data), lower);
+        assert_eq!(engine::STANDARD_UPPER.encode(&data), upper);
+        assert_eq!(
+            engine::PREFIXED.encode(&data),
+            const_hex::encode_prefixed(&data)
+        );
+        assert_eq!(
+            engine::PREFIXED_UPPER.encode(&data),
+            const_hex::encode_upper_prefixed(&data)
+        );
+        assert_eq!(engine::STANDARD.decode(&upper), Ok(data.clone()));
+        assert_eq!(
+            engine::STANDARD.decode(format!("0x{lower}")),
+            Ok(data.clone())
+        );
+
+        for separator in [None, Some(b':'), Some(b' ')] {
+            for case in [Case::Lower, Case::Upper] {
+                for encode_prefix in [false, true] {
+                    let config = Config::new()
+                        .with_case(case)
+                        .with_encode_prefix(encode_prefix)
+                        .with_separator(separator)
+                        .with_strict(true);
+                    let engine = Engine::new(config);
+
+                    let encoded = engine.encode(&data);
+                    assert_eq!(encoded.len(), engine.encoded_len(len));
+                    let mut expected = if case == Case::Upper { &upper } else { &lower }
+                        .as_bytes()
+                        .chunks(2)
+                        .map(|pair| std::str::from_utf8(pair).unwrap())
+                        .collect::<Vec<_>>()
+                        .join(
+                            &separator
+                                .map(|c| (c as char).to_string())
+                                .unwrap_or_default(),
+                        );
+                    if encode_prefix {
+                        expected.insert_str(0, "0x");
+                    }
+                    assert_eq!(encoded, expected);
+
+                    let mut output = vec![0; encoded.len()];
+                    assert_eq!(&*engine.encode_to_str(&data, &mut output).unwrap(), encoded);
+
+                    assert_eq!(engine.decoded_len(&encoded), Ok(len));
+                    assert_eq!(engine.decode(&encoded), Ok(data.clone()));
+                    assert_eq!(engine.check(&encoded), Ok(()));
+                    let mut output = vec![0; len];
+                    engine.decode_to_slice(&encoded, &mut output).unwrap();
+                    assert_eq!(output, data);
+
+                    // The first invalid character is reported, wherever it is.
+                    let start = encode_prefix as usize * 2;
+                    for index in (start..encoded.len()).step_by(7) {
+                        let mut invalid = encoded.clone().into_bytes();
+                        invalid[index] = b'g';
+                        if index + 1 < invalid.len() {
+                            *invalid.last_mut().unwrap() = b'z';
+                        }
+                        // Like the free functions, only `check` counts the prefix.
+                        let expected = FromHexError::InvalidHexCharacter { c: 'g', index };
+                        assert_eq!(engine.check(&invalid), Err(expected));
+                        let expected = FromHexError::InvalidHexCharacter {
+                            c: 'g',
+                            index: index - start,
+                        };
+                        assert_eq!(engine.decode(&invalid), Err(expected));
+                    }
+
+                    // Strict engines reject the other case.
+                    let other = if case == Case::Upper {
+                        encoded.to_lowercase()
+                    } else {
+                        encoded.to_uppercase()
+                    };
+                    let other = other.replacen("0X", "0x", 1);
+                    match other
+                        .bytes()
+                        .position(|c| c.is_ascii_alphabetic() && c != b'x')
+                    {
+                        Some(index) => assert_eq!(
+                            engine.check(&other),
+                            Err(FromHexError::InvalidHexCharacter {
+                                c: other.as_bytes()[index] as char,
+                                index
+                            })
+                        ),
+                        None => assert_eq!(engine.check(&other), Ok(())),
+                    }
+                    let lenient = Engine::new(config.with_strict(false));
+                    assert_eq!(lenient.decode(&other), Ok(data.clone()));
+                }
+            }
+        }
+    }
+
+    // Separators.
+    let engine = Engine::new(Config::new().with_separator(Some(b':')));
+    assert_eq!(engine.decode(""), Ok(vec![]));
+    assert_eq!(engine.decode("ab"), Ok(vec![0xab]));
+    assert_eq!(engine.decode("ab:"), Err(FromHexError::InvalidStringLength));
+    assert_eq!(
+        engine.decode("abcd"),
+        Err(FromHexError::InvalidStringLength)
+    );
+    assert_eq!(
+        engine.decode("ab-cd"),
+        Err(FromHexError::InvalidHexCharacter { c: '-', index: 2 })
+    );
+    assert_eq!(
+        engine.decode("0xab-cz"),
+        Err(FromHexError::InvalidHexCharacter { c: '-', index: 2 })
+    );
+    assert_eq!(
+        engine.decode("ab:z-:cd"),
+        Err(FromHexError::InvalidHexCharacter { c: 'z', index: 3 })
+    );
+    assert_eq!(
+        engine.decode_to_array::<2>("ab:cd:ef"),
+        Err(FromHexError::InvalidStringLength)
+    );
+
+    // Prefix modes.
+    let required = Engine::new(Config::new().with_decode_prefix(PrefixMode::Required));
+    assert_eq!(required.decode("0Xab"), Ok(vec![0xab]));
+    assert_eq!(
+        required.decode("ab"),
+        Err(FromHexError::InvalidHexCharacter { c: 'a', index: 0 })
+    );
+    assert_eq!(
+        required.decode("00ab"),
+        Err(FromHexError::InvalidHexCharacter { c: '0', index: 1 })
+    );
+    assert_eq!(required.decode(""), Err(FromHexError::InvalidStringLength));
+    assert_eq!(required.decode("0x"), Ok(vec![]));
+    assert_eq!(
+        required.check("0xabz0"),
+        Err(FromHexError::InvalidHexCharacter { c: 'z', index: 4 })
+    );
+    let forbidden = Engine::new(Config::new().with_decode_prefix(PrefixMode::Forbidden));
+    assert_eq!(forbidden.decode("ab"), Ok(vec![0xab]));
+    assert_eq!(
+        forbidden.decode("0xab"),
+        Err(FromHexError::InvalidHexCharacter { c: 'x', index: 1 })
+    );
+    assert_eq!(engine::STANDARD.decode("abc"), Err(FromHexError::OddLength));
+
+    // The standard engines report the same errors as the free functions.
+    for input in ["0xzz", "0Xabzz", "0x0g", "zz", "0xabc"] {
+        assert_eq!(engine::STANDARD.decode(input), const_hex::decode(input));
+        assert_eq!(engine::PREFIXED.decode(input), const_hex::decode(input));
+        assert_eq!(engine::STANDARD.check(input), const_hex::check(input));
+    }
+
+    let mut output = [0; 3];
+    assert_eq!(
+        engine::PREFIXED.encode_to_slice([1], &mut output),
+        Err(FromHexError::InvalidStringLength)
+    );
+}
+
+#[test]
+#[should_panic = "separator must not be a hex digit"]
+fn engine_hex_separator() {
+    let _ = const_hex::engine::Config::new().with_separator(Some(b'a'));
+}
+
+#[test]
+fn encode_into() {
+    use const_hex::{FromHexError, Output};
+
+    struct Limited {
+        buf: [u8; 64],
+        len: usize,
+    }
+
+    impl Output for Limited {
+        fn reserve(&mut self, additional: usize) -> bool {
+            self.buf.len() - self.len >= additional
+        }
+
+        fn write_str(&mut self, s: &str) {
+            self.buf[self.len..self.len + s.len()].copy_from_slice(s.as_bytes());
+            self.len += s.len();
+        }
+    }
+
+    let hex = |bytes: &[u8], upper: bool| {
+        let mut buf = vec![0; bytes.len() * 2];
+        if upper {
+            const_hex::encode_to_slice_upper(bytes, &mut buf).unwrap();
+        } else {
+            const_hex::encode_to_slice(bytes, &mut buf).unwrap();
+        }
+        buf
+    };
+
+    let data = (0..32).map(|i| i * 7).collect::<Vec<u8>>();
+    for len in 0..=32 {
+        let mut out = Limited {
+            buf: [0; 64],
+            len: 0,
+        };
+        const_hex::encode_into(&data[..len], &mut out).unwrap();
+        assert_eq!(out.buf[..out.len], hex(&data[..len], false));
+        let before = out.len;
+        let fits = before + (32 - len) * 2 <= 64;
+        let result = const_hex::encode_upper_into(&data[len..], &mut &mut out);
+        if fits {
+            assert_eq!(result, Ok(()));
+            assert_eq!(out.buf[before..out.len], hex(&data[len..], true));
+        } else {
+            assert_eq!(result, Err(FromHexError::InvalidStringLength));
+            assert_eq!(out.len, before);
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    {
+        let mut s = String::from("0x");
+        const_hex::encode_into(&data, &mut s).unwrap();
+        assert_eq!(s, const_hex::encode_prefixed(&data));
+        let mut v = Vec::new();
+        const_hex::encode_upper_into(&data, &mut v).unwrap();
+        assert_eq!(v, const_hex::encode_upper(&data).into_bytes());
+        let dyn_output: &mut dyn Output = &mut v;
+        const_hex::encode_into([0xab], dyn_output).unwrap();
+        assert!(v.ends_with(b"ab"));
+    }
+
+    #[cfg(feature = "heapless")]
+    {
+        let mut s = heapless::String::<8>::new();
+        const_hex::encode_into(b"kiwi", &mut s).unwrap();
+        assert_eq!(s, "6b697769");
+        assert_eq!(
+            const_hex::encode_into(b"k", &mut s),
+            Err(FromHexError::InvalidStringLength)
+        );
+        let mut v = heapless::Vec::<u8, 8>::new();
+        const_hex::encode_upper_into(b"kiwi", &mut v).unwrap();
+        assert_eq!(v, b"6B697769");
+    }
+
+    #[cfg(feature = "arrayvec")]
+    {
+        let mut s = arrayvec::ArrayString::<8>::new();
+        const_hex::encode_into(b"kiwi", &mut s).unwrap();
+        assert_eq!(&s, "6b697769");
+        assert_eq!(
+            const_hex::encode_into(b"k", &mut s),
+            Err(FromHexError::InvalidStringLength)
+        );
+        let mut v = arrayvec::ArrayVec::<u8, 8>::new();
+        const_hex::encode_upper_into(b"kiwi", &mut v).unwrap();
+        assert_eq!(&v[..], b"6B697769");
+    }
+
+    #[cfg(feature = "bytes")]
+    {
+        let mut b = bytes::BytesMut::new();
+        const_hex::encode_into(b"kiwi", &mut b).unwrap();
+        assert_eq!(&b[..], b"6b697769");
+    }
+}
+
+#[test]
+fn const_str() {
+    use const_hex::{const_concat, const_join, const_str, ConstStr};
+
+    const A: Buffer<2, true> = const_hex::const_encode(&[0xab, 0xcd]);
+    const EMPTY: &str = const_concat!();
+    const ONE: &str = const_join!(","; A.as_str());
+    const PADDED: &str = const_concat!("0x", "0000", const_str::trim_prefix(A.as_str()));
+    const LIST: &str = const_join!(", "; A.as_str(), PADDED, const_str::slice(PADDED, 6, 10));
+    assert_eq!(EMPTY, "");
+    assert_eq!(ONE, "0xabcd");
+    assert_eq!(PADDED, "0x0000abcd");
+    assert_eq!(LIST, "0xabcd, 0x0000abcd, abcd");
+
+    const S: ConstStr<8> = ConstStr::new()
+        .push_hex_upper(&[0xab])
+        .push_repeat("-", 3)
+        .push_hex(&[0xcd])
+        .truncate(6);
+    assert_eq!(S, "AB---c");
+    assert_eq!(S.len(), 6);
+    assert_eq!(S.capacity(), 8);
+    assert_eq!(format!("{S:?} {S:>7}"), r#""AB---c"  AB---c"#);
+    assert!(ConstStr::<0>::new().is_empty());
+    assert_eq!(const_str::slice("αβ", 2, 4), "β");
+    assert_eq!(const_str::trim_prefix("0Xab"), "ab");
+}
+
+#[test]
+#[should_panic = "capacity exceeded"]
+fn const_str_overflow() {
+    let _ = const_hex::ConstStr::<3>::new().push_hex(&[1, 2]);
+}
+
+#[test]
+#[should_panic = "not a char boundary"]
+fn const_str_char_boundary() {
+    let _ = const_hex::const_str::slice("αβ", 1, 4);
+}
+
+#[cfg(feature = "const-mut")]
+mod const_mut {
+    use const_hex::{
+        const_decode_to_slice, const_encode_to_slice, const_encode_to_slice_upper, FromHexError,
+    };
+
+    const INPUT: &[u8] = &[0xde, 0xad, 0xbe, 0xef, 0x01];
+
+    const _: () = {
+        let mut lower = [0; INPUT.len() * 2];
+        assert!(const_encode_to_slice(INPUT, &mut lower).is_ok());
+        let mut upper = [0; INPUT.len() * 2];
+        assert!(const_encode_to_slice_upper(INPUT, &mut upper).is_ok());
+
+        let mut decoded = [0; INPUT.len()];
+        assert!(const_decode_to_slice(&lower, &mut decoded).is_ok());
+        assert!(eq(&decoded, INPUT));
+        decoded = [0; INPUT.len()];
+        assert!(const_decode_to_slice(&upper, &mut decoded).is_ok());
+        assert!(eq(&decoded, INPUT));
+        assert!(const_decode_to_slice(b"0Xdeadbeef01", &mut decoded).is_ok());
+        assert!(eq(&decoded, INPUT));
+        assert!(const_encode_to_slice(&[], &mut []).is_ok());
+        assert!(const_decode_to_slice(b"0x", &mut []).is_ok());
+    };
+
+    const _: () = {
+        let mut output = [0; 4];
+        assert!(matches!(
+            const_encode_to_slice(INPUT, &mut output),
+            Err(FromHexError::InvalidStringLength)
+        ));
+        assert!(matches!(
+            const_decode_to_slice(b"0xabc", &mut output),
+            Err(FromHexError::OddLength)
+        ));
+        assert!(matches!(
+            const_decode_to_slice(b"abcd", &mut output),
+            Err(FromHexError::InvalidStringLength)
+        ));
+        assert!(matches!(
+            const_decode_to_slice(b"0x0102030g", &mut output),
+            Err(FromHexError::InvalidHexCharacter { c: 'g', index: 7 })
+        ));
+    };
+
+    const fn eq(a: &[u8], b: &[u8]) -> bool {
+        if a.len() != b.len() {
+            return false;
+        }
+        let mut i = 0;
+        while i < a.len() {
+            if a[i] != b[i] {
+                return false;
+            }
+            i += 1;
+        }
+        true
+    }
+
+    #[test]
+    fn matches_runtime() {
+        const LOWER: [u8; 10] = {
+            let mut output = [0; 10];
+            assert!(const_encode_to_slice(INPUT, &mut output).is_ok());
+            output
+        };
+        assert_eq!(&LOWER, b"deadbeef01");
+        let mut runtime = [0; 10];
+        const_hex::encode_to_slice_upper(INPUT, &mut runtime).unwrap();
+        let mut upper = [0; 10];
+        const_encode_to_slice_upper(INPUT, &mut upper).unwrap();
+        assert_eq!(upper, runtime);
+    }
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn iter() {
+    use const_hex::{decode_iter, encode_iter, encode_iter_upper, iter, FromHexError};
+
+    let all = Buffer::<256>::new().const_format(&ALL);
+    let all_upper = Buffer::<256>::new().const_format_upper(&ALL);
+    for len in [0, 1, 31, 32, 33, 64, 65, 256] {
+        let expected = &all.as_bytes()[..len * 2];
+        let input = &ALL[..len];
+        assert!(encode_iter(input).eq(expected.iter().copied()));
+        assert!(encode_iter(input).rev().eq(expected.iter().rev().copied()));
+        assert!(encode_iter_upper(input).eq(all_upper.as_bytes()[..len * 2].iter().copied()));
+        assert!(iter::encode_pairs(input).eq(expected.chunks(2).map(|c| [c[0], c[1]])));
+        assert_eq!(encode_iter(input).len(), len * 2);
+
+        // Alternate between both ends.
+        let mut it = encode_iter(input);
+        let (mut front, mut back) = (0, len * 2);
+        for i in 0.. {
+            assert_eq!(it.len(), back - front);
+            let next = if i % 3 == 0 {
+                it.next_back().inspect(|_| back -= 1).map(|b| (b, back))
+            } else {
+                it.next().map(|b| (b, front)).inspect(|_| front += 1)
+            };
+            match next {
+                Some((b, index)) => assert_eq!(b, expected[index]),
+                None => break,
+            }
+        }
+        assert_eq!(front, back);
+
+        let decoded: Result<Vec<u8>, _> = decode_iter(expected.iter().copied()).collect();
+        assert_eq!(decoded.unwrap(), input);
+        let prefixed = b"0X".iter().chain(all_upper.as_bytes()[..len * 2].iter());
+        let decoded: Result<Vec<u8>, _> = decode_iter(prefixed.copied()).collect();
+        assert_eq!(decoded.unwrap(), input);
+    }
+
+    let decode = |s: &str| decode_iter(s.bytes()).collect::<Vec<_>>();
+    assert_eq!(decode(""), []);
+    assert_eq!(decode("0x"), []);
+    assert_eq!(decode("0"), [Err(FromHexError::OddLength)]);
+    assert_eq!(decode("0x0"), [Err(FromHexError::OddLength)]);
+    assert_eq!(
+        decode("0x0x"),
+        [Err(FromHexError::InvalidHexCharacter { c: 'x', index: 1 })]
+    );
+    assert_eq!(
+        decode("abg1"),
+        [
+            Ok(0xab),
+            Err(FromHexError::InvalidHexCharacter { c: 'g', index: 2 })
+        ]
+    );
+    assert_eq!(
+        decode("ab1g"),
+        [
+            Ok(0xab),
+            Err(FromHexError::InvalidHexCharacter { c: 'g', index: 3 })
+        ]
+    );
+    for s in ["0x0x", "abg1", "ab1g", "0xabzz00"] {
+        assert_eq!(decode(s).pop().unwrap(), const_hex::decode(s).map(|_| 0));
+    }
+
+    #[allow(deprecated)]
+    {
+        use const_hex::ToHex;
+        assert_eq!(ALL.encode_hex::<String>(), all.as_str());
+        assert_eq!(ALL.encode_hex_upper::<String>(), all_upper.as_str());
+    }
+}
+
+#[test]
+#[cfg(all(
+    feature = "arrayvec",
+    feature = "bytes",
+    feature = "heapless",
+    feature = "smallvec"
+))]
+fn containers() {
+    use const_hex::{FromHex, FromHexError};
+
+    fn check<T: FromHex<Error = FromHexError> + AsRef<[u8]> + core::fmt::Debug>() {
+        assert_eq!(T::from_hex("").unwrap().as_ref(), b"");
+        assert_eq!(T::from_hex("0x6b697769").unwrap().as_ref(), b"kiwi");
+        assert_eq!(T::from_hex("6B697769").unwrap().as_ref(), b"kiwi");
+        assert_eq!(T::from_hex("6b69776").unwrap_err(), FromHexError::OddLength);
+        assert_eq!(
+            T::from_hex("0x6b69776g").unwrap_err(),
+            FromHexError::InvalidHexCharacter { c: 'g', index: 7 }
+        );
+    }
+    check::<heapless::Vec<u8, 4>>();
+    check::<arrayvec::ArrayVec<u8, 4>>();
+    check::<smallvec::SmallVec<[u8; 2]>>();
+    check::<smallvec::SmallVec<[u8; 8]>>();
+    check::<bytes::Bytes>();
+    check::<bytes::BytesMut>();
+
+    let long = "0x6b6977697300";
+    assert_eq!(
+        heapless::Vec::<u8, 4>::from_hex(long),
+        Err(FromHexError::InvalidStringLength)
+    );
+    assert_eq!(
+        arrayvec::ArrayVec::<u8, 4>::from_hex(long),
+        Err(FromHexError::InvalidStringLength)
+    );
+    assert!(smallvec::SmallVec::<[u8; 4]>::from_hex(long)
+        .unwrap()
+        .spilled());
+
+    let mut small = smallvec::SmallVec::<[u8; 4]>::new();
+    const_hex::encode_into(b"kiwi", &mut small).unwrap();
+    assert_eq!(&small[..], b"6b697769");
+}
+
+#[cfg(feature = "tokio")]
+mod tokio_io {
+    use const_hex::async_io::{HexDecoder, HexEncoder};
+    use const_hex::FromHexError;
+    use tokio::io::{duplex, AsyncReadExt, AsyncWriteExt};
+
+    fn data() -> Vec<u8> {
+        (0..20_000u32).map(|i| (i * 7) as u8).collect()
+    }
+
+    fn hex_error(e: std::io::Error) -> FromHexError {
+        assert_eq!(e.kind(), std::io::ErrorKind::InvalidData);
+        *e.into_inner().unwrap().downcast::<FromHexError>().unwrap()
+    }
+
+    #[tokio::test]
+    async fn encode() {
+        let data = data();
+        for upper in [false, true] {
+            let (client, mut server) = duplex(7);
+            let write = async {
+                let mut encoder = if upper {
+                    HexEncoder::new_upper(client)
+                } else {
+                    HexEncoder::new(client)
+                };
+                for chunk in data.chunks(333) {
+                    encoder.write_all(chunk).await.unwrap();
+                }
+                encoder.shutdown().await.unwrap();
+            };
+            let read = async {
+                let mut hex = String::new();
+                server.read_to_string(&mut hex).await.unwrap();
+                hex
+            };
+            let ((), hex) = tokio::join!(write, read);
+            let expected = if upper {
+                const_hex::encode_upper(&data)
+            } else {
+                const_hex::encode(&data)
+            };
+            assert_eq!(hex, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn decode() {
+        let data = data();
+        let hex = format!("0X{}", const_hex::encode_upper(&data));
+        let (mut client, server) = duplex(7);
+        let write = async {
+            // Odd-sized chunks split the pairs across reads.
+            for chunk in hex.as_bytes().chunks(101) {
+                client.write_all(chunk).await.unwrap();
+            }
+            client.shutdown().await.unwrap();
+        };
+        let read = async {
+            let mut decoder = HexDecoder::new(server);
+            let mut bytes = Vec::new();
+            decoder.read_to_end(&mut bytes).await.unwrap();
+            assert_eq!(decoder.offset(), hex.len());
+            bytes
+        };
+        let ((), bytes) = tokio::join!(write, read);
+        assert_eq!(bytes, data);
+    }
+
+    #[tokio::test]
+    async fn decode_errors() {
+        let mut hex = const_hex::encode(data());
+        hex.replace_range(30_001..30_002, "z");
+        let mut decoder = HexDecoder::new(hex.as_bytes());
+        let mut bytes = Vec::new();
+        let e = decoder.read_to_end(&mut bytes).await.unwrap_err();
+        assert_eq!(
+            hex_error(e),
+            FromHexError::InvalidHexCharacter {
+                c: 'z',
+                index: 30_001
+            }
+        );
+        assert_eq!(bytes, data()[..15_000]);
+        let e = decoder.read_u8().await.unwrap_err();
+        assert_eq!(
+            hex_error(e),
+            FromHexError::InvalidHexCharacter {
+                c: 'z',
+                index: 30_001
+            }
+        );
+
+        let mut decoder = HexDecoder::new(&b"0xabc"[..]);
+        let mut bytes = Vec::new();
+        let e = decoder.read_to_end(&mut bytes).await.unwrap_err();
+        assert_eq!(hex_error(e), FromHexError::OddLength);
+        assert_eq!(bytes, [0xab]);
+
+        let mut decoder = HexDecoder::new(&b"0x0x"[..]);
+        let e = decoder.read_u8().await.unwrap_err();
+        assert_eq!(
+            hex_error(e),
+            FromHexError::InvalidHexCharacter { c: 'x', index: 3 }
+        );
+
+        for empty in ["", "0x"] {
+            let mut decoder = HexDecoder::new(empty.as_bytes());
+            let mut bytes = Vec::new();
+            decoder.read_to_end(&mut bytes).await.unwrap();
+            assert!(bytes.is_empty());
+        }
+    }
+}
+
+#[cfg(feature = "futures-io")]
+mod futures_io {
+    use const_hex::async_io::{HexDecoder, HexEncoder};
+    use futures::executor::block_on;
+    use futures::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    /// Reads one byte at a time, returning `Pending` before each one.
+    struct Trickle<'a> {
+        data: &'a [u8],
+        /// Whether the last poll returned `Pending`.
+        pending: bool,
+    }
+
+    impl AsyncRead for Trickle<'_> {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<std::io::Result<usize>> {
+            self.pending = !self.pending;
+            if self.pending {
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            let Some((&first, rest)) = self.data.split_first() else {
+                return Poll::Ready(Ok(0));
+            };
+            buf[0] = first;
+            self.data = rest;
+            Poll::Ready(Ok(1))
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        block_on(async {
+            let data: Vec<u8> = (0..=255).collect();
+            let mut encoder = HexEncoder::new(Vec::new());
+            encoder.write_all(&data).await.unwrap();
+            encoder.close().await.unwrap();
+            let hex = encoder.into_inner();
+            assert_eq!(hex, const_hex::encode(&data).as_bytes());
+
+            let mut decoder = HexDecoder::new(Trickle {
+                data: &hex,
+                pending: false,
+            });
+            let mut bytes = Vec::new();
+            decoder.read_to_end(&mut bytes).await.unwrap();
+            assert_eq!(bytes, data);
+        });
+    }
+}
+
+#[cfg(feature = "cli")]
+mod cli {
+    use std::io::Write;
+    use std::process::{Command, Output, Stdio};
+    use std::thread;
+
+    fn run(args: &[&str], input: &[u8]) -> Output {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_const-hex"))
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut stdin = child.stdin.take().unwrap();
+        // Write from another thread so that a full output pipe can't block the child.
+        let input = input.to_vec();
+        let writer = thread::spawn(move || {
+            // The child may exit early, for example on invalid input.
+            let _ = stdin.write_all(&input);
+        });
+        let output = child.wait_with_output().unwrap();
+        writer.join().unwrap();
+        output
+    }
+
+    #[track_caller]
+    fn run_ok(args: &[&str], input: &[u8]) -> Vec<u8> {
+        let output = run(args, input);
+        assert!(output.status.success(), "{output:?}");
+        output.stdout
+    }
+
+    #[track_caller]
+    fn run_err(args: &[&str], input: &[u8]) -> (i32, String) {
+        let output = run(args, input);
+        assert!(output.stdout.is_empty(), "{output:?}");
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        (output.status.code().unwrap(), stderr)
+    }
+
+    #[test]
+    fn encode() {
+        assert_eq!(run_ok(&["encode"], b"kiwi"), b"6b697769\n");
+        assert_eq!(run_ok(&["encode"], b""), b"");
+        assert_eq!(
+            run_ok(
+                &["encode", "--upper", "--prefix", "-s", ":"],
+                b"\x01\xab\xff"
+            ),
+            b"0x01:AB:FF\n"
+        );
+        assert_eq!(
+            run_ok(&["encode", "--wrap=2", "--separator= "], b"abcde"),
+            b"61 62\n63 64\n65\n"
+        );
+
+        let data: Vec<u8> = (0..200_000u32).map(|i| (i * 7) as u8).collect();
+        let hex = run_ok(&["encode"], &data);
+        assert_eq!(
+            hex.strip_suffix(b"\n").unwrap(),
+            const_hex::encode(&data).as_bytes()
+        );
+    }
+
+    #[test]
+    fn decode() {
+        assert_eq!(run_ok(&["decode"], b"6b697769\n"), b"kiwi");
+        assert_eq!(run_ok(&["decode"], b"0x6B69\r\n7769"), b"kiwi");
+        assert_eq!(run_ok(&["decode", "-s", ":"], b"6b:69:77:69"), b"kiwi");
+        assert_eq!(run_ok(&["decode", "-i"], b"<6b 69-77 69>"), b"kiwi");
+        assert_eq!(run_ok(&["check"], b"6b697769"), b"");
+
+        let data: Vec<u8> = (0..200_000u32).map(|i| (i * 7) as u8).collect();
+        let hex = run_ok(&["encode", "-w", "33", "-s", " "], &data);
+        assert_eq!(run_ok(&["decode", "-s", " "], &hex), data);
+    }
+
+    #[test]
+    fn decode_errors() {
+        let (code, stderr) = run_err(&["decode"], b"6b69 7769");
+        assert_eq!(code, 1);
+        assert_eq!(stderr, "const-hex: invalid character ' ' at position 4\n");
+
+        let (_, stderr) = run_err(&["check"], b"6b69777");
+        assert_eq!(stderr, "const-hex: odd number of digits\n");
+
+        // Positions are offsets in the whole input, across line breaks and reads.
+        let mut input = b"0x".to_vec();
+        input.extend(b"a\n".repeat(50_000));
+        input.extend(b"g");
+        let (_, stderr) = run_err(&["check"], &input);
+        assert_eq!(
+            stderr,
+            "const-hex: invalid character 'g' at position 100002\n"
+        );
+    }
+
+    #[test]
+    fn dump() {
+        assert_eq!(
+            run_ok(&["dump"], b"Hello, world!\n0123"),
+            b"00000000: 48 65 6c 6c 6f 2c 20 77 6f 72 6c 64 21 0a 30 31  Hello, world!.01\n\
+              00000010: 32 33                                            23\n"
+        );
+        assert_eq!(
+            run_ok(
+                &["dump", "-u", "-p", "-w", "3", "-s", "-"],
+                b"\xfe\xff\x00\x7f"
+            ),
+            b"0x00000000: FE-FF-00  ...\n0x00000003: 7F        .\n"
+        );
+        // A wrap width of 0 means no limit.
+        let input = "a".repeat(40);
+        let expected = format!("00000000: {}  {input}\n", ["61"; 40].join(" "));
+        assert_eq!(
+            run_ok(&["dump", "-w", "0"], input.as_bytes()),
+            expected.as_bytes()
+        );
+        assert_eq!(run_ok(&["dump", "-w", "0"], b""), b"");
+    }
+
+    #[test]
+    fn usage() {
+        assert!(run_ok(&["--help"], b"").starts_with(b"Usage: const-hex"));
+        for args in [
+            &[][..],
+            &["frob"],
+            &["encode", "--frob"],
+            &["encode", "-s", "a"],
+            &["encode", "-w"],
+        ] {
+            let (code, stderr) = run_err(args, b"");
+            assert_eq!(code, 2, "{args:?}");
+            assert!(stderr.starts_with("const-hex: "), "{stderr}");
+        }
+    }
+}
+
+#[cfg(feature = "ffi")]
+mod ffi {
+    use const_hex::ffi::*;
+    use std::ffi::CStr;
+    use std::ptr;
+
+    #[test]
+    fn encode() {
+        let mut output = [0u8; 6];
+        let status = unsafe {
+            const_hex_encode(
+                [1, 0xab, 0xff].as_ptr(),
+                3,
+                output.as_mut_ptr().cast(),
+                6,
+                true,
+            )
+        };
+        assert_eq!(status, Status::Ok);
+        assert_eq!(&output, b"01ABFF");
+
+        let status =
+            unsafe { const_hex_encode([1].as_ptr(), 1, output.as_mut_ptr().cast(), 6, false) };
+        assert_eq!(status, Status::InvalidLength);
+        assert_eq!(
+            unsafe { const_hex_encode(ptr::null(), 0, ptr::null_mut(), 0, false) },
+            Status::Ok
+        );
+    }
+
+    #[test]
+    fn decode() {
+        let decode = |input: &str, output: &mut [u8], index: &mut usize| unsafe {
+            const_hex_decode(
+                input.as_ptr().cast(),
+                input.len(),
+                output.as_mut_ptr(),
+                output.len(),
+                index,
+            )
+        };
+        let mut output = [0u8; 4];
+        let mut index = usize::MAX;
+        assert_eq!(decode("0x6b697769", &mut output, &mut index), Status::Ok);
+        assert_eq!(&output, b"kiwi");
+        assert_eq!(index, usize::MAX);
+        assert_eq!(
+            decode("6b69776g", &mut output, &mut index),
+            Status::InvalidCharacter
+        );
+        assert_eq!(index, 7);
+        assert_eq!(
+            decode("0x6b69776g", &mut output, &mut index),
+            Status::InvalidCharacter
+        );
+        assert_eq!(index, 9);
+        assert_eq!(
+            decode("6b6977", &mut output, &mut index),
+            Status::InvalidLength
+        );
+        assert_eq!(
+            decode("6b69776", &mut output, &mut index),
+            Status::OddLength
+        );
+
+        let check = |input: &str, index: *mut usize| unsafe {
+            const_hex_check(input.as_ptr().cast(), input.len(), index)
+        };
+        assert_eq!(check("0xabcd", ptr::null_mut()), Status::Ok);
+        assert_eq!(check("ab-d", ptr::null_mut()), Status::InvalidCharacter);
+        assert_eq!(check("ab-d", &mut index), Status::InvalidCharacter);
+        assert_eq!(index, 2);
+    }
+
+    #[test]
+    fn decode_error() {
+        let message = |status| unsafe { CStr::from_ptr(const_hex_decode_error(status)) };
+        let errors = [
+            (
+                Status::InvalidCharacter,
+                const_hex::FromHexError::InvalidHexCharacter { c: 'g', index: 0 },
+            ),
+            (Status::OddLength, const_hex::FromHexError::OddLength),
+            (
+                Status::InvalidLength,
+                const_hex::FromHexError::InvalidStringLength,
+            ),
+        ];
+        for (status, error) in errors {
+            let message = message(status as i32).to_str().unwrap();
+            assert!(error.to_string().starts_with(message), "{message}");
+        }
+        assert_eq!(message(Status::Ok as i32).to_str(), Ok("success"));
+        assert_eq!(message(42).to_str(), Ok("unknown status"));
+    }
+}